@@ -1,4 +1,8 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
 pub mod point_u256;
-pub use point_u256::U256ECPoint;
+pub use point_u256::{ECPoint, ProjectivePoint, U256ECPoint};
 
 pub mod secp256k1;
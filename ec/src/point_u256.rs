@@ -1,35 +1,150 @@
+use core::str::FromStr;
 use ff::p_u256::U256FieldElement;
-use primitive_types::U256;
+use ff::Field;
+use subtle::{Choice, ConditionallySelectable};
 
-#[derive(Debug, Clone)]
-pub struct U256ECPoint {
-    pub x: U256FieldElement,
-    pub y: U256FieldElement,
+use crate::secp256k1::Secp256k1Field;
+
+/// Affine point with coordinates in the field type `F`, (0, 0) standing for
+/// the identity
+#[derive(Debug)]
+pub struct ECPoint<F: Field> {
+    pub x: F,
+    pub y: F,
+}
+
+/// Point over a `U256FieldElement` base field
+pub type U256ECPoint<P> = ECPoint<U256FieldElement<P>>;
+
+impl<F: Field> Clone for ECPoint<F> {
+    fn clone(&self) -> Self {
+        Self {
+            x: self.x.clone(),
+            y: self.y.clone(),
+        }
+    }
 }
 
-impl Eq for U256ECPoint {}
-impl PartialEq for U256ECPoint {
+impl<F: Field> Eq for ECPoint<F> {}
+impl<F: Field> PartialEq for ECPoint<F> {
     fn eq(&self, other: &Self) -> bool {
         self.x == other.x && self.y == other.y
     }
 }
 
-impl U256ECPoint {
-    pub fn from_str(x: &str, y: &str, prime: &str) -> Self {
+impl<F: Field + FromStr> ECPoint<F>
+where
+    F::Err: core::fmt::Debug,
+{
+    pub fn from_str(x: &str, y: &str) -> Self {
         Self {
-            x: U256FieldElement::from_str(x, prime).unwrap(),
-            y: U256FieldElement::from_str(y, prime).unwrap(),
+            x: F::from_str(x).unwrap(),
+            y: F::from_str(y).unwrap(),
         }
     }
+}
 
-    pub fn zero_zero(p: U256) -> Self {
+impl<F: Field> ECPoint<F> {
+    pub fn zero_zero() -> Self {
         Self {
-            x: U256FieldElement::new(U256::zero(), p).unwrap(),
-            y: U256FieldElement::new(U256::zero(), p).unwrap(),
+            x: F::zero(),
+            y: F::zero(),
         }
     }
 
     pub fn is_identity(&self) -> bool {
-        self.x.num.is_zero() && self.y.num.is_zero()
+        self.x.is_zero() && self.y.is_zero()
+    }
+}
+
+/// Projective point (X : Y : Z) standing for the affine (X / Z, Y / Z), the
+/// identity being (0 : 1 : 0)
+#[derive(Debug)]
+pub struct ProjectivePoint<F: Field> {
+    pub x: F,
+    pub y: F,
+    pub z: F,
+}
+
+impl<F: Field> Clone for ProjectivePoint<F> {
+    fn clone(&self) -> Self {
+        Self {
+            x: self.x.clone(),
+            y: self.y.clone(),
+            z: self.z.clone(),
+        }
+    }
+}
+
+impl<F: Field + Copy> Copy for ProjectivePoint<F> {}
+
+/// Coordinate by coordinate, for ladders over secret scalars
+impl<F: Field + ConditionallySelectable> ConditionallySelectable for ProjectivePoint<F> {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        Self {
+            x: F::conditional_select(&a.x, &b.x, choice),
+            y: F::conditional_select(&a.y, &b.y, choice),
+            z: F::conditional_select(&a.z, &b.z, choice),
+        }
+    }
+}
+
+impl<F: Field> Eq for ProjectivePoint<F> {}
+impl<F: Field> PartialEq for ProjectivePoint<F> {
+    /// Equal up to scaling of the coordinates
+    fn eq(&self, other: &Self) -> bool {
+        self.x.clone() * &other.z == other.x.clone() * &self.z
+            && self.y.clone() * &other.z == other.y.clone() * &self.z
+    }
+}
+
+impl<F: Field> ProjectivePoint<F> {
+    pub fn identity() -> Self {
+        Self {
+            x: F::zero(),
+            y: F::one(),
+            z: F::zero(),
+        }
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.z.is_zero()
+    }
+
+    /// (0, 0) is taken to the identity
+    pub fn from_affine(p: &ECPoint<F>) -> Self {
+        if p.is_identity() {
+            return Self::identity();
+        }
+        Self {
+            x: p.x.clone(),
+            y: p.y.clone(),
+            z: F::one(),
+        }
+    }
+
+    /// Costs one inversion, the identity comes back as (0, 0)
+    pub fn to_affine(&self) -> ECPoint<F> {
+        match self.z.inv() {
+            Some(z_inv) => ECPoint {
+                x: self.x.clone() * &z_inv,
+                y: self.y.clone() * &z_inv,
+            },
+            None => ECPoint::zero_zero(),
+        }
+    }
+}
+
+impl<F: Secp256k1Field> ProjectivePoint<F> {
+    /// `to_affine` with the constant-time inversion, for points derived from
+    /// secrets. Only whether the point is the identity shows in the timing.
+    pub fn to_affine_ct(&self) -> ECPoint<F> {
+        match self.z.inv_ct() {
+            Some(z_inv) => ECPoint {
+                x: self.x.clone() * &z_inv,
+                y: self.y.clone() * &z_inv,
+            },
+            None => ECPoint::zero_zero(),
+        }
     }
 }
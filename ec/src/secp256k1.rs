@@ -1,11 +1,82 @@
-use crate::U256ECPoint;
-use ff::p_u256::U256FieldElement;
+use alloc::{vec, vec::Vec};
+use core::marker::PhantomData;
+use core::str::FromStr;
+
+use crate::{ECPoint, ProjectivePoint};
+use ff::params::u256_from_hex;
+use ff::{Field, Fp, PrimeFieldParams, PseudoMersenneFieldElement, PseudoMersenneParams, Secret};
 use primitive_types::U256;
-use std::str::FromStr;
+use subtle::{Choice, ConditionallySelectable};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// Base field of secp256k1, p = 2^256 - 2^32 - 977
+#[derive(Debug)]
+pub struct Secp256k1Base;
+impl PrimeFieldParams for Secp256k1Base {
+    const MODULUS: U256 =
+        u256_from_hex("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F");
+}
+impl PseudoMersenneParams for Secp256k1Base {}
+
+/// Scalar field of secp256k1, i.e. its group order n
+#[derive(Debug)]
+pub struct Secp256k1Scalar;
+impl PrimeFieldParams for Secp256k1Scalar {
+    const MODULUS: U256 =
+        u256_from_hex("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141");
+}
+
+/// Private key k in [1, n), wiped on drop and redacted in `Debug`. The
+/// public key is k * G.
+#[derive(Debug)]
+pub struct SecretKey(Secret<Fp<Secp256k1Scalar>>);
+
+impl SecretKey {
+    /// `None` for k = 0; every other scalar is below n and a valid key
+    pub fn new(k: Fp<Secp256k1Scalar>) -> Option<Self> {
+        let key = Self(Secret::new(k));
+        (!key.expose_secret().is_zero()).then_some(key)
+    }
+
+    pub fn expose_secret(&self) -> &Fp<Secp256k1Scalar> {
+        self.0.expose_secret()
+    }
+
+    /// An explicit copy, wiped on drop like the original
+    pub fn clone_secret(&self) -> Self {
+        Self(self.0.clone_secret())
+    }
+}
+
+impl ZeroizeOnDrop for SecretKey {}
+
+/// Field element types that implement the secp256k1 base field
+pub trait Secp256k1Field: Field + FromStr<Err = ff::Error> {
+    /// Inverse in constant time, for values that depend on secrets
+    fn inv_ct(&self) -> Option<Self>;
+}
+impl Secp256k1Field for Fp<Secp256k1Base> {
+    fn inv_ct(&self) -> Option<Self> {
+        Fp::inv_ct(self)
+    }
+}
+impl Secp256k1Field for PseudoMersenneFieldElement<Secp256k1Base> {
+    fn inv_ct(&self) -> Option<Self> {
+        PseudoMersenneFieldElement::inv_ct(self)
+    }
+}
+
+/// secp256k1 with its base field arithmetic done by `F`. Both backends give
+/// the same points, pick one with the aliases below.
+pub struct Secp256k1Curve<F: Secp256k1Field>(PhantomData<F>);
+
+/// Over the generic Montgomery field
+pub type Secp256k1 = Secp256k1Curve<Fp<Secp256k1Base>>;
+/// Over the field specialised for p = 2^256 - c
+pub type Secp256k1Fast = Secp256k1Curve<PseudoMersenneFieldElement<Secp256k1Base>>;
 
-pub struct Secp256k1;
 // Constants
-impl Secp256k1 {
+impl<F: Secp256k1Field> Secp256k1Curve<F> {
     pub fn p_str() -> &'static str {
         "0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F"
     }
@@ -16,13 +87,13 @@ impl Secp256k1 {
         "0x483ADA7726A3C4655DA4FBFC0E1108A8FD17B448A68554199C47D08FFB10D4B8"
     }
     pub fn p() -> U256 {
-        U256::from_str(Self::p_str()).unwrap()
+        Secp256k1Base::MODULUS
     }
-    pub fn g() -> U256ECPoint {
-        U256ECPoint::from_str(Self::gx_str(), Self::gy_str(), Self::p_str())
+    pub fn g() -> ECPoint<F> {
+        ECPoint::from_str(Self::gx_str(), Self::gy_str())
     }
-    pub fn identity() -> U256ECPoint {
-        U256ECPoint::zero_zero(Self::p())
+    pub fn identity() -> ECPoint<F> {
+        ECPoint::zero_zero()
     }
 }
 
@@ -32,8 +103,8 @@ impl Secp256k1 {
     chord and tangent rules are different for different curves and
     different representations
 */
-impl Secp256k1 {
-    pub fn add(p: &U256ECPoint, q: &U256ECPoint) -> U256ECPoint {
+impl<F: Secp256k1Field> Secp256k1Curve<F> {
+    pub fn add(p: &ECPoint<F>, q: &ECPoint<F>) -> ECPoint<F> {
         if p.x == q.x {
             if p.y == q.y {
                 return Self::double(p);
@@ -52,9 +123,9 @@ impl Secp256k1 {
         Self::add_by_slope(&slope, p, q)
     }
 
-    pub fn double(p: &U256ECPoint) -> U256ECPoint {
+    pub fn double(p: &ECPoint<F>) -> ECPoint<F> {
         // Tangent doesn't intersect the curve, or is identity point
-        if p.y.num.is_zero() {
+        if p.y.is_zero() {
             return Self::identity();
         }
 
@@ -63,69 +134,167 @@ impl Secp256k1 {
         Self::add_by_slope(&slope, p, p)
     }
 
-    /// Double & add algorithm
-    pub fn exp(p: &U256ECPoint, exp: U256) -> U256ECPoint {
-        if p.x.prime != Self::p() {
-            panic!("Does not belong to this curve");
-        }
-
-        if exp.is_zero() {
-            return p.clone();
-        }
-
+    /// Double & add algorithm. 0 * p is the identity.
+    pub fn exp(p: &ECPoint<F>, exp: U256) -> ECPoint<F> {
         let mut exp = exp;
         let mut base = p.clone();
         let mut res = Self::identity();
 
         while exp != U256::zero() {
             if exp & U256::one() == U256::one() {
-                res = Secp256k1::add(&res, &base);
+                res = Self::add(&res, &base);
             }
-            base = Secp256k1::double(&base);
+            base = Self::double(&base);
             exp >>= 1;
         }
 
         res
     }
 
+    /// k * G for the private key k, by a Montgomery ladder over all 256
+    /// bits of k with the complete projective addition: the same operations
+    /// run for every key, and the copy of k the bits are read from is wiped
+    pub fn public_key(secret: &SecretKey) -> ECPoint<F>
+    where
+        F: ConditionallySelectable,
+    {
+        let mut k = secret.expose_secret().num();
+
+        // r1 = r0 + G throughout
+        let mut r0 = ProjectivePoint::identity();
+        let mut r1 = ProjectivePoint::from_affine(&Self::g());
+        for i in (0..256).rev() {
+            let bit = Choice::from(((k.0[i / 64] >> (i % 64)) & 1) as u8);
+            ProjectivePoint::conditional_swap(&mut r0, &mut r1, bit);
+            r1 = Self::add_projective(&r0, &r1);
+            r0 = Self::add_projective(&r0, &r0);
+            ProjectivePoint::conditional_swap(&mut r0, &mut r1, bit);
+        }
+        k.0.zeroize();
+
+        r0.to_affine_ct()
+    }
+
     /// dy / dx
-    fn calc_slope_chord(p: &U256ECPoint, q: &U256ECPoint) -> U256FieldElement {
-        let dx = &p.x - &q.x;
-        let dy = &p.y - &q.y;
+    fn calc_slope_chord(p: &ECPoint<F>, q: &ECPoint<F>) -> F {
+        let dx = p.x.clone() - &q.x;
+        let dy = p.y.clone() - &q.y;
         dy / dx
     }
 
     /// s = ( 3 * x^2 + a) / 2 * y
     /// a is 0 in secp256k1, so it's just 3 * x^2  / 2 * y
-    fn calc_slope_tang(p: &U256ECPoint) -> U256FieldElement {
-        let numerator = p.x.sq() * &U256FieldElement::from_u64_and_u256_prime(3, p.x.prime).unwrap();
-        let denominator = &(&p.y * &U256FieldElement::from_u64_and_u256_prime(2, p.x.prime).unwrap());
+    fn calc_slope_tang(p: &ECPoint<F>) -> F {
+        let x_sq = p.x.square();
+        let numerator = x_sq.double() + x_sq;
+        let denominator = p.y.double();
         numerator / denominator
     }
 
-    fn add_by_slope(slope: &U256FieldElement, p: &U256ECPoint, q: &U256ECPoint) -> U256ECPoint {
-        let x3 = Self::calc_x_of_addition(&slope, &p.x, &q.x);
-        let y3 = Self::calc_y_of_addition(&slope, &x3, &p.x, &p.y);
-        U256ECPoint { x: x3, y: y3 }
+    fn add_by_slope(slope: &F, p: &ECPoint<F>, q: &ECPoint<F>) -> ECPoint<F> {
+        let x3 = Self::calc_x_of_addition(slope, &p.x, &q.x);
+        let y3 = Self::calc_y_of_addition(slope, &x3, &p.x, &p.y);
+        ECPoint { x: x3, y: y3 }
     }
 
     /// 𝑥𝑟=𝜆2−𝑥𝑝−𝑥𝑞
-    fn calc_x_of_addition(
-        slope: &U256FieldElement,
-        x1: &U256FieldElement,
-        x2: &U256FieldElement,
-    ) -> U256FieldElement {
-        slope.sq() - x1 - x2
+    fn calc_x_of_addition(slope: &F, x1: &F, x2: &F) -> F {
+        slope.square() - x1 - x2
     }
 
     /// 𝑦𝑟=𝜆(𝑥𝑝−𝑥𝑟)−𝑦𝑝
-    fn calc_y_of_addition(
-        slope: &U256FieldElement,
-        x3: &U256FieldElement,
-        x1: &U256FieldElement,
-        y1: &U256FieldElement,
-    ) -> U256FieldElement {
-        (x1 - x3) * slope - y1
+    fn calc_y_of_addition(slope: &F, x3: &F, x1: &F, y1: &F) -> F {
+        (x1.clone() - x3) * slope - y1
+    }
+}
+
+/*
+    Projective arithmetic. The complete formulas have no exceptional cases,
+    so MSM can pile points into buckets without checking for doubling or
+    the identity, and no inversion is needed until the very end.
+*/
+impl<F: Secp256k1Field> Secp256k1Curve<F> {
+    /// Complete addition for a = 0 (Renes, Costello and Batina, 2015,
+    /// algorithm 7), each output coordinate one unreduced sum of two products
+    pub fn add_projective(p: &ProjectivePoint<F>, q: &ProjectivePoint<F>) -> ProjectivePoint<F> {
+        let b3 = Self::b3();
+
+        // X1 X2, Y1 Y2 and Z1 Z2
+        let xx = p.x.clone() * &q.x;
+        let yy = p.y.clone() * &q.y;
+        let zz = p.z.clone() * &q.z;
+
+        // X1 Y2 + X2 Y1, Y1 Z2 + Y2 Z1 and X1 Z2 + X2 Z1, one product each
+        let xy = (p.x.clone() + &p.y) * (q.x.clone() + &q.y) - &xx - &yy;
+        let yz = (p.y.clone() + &p.z) * (q.y.clone() + &q.z) - &yy - &zz;
+        let xz = (p.x.clone() + &p.z) * (q.x.clone() + &q.z) - &xx - &zz;
+
+        let zz = zz * &b3;
+        let minus = yy.clone() - &zz;
+        let plus = yy + zz;
+        let xx3 = xx.double() + xx;
+
+        let yz_b3 = yz.clone() * &b3;
+        let xx3_b3 = xx3.clone() * &b3;
+        let x = F::sum_of_products(&[xy.clone(), -yz_b3], &[minus.clone(), xz.clone()]);
+        let y = F::sum_of_products(&[plus.clone(), xx3_b3], &[minus, xz]);
+        let z = F::sum_of_products(&[yz, xx3], &[plus, xy]);
+
+        ProjectivePoint { x, y, z }
+    }
+
+    /// sum of scalars[i] * points[i] by Pippenger's bucket method. Panics if
+    /// the slices have different lengths.
+    pub fn msm(points: &[ECPoint<F>], scalars: &[U256]) -> ECPoint<F> {
+        assert_eq!(points.len(), scalars.len(), "one scalar per point");
+
+        let points: Vec<ProjectivePoint<F>> =
+            points.iter().map(ProjectivePoint::from_affine).collect();
+        let c = Self::msm_window(points.len());
+
+        let mut acc = ProjectivePoint::identity();
+        for w in (0..256usize.div_ceil(c)).rev() {
+            for _ in 0..c {
+                acc = Self::add_projective(&acc, &acc);
+            }
+
+            // bucket d - 1 collects the points whose digit in this window is d
+            let mut buckets = vec![ProjectivePoint::identity(); (1 << c) - 1];
+            for (p, s) in points.iter().zip(scalars) {
+                let digit = ((s >> (w * c)).low_u64() & ((1 << c) - 1)) as usize;
+                if digit != 0 {
+                    buckets[digit - 1] = Self::add_projective(&buckets[digit - 1], p);
+                }
+            }
+
+            // sum of d * bucket[d - 1] through running sums from the top
+            let mut running = ProjectivePoint::identity();
+            let mut window = ProjectivePoint::identity();
+            for bucket in buckets.iter().rev() {
+                running = Self::add_projective(&running, bucket);
+                window = Self::add_projective(&window, &running);
+            }
+            acc = Self::add_projective(&acc, &window);
+        }
+
+        acc.to_affine()
+    }
+
+    /// 3b = 21 = 16 + 4 + 1, from a few additions rather than parsing
+    fn b3() -> F {
+        let one = F::one();
+        let four = one.double().double();
+        four.double().double() + four + one
+    }
+
+    /// Window width in bits, growing roughly with log n
+    fn msm_window(n: usize) -> usize {
+        match n {
+            0..=31 => 3,
+            32..=255 => 5,
+            256..=4095 => 8,
+            _ => 12,
+        }
     }
 }
 
@@ -134,84 +303,228 @@ mod tests {
     use super::*;
 
     #[test]
-    fn add_0() {
-        let prime = Secp256k1::p();
+    fn base_field_is_prime() {
+        Secp256k1Base::check().unwrap();
+    }
+
+    #[test]
+    fn scalar_field_is_prime() {
+        Secp256k1Scalar::check().unwrap();
+    }
+
+    #[test]
+    fn secret_key_is_redacted() {
+        let key = SecretKey::new(Fp::from_u64(0xdeadbeef)).unwrap();
+        assert_eq!(format!("{:?}", key), "SecretKey(Secret(<redacted>))");
+    }
 
-        let p = Secp256k1::g();
-        let q = U256ECPoint::zero_zero(prime);
+    #[test]
+    fn secret_key_is_nonzero() {
+        assert!(SecretKey::new(Fp::zero()).is_none());
+        // n reduces to zero
+        assert!(SecretKey::new(Fp::new(Secp256k1Scalar::MODULUS)).is_none());
 
-        let r = Secp256k1::add(&p, &q);
+        let key = SecretKey::new(Fp::one()).unwrap();
+        assert_eq!(*key.clone_secret().expose_secret(), Fp::one());
+    }
+
+    fn add_0<F: Secp256k1Field>() {
+        let p = Secp256k1Curve::<F>::g();
+        let q = ECPoint::<F>::zero_zero();
+
+        let r = Secp256k1Curve::<F>::add(&p, &q);
         assert_eq!(r, p);
 
-        let r = Secp256k1::add(&q, &p);
+        let r = Secp256k1Curve::<F>::add(&q, &p);
         assert_eq!(r, p);
     }
 
-    #[test]
-    fn add_1() {
-        let prime = Secp256k1::p_str();
-
-        let p = U256ECPoint::from_str(
+    fn add_1<F: Secp256k1Field>() {
+        let p = ECPoint::<F>::from_str(
             "0x79BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798",
             "0x483ADA7726A3C4655DA4FBFC0E1108A8FD17B448A68554199C47D08FFB10D4B8",
-            prime,
         );
-        let q = U256ECPoint::from_str(
+        let q = ECPoint::<F>::from_str(
             "0xC6047F9441ED7D6D3045406E95C07CD85C778E4B8CEF3CA7ABAC09B95C709EE5",
             "0x1AE168FEA63DC339A3C58419466CEAEEF7F632653266D0E1236431A950CFE52A",
-            prime,
         );
-        let r = Secp256k1::add(&p, &q);
+        let r = Secp256k1Curve::<F>::add(&p, &q);
 
-        let expected_result = U256ECPoint::from_str(
+        let expected_result = ECPoint::<F>::from_str(
             "0xf9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9",
             "0x388f7b0f632de8140fe337e62a37f3566500a99934c2231b6cb9fd7584b8e672",
-            prime,
         );
 
         assert_eq!(r, expected_result);
     }
 
-    #[test]
-    fn double_0() {
-        let prime = Secp256k1::p_str();
-
-        let p = U256ECPoint::from_str(
+    fn double_0<F: Secp256k1Field>() {
+        let p = ECPoint::<F>::from_str(
             "79BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798",
             "483ADA7726A3C4655DA4FBFC0E1108A8FD17B448A68554199C47D08FFB10D4B8",
-            prime,
         );
 
-        let p_square = Secp256k1::double(&p);
-        let p_to_the_four = Secp256k1::double(&p_square);
+        let p_square = Secp256k1Curve::<F>::double(&p);
+        let p_to_the_four = Secp256k1Curve::<F>::double(&p_square);
 
-        let expected_result = U256ECPoint::from_str(
+        let expected_result = ECPoint::<F>::from_str(
             "e493dbf1c10d80f3581e4904930b1404cc6c13900ee0758474fa94abe8c4cd13",
             "51ed993ea0d455b75642e2098ea51448d967ae33bfbdfe40cfe97bdc47739922",
-            prime,
         );
 
         assert_eq!(p_to_the_four, expected_result);
     }
 
-    #[test]
-    fn exp_0() {
-        let prime = Secp256k1::p_str();
-
-        let p = U256ECPoint::from_str(
+    fn exp_0<F: Secp256k1Field>() {
+        let p = ECPoint::<F>::from_str(
             "79BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798",
             "483ADA7726A3C4655DA4FBFC0E1108A8FD17B448A68554199C47D08FFB10D4B8",
-            prime,
         );
 
-        let p_square = Secp256k1::double(&p);
-        let p_to_the_four = Secp256k1::double(&p_square);
-        let p_to_the_eight = Secp256k1::double(&p_to_the_four);
-        let p_to_the_sixteen = Secp256k1::double(&p_to_the_eight);
-        let p_to_the_seventeen = Secp256k1::add(&p_to_the_sixteen, &p);
+        let p_square = Secp256k1Curve::<F>::double(&p);
+        let p_to_the_four = Secp256k1Curve::<F>::double(&p_square);
+        let p_to_the_eight = Secp256k1Curve::<F>::double(&p_to_the_four);
+        let p_to_the_sixteen = Secp256k1Curve::<F>::double(&p_to_the_eight);
+        let p_to_the_seventeen = Secp256k1Curve::<F>::add(&p_to_the_sixteen, &p);
 
-        let p_exp_seventeen = Secp256k1::exp(&p, U256::from(17));
+        let p_exp_seventeen = Secp256k1Curve::<F>::exp(&p, U256::from(17));
 
         assert_eq!(p_to_the_seventeen, p_exp_seventeen);
+        assert_eq!(
+            Secp256k1Curve::<F>::exp(&p, U256::zero()),
+            Secp256k1Curve::<F>::identity()
+        );
+    }
+
+    fn add_projective_0<F: Secp256k1Field>() {
+        let g = Secp256k1Curve::<F>::g();
+        let mut points = vec![Secp256k1Curve::<F>::identity(), g.clone()];
+        for k in [2u64, 3, 17, 0xdeadbeef] {
+            points.push(Secp256k1Curve::<F>::exp(&g, U256::from(k)));
+        }
+        let minus_g = ECPoint {
+            x: g.x.clone(),
+            y: -g.y.clone(),
+        };
+        points.push(minus_g);
+
+        for p in &points {
+            for q in &points {
+                let r = Secp256k1Curve::<F>::add_projective(
+                    &ProjectivePoint::from_affine(p),
+                    &ProjectivePoint::from_affine(q),
+                );
+                assert_eq!(r.to_affine(), Secp256k1Curve::<F>::add(p, q));
+            }
+        }
+
+        // Scaled coordinates give the same point
+        let two = F::one().double();
+        let scaled = ProjectivePoint {
+            x: g.x.clone() * &two,
+            y: g.y.clone() * &two,
+            z: two,
+        };
+        assert_eq!(scaled, ProjectivePoint::from_affine(&g));
+        assert_eq!(scaled.to_affine(), g);
+        assert_eq!(scaled.to_affine_ct(), g);
+        assert_eq!(
+            ProjectivePoint::<F>::identity().to_affine_ct(),
+            Secp256k1Curve::<F>::identity()
+        );
+    }
+
+    fn msm_0<F: Secp256k1Field>() {
+        let g = Secp256k1Curve::<F>::g();
+        let scalars = [
+            "1",
+            "0",
+            "2",
+            "deadbeefcafebabe0123456789abcdef",
+            "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364140",
+            "7",
+        ]
+        .map(|s| U256::from_str_radix(s, 16).unwrap());
+        let points: Vec<ECPoint<F>> = (1..=scalars.len() as u64)
+            .map(|k| Secp256k1Curve::<F>::exp(&g, U256::from(k * 1000 + 1)))
+            .collect();
+
+        let expected = points
+            .iter()
+            .zip(&scalars)
+            .fold(Secp256k1Curve::<F>::identity(), |acc, (p, s)| {
+                Secp256k1Curve::<F>::add(&acc, &Secp256k1Curve::<F>::exp(p, *s))
+            });
+        assert_eq!(Secp256k1Curve::<F>::msm(&points, &scalars), expected);
+
+        assert_eq!(
+            Secp256k1Curve::<F>::msm(&[], &[]),
+            Secp256k1Curve::<F>::identity()
+        );
+        assert_eq!(
+            Secp256k1Curve::<F>::msm(&points[..2], &[U256::zero(), U256::zero()]),
+            Secp256k1Curve::<F>::identity()
+        );
+    }
+
+    fn public_key_0<F: Secp256k1Field + ConditionallySelectable>() {
+        let one = SecretKey::new(Fp::one()).unwrap();
+        assert_eq!(
+            Secp256k1Curve::<F>::public_key(&one),
+            Secp256k1Curve::<F>::g()
+        );
+
+        // n - 1 gives -G
+        let minus_one = SecretKey::new(-Fp::one()).unwrap();
+        let g = Secp256k1Curve::<F>::g();
+        let minus_g = ECPoint { x: g.x, y: -g.y };
+        assert_eq!(Secp256k1Curve::<F>::public_key(&minus_one), minus_g);
+
+        // the ladder and its constant-time affine conversion agree with
+        // double & add
+        for k in [
+            "2",
+            "deadbeefcafebabe0123456789abcdef",
+            "7fffffffffffffffffffffffffffffff5d576e7357a4501ddfe92f46681b20a0",
+            "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd036413f",
+        ] {
+            let k = U256::from_str_radix(k, 16).unwrap();
+            let key = SecretKey::new(Fp::new(k)).unwrap();
+            assert_eq!(
+                Secp256k1Curve::<F>::public_key(&key),
+                Secp256k1Curve::<F>::exp(&g, k)
+            );
+        }
+    }
+
+    #[test]
+    fn montgomery_backend() {
+        add_0::<Fp<Secp256k1Base>>();
+        add_1::<Fp<Secp256k1Base>>();
+        double_0::<Fp<Secp256k1Base>>();
+        exp_0::<Fp<Secp256k1Base>>();
+        add_projective_0::<Fp<Secp256k1Base>>();
+        msm_0::<Fp<Secp256k1Base>>();
+        public_key_0::<Fp<Secp256k1Base>>();
+    }
+
+    #[test]
+    fn pseudo_mersenne_backend() {
+        add_0::<PseudoMersenneFieldElement<Secp256k1Base>>();
+        add_1::<PseudoMersenneFieldElement<Secp256k1Base>>();
+        double_0::<PseudoMersenneFieldElement<Secp256k1Base>>();
+        exp_0::<PseudoMersenneFieldElement<Secp256k1Base>>();
+        add_projective_0::<PseudoMersenneFieldElement<Secp256k1Base>>();
+        msm_0::<PseudoMersenneFieldElement<Secp256k1Base>>();
+        public_key_0::<PseudoMersenneFieldElement<Secp256k1Base>>();
+    }
+
+    #[test]
+    fn backends_agree() {
+        let k = U256::from_str_radix("deadbeefcafebabe0123456789abcdef", 16).unwrap();
+        let slow = Secp256k1::exp(&Secp256k1::g(), k);
+        let fast = Secp256k1Fast::exp(&Secp256k1Fast::g(), k);
+        assert_eq!(slow.x, Fp::from(fast.x));
+        assert_eq!(slow.y, Fp::from(fast.y));
     }
 }
@@ -0,0 +1,216 @@
+//! Parameters of the BN254 (alt_bn128) pairing-friendly curve: the base
+//! field Fq, the scalar field Fr and the tower
+//!
+//! ```text
+//! Fq2  = Fq[u]  / (u^2 + 1)
+//! Fq6  = Fq2[v] / (v^3 - (9 + u))
+//! Fq12 = Fq6[w] / (w^2 - v)
+//! ```
+use crate::fp12::Fp12Params;
+use crate::fp2::{Fp2, Fp2Params};
+use crate::fp6::Fp6Params;
+use crate::p_u256::Fp;
+use crate::params::{u256_from_hex, PrimeFieldParams};
+use primitive_types::U256;
+
+#[derive(Debug)]
+pub struct Bn254Fq;
+impl PrimeFieldParams for Bn254Fq {
+    const MODULUS: U256 =
+        u256_from_hex("30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd47");
+}
+
+#[derive(Debug)]
+pub struct Bn254Fr;
+impl PrimeFieldParams for Bn254Fr {
+    const MODULUS: U256 =
+        u256_from_hex("30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001");
+    /// p - 1 = 2^28 * t, so radix-2 FFTs up to size 2^28
+    const MULTIPLICATIVE_GENERATOR: Option<U256> = Some(U256([5, 0, 0, 0]));
+}
+
+#[derive(Debug)]
+pub struct Bn254Fq2;
+impl Fp2Params for Bn254Fq2 {
+    type Fp = Bn254Fq;
+    /// -1
+    const NON_RESIDUE: Fp<Bn254Fq> = Fp::new(u256_from_hex(
+        "30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd46",
+    ));
+}
+
+#[derive(Debug)]
+pub struct Bn254Fq6;
+impl Fp6Params for Bn254Fq6 {
+    type Fp2 = Bn254Fq2;
+    /// 9 + u
+    const NON_RESIDUE: Fp2<Bn254Fq2> = fp2("9", "1");
+    const FROBENIUS_COEFF_C1: [Fp2<Bn254Fq2>; 6] = [
+        fp2("1", "0"),
+        fp2(
+            "2fb347984f7911f74c0bec3cf559b143b78cc310c2c3330c99e39557176f553d",
+            "16c9e55061ebae204ba4cc8bd75a079432ae2a1d0b7c9dce1665d51c640fcba2",
+        ),
+        fp2(
+            "30644e72e131a0295e6dd9e7e0acccb0c28f069fbb966e3de4bd44e5607cfd48",
+            "0",
+        ),
+        fp2(
+            "0856e078b755ef0abaff1c77959f25ac805ffd3d5d6942d37b746ee87bdcfb6d",
+            "04f1de41b3d1766fa9f30e6dec26094f0fdf31bf98ff2631380cab2baaa586de",
+        ),
+        fp2(
+            "000000000000000059e26bcea0d48bacd4f263f1acdb5c4f5763473177fffffe",
+            "0",
+        ),
+        fp2(
+            "28be74d4bb943f51699582b87809d9caf71614d4b0b71f3a62e913ee1dada9e4",
+            "14a88ae0cb747b99c2b86abcbe01477a54f40eb4c3f6068dedae0bcec9c7aac7",
+        ),
+    ];
+    const FROBENIUS_COEFF_C2: [Fp2<Bn254Fq2>; 6] = [
+        fp2("1", "0"),
+        fp2(
+            "05b54f5e64eea80180f3c0b75a181e84d33365f7be94ec72848a1f55921ea762",
+            "2c145edbe7fd8aee9f3a80b03b0b1c923685d2ea1bdec763c13b4711cd2b8126",
+        ),
+        fp2(
+            "000000000000000059e26bcea0d48bacd4f263f1acdb5c4f5763473177fffffe",
+            "0",
+        ),
+        fp2(
+            "0bc58c6611c08dab19bee0f7b5b2444ee633094575b06bcb0e1a92bc3ccbf066",
+            "23d5e999e1910a12feb0f6ef0cd21d04a44a9e08737f96e55fe3ed9d730c239f",
+        ),
+        fp2(
+            "30644e72e131a0295e6dd9e7e0acccb0c28f069fbb966e3de4bd44e5607cfd48",
+            "0",
+        ),
+        fp2(
+            "1ee972ae6a826a7d1d9da40771b6f589de1afb54342c724fa97bda050992657f",
+            "10de546ff8d4ab51d2b513cdbb25772454326430418536d15721e37e70c255c9",
+        ),
+    ];
+}
+
+#[derive(Debug)]
+pub struct Bn254Fq12;
+impl Fp12Params for Bn254Fq12 {
+    type Fp6 = Bn254Fq6;
+    const FROBENIUS_COEFF_C1: [Fp2<Bn254Fq2>; 12] = [
+        fp2("1", "0"),
+        fp2(
+            "1284b71c2865a7dfe8b99fdd76e68b605c521e08292f2176d60b35dadcc9e470",
+            "246996f3b4fae7e6a6327cfe12150b8e747992778eeec7e5ca5cf05f80f362ac",
+        ),
+        fp2(
+            "30644e72e131a0295e6dd9e7e0acccb0c28f069fbb966e3de4bd44e5607cfd49",
+            "0",
+        ),
+        fp2(
+            "19dc81cfcc82e4bbefe9608cd0acaa90894cb38dbe55d24ae86f7d391ed4a67f",
+            "00abf8b60be77d7306cbeee33576139d7f03a5e397d439ec7694aa2bf4c0c101",
+        ),
+        fp2(
+            "30644e72e131a0295e6dd9e7e0acccb0c28f069fbb966e3de4bd44e5607cfd48",
+            "0",
+        ),
+        fp2(
+            "0757cab3a41d3cdc072fc0af59c61f302cfa95859526b0d41264475e420ac20f",
+            "0ca6b035381e35b618e9b79ba4e2606ca20b7dfd71573c93e85845e34c4a5b9c",
+        ),
+        fp2(
+            "30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd46",
+            "0",
+        ),
+        fp2(
+            "1ddf9756b8cbf849cf96a5d90a9accfd3b2f4c893f42a9166615563bfbb318d7",
+            "0bfab77f2c36b843121dc8b86f6c4ccf2307d819d98302a771c39bb757899a9b",
+        ),
+        fp2(
+            "000000000000000059e26bcea0d48bacd4f263f1acdb5c4f5763473177fffffe",
+            "0",
+        ),
+        fp2(
+            "1687cca314aebb6dc866e529b0d4adcd0e34b703aa1bf84253b10eddb9a856c8",
+            "2fb855bcd54a22b6b18456d34c0b44c0187dc4add09d90a0c58be1eae3bc3c46",
+        ),
+        fp2(
+            "000000000000000059e26bcea0d48bacd4f263f1acdb5c4f5763473177ffffff",
+            "0",
+        ),
+        fp2(
+            "290c83bf3d14634db120850727bb392d6a86d50bd34b19b929bc44b896723b38",
+            "23bd9e3da9136a739f668e1adc9ef7f0f575ec93f71a8df953c846338c32a1ab",
+        ),
+    ];
+    const FINAL_EXPONENT_HARD_PART: &'static [u64] = &[
+        0xe81bb482ccdf42b1,
+        0x5abf5cc4f49c36d4,
+        0xf1154e7e1da014fd,
+        0xdcc7b44c87cdbacf,
+        0xaaa441e3954bcf8a,
+        0x6b887d56d5095f23,
+        0x79581e16f3fd90c6,
+        0x3b1b1355d189227d,
+        0x4e529a5861876f6b,
+        0x6c0eb522d5b12278,
+        0x331ec15183177faf,
+        0x01baaa710b0759ad,
+    ];
+}
+
+const fn fp2(c0: &str, c1: &str) -> Fp2<Bn254Fq2> {
+    Fp2::new(Fp::new(u256_from_hex(c0)), Fp::new(u256_from_hex(c1)))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::field::Field;
+    use num_bigint::BigUint;
+
+    pub(crate) fn biguint(limbs: &[u64]) -> BigUint {
+        let bytes: Vec<u8> = limbs.iter().flat_map(|l| l.to_le_bytes()).collect();
+        BigUint::from_bytes_le(&bytes)
+    }
+
+    pub(crate) fn limbs(num: &BigUint) -> Vec<u64> {
+        num.to_bytes_le()
+            .chunks(8)
+            .map(|c| {
+                let mut limb = [0u8; 8];
+                limb[..c.len()].copy_from_slice(c);
+                u64::from_le_bytes(limb)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn params_are_prime() {
+        Bn254Fq::check().unwrap();
+        Bn254Fr::check().unwrap();
+    }
+
+    /// Recomputes ξ^(k(p^i - 1) / d)
+    fn frobenius_coeff(i: usize, k: u32, d: u32) -> Fp2<Bn254Fq2> {
+        let p = biguint(&Bn254Fq::MODULUS.0);
+        let mut p_i = BigUint::from(1u32);
+        for _ in 0..i {
+            p_i *= &p;
+        }
+        let exp = (p_i - 1u32) * k / d;
+        Bn254Fq6::NON_RESIDUE.pow(&limbs(&exp))
+    }
+
+    #[test]
+    fn frobenius_coefficients() {
+        for i in 0..6 {
+            assert_eq!(frobenius_coeff(i, 1, 3), Bn254Fq6::FROBENIUS_COEFF_C1[i]);
+            assert_eq!(frobenius_coeff(i, 2, 3), Bn254Fq6::FROBENIUS_COEFF_C2[i]);
+        }
+        for i in 0..12 {
+            assert_eq!(frobenius_coeff(i, 1, 6), Bn254Fq12::FROBENIUS_COEFF_C1[i]);
+        }
+    }
+}
@@ -0,0 +1,520 @@
+//! Discrete logarithms: given g and h = g^x, find x.
+//!
+//! The solvers only need the group operation, so they are written over
+//! `CyclicGroup`, which the multiplicative group of `U64FieldElement`
+//! implements and which the points of a toy curve can implement as well.
+//! All of them are variable time and meant for public values: teaching,
+//! parameter validation, breaking deliberately small groups.
+//!
+//! ```text
+//! solver                 time          memory      needs
+//! baby_step_giant_step   O(sqrt n)     O(sqrt n)   order n
+//! pollard_rho            O(sqrt n)     O(1)        order n, ideally prime
+//! pohlig_hellman         O(sum e_i (log n + sqrt q_i))     factorization of n
+//! ```
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+use crate::factor::{factor_u64, gcd, mul_mod};
+use crate::field::Field;
+use crate::p_u64::U64FieldElement;
+use crate::params::PrimeFieldParams;
+
+/// A finite group, written multiplicatively, in which discrete logarithms
+/// are solved. Only the subgroup generated by the base matters, so the
+/// group itself need not be cyclic.
+///
+/// `Ord` must agree with `Eq`; it backs the baby-step table.
+pub trait CyclicGroup: Clone + Ord {
+    fn identity() -> Self;
+
+    fn operate(&self, rhs: &Self) -> Self;
+
+    fn inverse(&self) -> Self;
+
+    /// A value computed from the canonical form of the element, picking the
+    /// step of Pollard's rho. Equal elements must give equal values, and the
+    /// values mod 3 should look random over the group.
+    fn partition(&self) -> u64;
+
+    /// self^k by square & multiply
+    fn power(&self, k: u64) -> Self {
+        let mut res = Self::identity();
+        for i in (0..64 - k.leading_zeros()).rev() {
+            res = res.operate(&res);
+            if (k >> i) & 1 == 1 {
+                res = res.operate(self);
+            }
+        }
+        res
+    }
+}
+
+/// GF(p)*, using the variable-time multiplication
+impl<P: PrimeFieldParams> CyclicGroup for U64FieldElement<P> {
+    fn identity() -> Self {
+        Self::ONE
+    }
+
+    fn operate(&self, rhs: &Self) -> Self {
+        self.mul_vartime(rhs)
+    }
+
+    /// Panics on zero, which is not in the group
+    fn inverse(&self) -> Self {
+        self.inv().expect("zero is not in the multiplicative group")
+    }
+
+    /// The canonical value, scrambled by a Fibonacci hash
+    fn partition(&self) -> u64 {
+        self.num().wrapping_mul(0x9e37_79b9_7f4a_7c15) >> 32
+    }
+
+    fn power(&self, k: u64) -> Self {
+        self.exp(&k)
+    }
+}
+
+/// Cycle finding for `pollard_rho`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CycleDetection {
+    /// Tortoise and hare, three steps of the walk per iteration
+    Floyd,
+    /// Power-of-two windows, about a third fewer group operations
+    Brent,
+}
+
+/// Groups of prime order up to this are handed to BSGS by `pohlig_hellman`,
+/// larger ones to rho. The baby-step table then has at most 2^16 entries.
+const BSGS_LIMIT: u64 = 1 << 32;
+
+/// Rho restarts from a new starting point at most this many times
+const RHO_ATTEMPTS: u64 = 32;
+
+/// x in [0, n) with g^x = h, where n is the order of g (or a multiple of
+/// it), by Shanks' baby-step giant-step. `None` if h is not a power of g.
+pub fn baby_step_giant_step<G: CyclicGroup>(g: &G, h: &G, n: u64) -> Option<u64> {
+    let m = ceil_sqrt(n);
+
+    // baby steps g^j
+    let mut table = BTreeMap::new();
+    let mut power = G::identity();
+    for j in 0..m {
+        table.entry(power.clone()).or_insert(j);
+        power = power.operate(g);
+    }
+
+    // giant steps h g^(-m i)
+    let giant = g.power(m).inverse();
+    let mut gamma = h.clone();
+    for i in 0..m {
+        if let Some(j) = table.get(&gamma) {
+            let x = i * m + j;
+            if x < n {
+                return Some(x);
+            }
+        }
+        gamma = gamma.operate(&giant);
+    }
+    None
+}
+
+/// x in [0, n) with g^x = h by Pollard's rho, where n is the order of g,
+/// with constant memory
+///
+/// Walks x_(i+1) = x_i h, x_i^2 or x_i g depending on the partition of x_i,
+/// tracking x_i = g^a h^b, until `detection` finds a collision
+/// g^a h^b = g^a' h^b', which gives (b - b') x = a' - a (mod n). When
+/// d = gcd(b - b', n) > 1 the d candidates are tried, so composite n works
+/// but prime n is the intended case. `None` if h is not a power of g or all
+/// restarts failed.
+pub fn pollard_rho<G: CyclicGroup>(g: &G, h: &G, n: u64, detection: CycleDetection) -> Option<u64> {
+    if n == 1 {
+        return (*h == G::identity()).then_some(0);
+    }
+
+    for attempt in 1..=RHO_ATTEMPTS {
+        let a0 = attempt % n;
+        let b0 = (attempt * 0x9e37_79b9) % n;
+        let start = Walk {
+            x: g.power(a0).operate(&h.power(b0)),
+            a: a0,
+            b: b0,
+        };
+        let (p, q) = match detection {
+            CycleDetection::Floyd => floyd(start, g, h, n),
+            CycleDetection::Brent => brent(start, g, h, n),
+        };
+        if let Some(x) = solve_collision(g, h, n, &p, &q) {
+            return Some(x);
+        }
+    }
+    None
+}
+
+/// x in [0, n) with g^x = h, where n is the order of g and `factors` its
+/// factorization as (prime, exponent) pairs
+///
+/// Solves the problem in the subgroup of order q^e for each prime power,
+/// one base-q digit at a time, and combines the results by CRT. Digits are
+/// found with BSGS, or with rho for primes above 2^32. `None` if h is not a
+/// power of g.
+pub fn pohlig_hellman<G: CyclicGroup>(g: &G, h: &G, n: u64, factors: &[(u64, u32)]) -> Option<u64> {
+    debug_assert_eq!(
+        factors
+            .iter()
+            .map(|(q, e)| q.pow(*e) as u128)
+            .product::<u128>(),
+        n as u128
+    );
+
+    let (mut x, mut modulus) = (0u64, 1u64);
+    for &(q, e) in factors {
+        let q_e = q.pow(e);
+        let cofactor = n / q_e;
+        // g_q has order q^e and h_q = g_q^(x mod q^e)
+        let g_q = g.power(cofactor);
+        let h_q = h.power(cofactor);
+        // order q
+        let gamma = g_q.power(q_e / q);
+
+        let mut x_q = 0u64;
+        let mut q_i = 1u64;
+        for i in 0..e {
+            // (h_q g_q^(-x_q))^(q^(e-1-i)) = gamma^(digit i)
+            let target = h_q
+                .operate(&g_q.power(x_q).inverse())
+                .power(q.pow(e - 1 - i));
+            let digit = match q <= BSGS_LIMIT {
+                true => baby_step_giant_step(&gamma, &target, q)?,
+                false => pollard_rho(&gamma, &target, q, CycleDetection::Brent)?,
+            };
+            x_q += digit * q_i;
+            q_i = q_i.wrapping_mul(q);
+        }
+
+        x = crt(x, modulus, x_q, q_e);
+        modulus *= q_e;
+    }
+
+    (g.power(x) == *h).then_some(x)
+}
+
+/// log_g(h) in GF(p)*, in [0, ord(g)), by Pohlig-Hellman over the order of
+/// g, which is found from a factorization of p - 1. `None` if either
+/// element is zero or h is not a power of g.
+pub fn discrete_log<P: PrimeFieldParams>(
+    g: &U64FieldElement<P>,
+    h: &U64FieldElement<P>,
+) -> Option<u64> {
+    if g.is_zero() || h.is_zero() {
+        return None;
+    }
+    let p_minus_1 = U64FieldElement::<P>::prime() - 1;
+    let (order, factors) = order_with_factors(g, p_minus_1, &factor_u64(p_minus_1));
+    pohlig_hellman(g, h, order, &factors)
+}
+
+/// The order of g, given a multiple n of it and n's factorization, along
+/// with the factorization of the order
+pub(crate) fn order_with_factors<G: CyclicGroup>(
+    g: &G,
+    n: u64,
+    factors: &[(u64, u32)],
+) -> (u64, Vec<(u64, u32)>) {
+    let mut order = n;
+    let mut order_factors = Vec::new();
+    for &(q, e) in factors {
+        let mut e_left = e;
+        while e_left > 0 && g.power(order / q) == G::identity() {
+            order /= q;
+            e_left -= 1;
+        }
+        if e_left > 0 {
+            order_factors.push((q, e_left));
+        }
+    }
+    (order, order_factors)
+}
+
+/////////////////////////////////////////////
+/////////////// Rho
+/////////////////////////////////////////////
+/// x = g^a h^b
+#[derive(Clone)]
+struct Walk<G> {
+    x: G,
+    a: u64,
+    b: u64,
+}
+
+impl<G: CyclicGroup> Walk<G> {
+    fn step(&self, g: &G, h: &G, n: u64) -> Self {
+        match self.x.partition() % 3 {
+            0 => Walk {
+                x: self.x.operate(h),
+                a: self.a,
+                b: add_mod(self.b, 1, n),
+            },
+            1 => Walk {
+                x: self.x.operate(&self.x),
+                a: add_mod(self.a, self.a, n),
+                b: add_mod(self.b, self.b, n),
+            },
+            _ => Walk {
+                x: self.x.operate(g),
+                a: add_mod(self.a, 1, n),
+                b: self.b,
+            },
+        }
+    }
+}
+
+/// Two points of the walk with the same x
+fn floyd<G: CyclicGroup>(start: Walk<G>, g: &G, h: &G, n: u64) -> (Walk<G>, Walk<G>) {
+    let mut tortoise = start.step(g, h, n);
+    let mut hare = tortoise.step(g, h, n);
+    while tortoise.x != hare.x {
+        tortoise = tortoise.step(g, h, n);
+        hare = hare.step(g, h, n).step(g, h, n);
+    }
+    (tortoise, hare)
+}
+
+/// Two points of the walk with the same x
+fn brent<G: CyclicGroup>(start: Walk<G>, g: &G, h: &G, n: u64) -> (Walk<G>, Walk<G>) {
+    let mut power = 1u64;
+    let mut lambda = 1u64;
+    let mut tortoise = start.clone();
+    let mut hare = start.step(g, h, n);
+    while tortoise.x != hare.x {
+        if power == lambda {
+            tortoise = hare.clone();
+            power *= 2;
+            lambda = 0;
+        }
+        hare = hare.step(g, h, n);
+        lambda += 1;
+    }
+    (tortoise, hare)
+}
+
+/// Solves (b - b') x = a' - a (mod n) and checks the candidates against h
+fn solve_collision<G: CyclicGroup>(g: &G, h: &G, n: u64, p: &Walk<G>, q: &Walk<G>) -> Option<u64> {
+    let db = sub_mod(p.b, q.b, n);
+    let da = sub_mod(q.a, p.a, n);
+    let d = gcd(db, n);
+    if db == 0 || !da.is_multiple_of(d) || d > 1 << 16 {
+        return None;
+    }
+
+    let m = n / d;
+    let x0 = mul_mod(da / d, inv_mod(db / d, m)?, m);
+    (0..d).map(|k| x0 + k * m).find(|x| g.power(*x) == *h)
+}
+
+/////////////////////////////////////////////
+/////////////// Integers mod n
+/////////////////////////////////////////////
+fn add_mod(a: u64, b: u64, n: u64) -> u64 {
+    ((a as u128 + b as u128) % n as u128) as u64
+}
+
+fn sub_mod(a: u64, b: u64, n: u64) -> u64 {
+    ((a as u128 + n as u128 - b as u128) % n as u128) as u64
+}
+
+/// a^-1 mod n by extended Euclid, `None` unless gcd(a, n) = 1
+fn inv_mod(a: u64, n: u64) -> Option<u64> {
+    if n == 1 {
+        return Some(0);
+    }
+    let (mut r0, mut r1) = (n as i128, (a % n) as i128);
+    let (mut t0, mut t1) = (0i128, 1i128);
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (t0, t1) = (t1, t0 - q * t1);
+    }
+    (r0 == 1).then(|| t0.rem_euclid(n as i128) as u64)
+}
+
+/// The x mod m1 m2 with x = x1 (mod m1) and x = x2 (mod m2), for coprime
+/// moduli
+fn crt(x1: u64, m1: u64, x2: u64, m2: u64) -> u64 {
+    // x = x1 + m1 ((x2 - x1) / m1 mod m2)
+    let t = mul_mod(sub_mod(x2, x1 % m2, m2), inv_mod(m1 % m2, m2).unwrap(), m2);
+    (x1 as u128 + m1 as u128 * t as u128) as u64
+}
+
+fn ceil_sqrt(n: u64) -> u64 {
+    let r = n.isqrt();
+    match r * r < n {
+        true => r + 1,
+        false => r.max(1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::params::u256_from_hex;
+    use primitive_types::U256;
+
+    // p - 1 = 2 * 509
+    test_prime_field!(P1019, U256([1019, 0, 0, 0]));
+    test_prime_field!(P43, U256([43, 0, 0, 0]));
+    // 2^61 - 1
+    test_prime_field!(M61, u256_from_hex("1fffffffffffffff"));
+    // safe prime, p - 1 = 2 * 4294967681
+    test_prime_field!(P33, u256_from_hex("200000303"));
+
+    #[test]
+    fn params_are_prime() {
+        P1019::check().unwrap();
+        P43::check().unwrap();
+        M61::check().unwrap();
+        P33::check().unwrap();
+    }
+
+    #[test]
+    fn bsgs_and_rho_in_prime_field() {
+        type F = U64FieldElement<P1019>;
+        // 2 generates GF(1019)*
+        let g = F::new(2);
+        assert_eq!(order_with_factors(&g, 1018, &factor_u64(1018)).0, 1018);
+        for x in [0, 1, 2, 500, 1017] {
+            let h = g.power(x);
+            assert_eq!(baby_step_giant_step(&g, &h, 1018), Some(x));
+        }
+
+        // rho in the subgroup of prime order 509
+        let g2 = g.square();
+        for x in [0, 1, 17, 508] {
+            let h = g2.power(x);
+            assert_eq!(pollard_rho(&g2, &h, 509, CycleDetection::Floyd), Some(x));
+            assert_eq!(pollard_rho(&g2, &h, 509, CycleDetection::Brent), Some(x));
+        }
+
+        // 2 is not a power of 4
+        assert_eq!(baby_step_giant_step(&g2, &g, 509), None);
+        assert_eq!(pollard_rho(&g2, &g, 509, CycleDetection::Brent), None);
+    }
+
+    #[test]
+    fn pohlig_hellman_smooth_order() {
+        type F = U64FieldElement<M61>;
+        let g = F::new(37);
+        let p_minus_1 = F::prime() - 1;
+        let (order, _) = order_with_factors(&g, p_minus_1, &factor_u64(p_minus_1));
+        for x in [0, 1, 0x0123_4567_89ab_cdef % order, order - 1] {
+            assert_eq!(discrete_log(&g, &g.power(x)), Some(x));
+        }
+    }
+
+    #[test]
+    fn pohlig_hellman_large_prime_factor() {
+        // the factor above 2^32 is left to rho
+        type F = U64FieldElement<P33>;
+        let g = F::new(5);
+        let x = 0x1_2345_6789;
+        let log = discrete_log(&g, &g.power(x)).unwrap();
+        assert_eq!(g.power(log), g.power(x));
+    }
+
+    #[test]
+    fn discrete_log_edge_cases() {
+        type F = U64FieldElement<P43>;
+        let g = F::new(3);
+        assert_eq!(discrete_log(&g, &F::ONE), Some(0));
+        assert_eq!(discrete_log(&g, &F::ZERO), None);
+        assert_eq!(discrete_log(&F::ZERO, &g), None);
+        // -1 has order 2, so only 1 and -1 are its powers
+        let minus_one = -F::ONE;
+        assert_eq!(discrete_log(&minus_one, &F::ONE), Some(0));
+        assert_eq!(discrete_log(&minus_one, &minus_one), Some(1));
+        assert_eq!(discrete_log(&minus_one, &g), None);
+    }
+
+    /// Points of y^2 = x^3 + 2x + 3 over GF(1019), for the solvers over a
+    /// group that is not a field
+    #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+    enum ToyPoint {
+        Infinity,
+        Affine(U64FieldElement<P1019>, U64FieldElement<P1019>),
+    }
+
+    impl CyclicGroup for ToyPoint {
+        fn identity() -> Self {
+            ToyPoint::Infinity
+        }
+
+        fn operate(&self, rhs: &Self) -> Self {
+            type F = U64FieldElement<P1019>;
+            let (x1, y1, x2, y2) = match (self, rhs) {
+                (ToyPoint::Infinity, q) => return q.clone(),
+                (p, ToyPoint::Infinity) => return p.clone(),
+                (ToyPoint::Affine(x1, y1), ToyPoint::Affine(x2, y2)) => (*x1, *y1, *x2, *y2),
+            };
+            let slope = if x1 != x2 {
+                (y2 - y1) / (x2 - x1)
+            } else if y1 == y2 && !y1.is_zero() {
+                (x1.square() * F::new(3) + F::new(2)) / y1.double()
+            } else {
+                return ToyPoint::Infinity;
+            };
+            let x3 = slope.square() - x1 - x2;
+            ToyPoint::Affine(x3, slope * (x1 - x3) - y1)
+        }
+
+        fn inverse(&self) -> Self {
+            match self {
+                ToyPoint::Infinity => ToyPoint::Infinity,
+                ToyPoint::Affine(x, y) => ToyPoint::Affine(*x, -*y),
+            }
+        }
+
+        fn partition(&self) -> u64 {
+            match self {
+                ToyPoint::Infinity => 0,
+                ToyPoint::Affine(x, y) => x.partition() ^ y.partition(),
+            }
+        }
+    }
+
+    #[test]
+    fn toy_curve() {
+        type F = U64FieldElement<P1019>;
+        let points: Vec<ToyPoint> = (0..1019)
+            .flat_map(|x| {
+                let (x, rhs) = (F::new(x), F::new(x).exp(&3) + F::new(2 * x + 3));
+                (0..1019)
+                    .map(F::new)
+                    .filter(move |y| y.square() == rhs)
+                    .map(move |y| ToyPoint::Affine(x, y))
+            })
+            .collect();
+        let n = points.len() as u64 + 1;
+
+        // the point of largest order, and a point of prime order in its group
+        let (g, order) = points
+            .iter()
+            .map(|p| (p.clone(), order_with_factors(p, n, &factor_u64(n)).0))
+            .max_by_key(|(_, order)| *order)
+            .unwrap();
+        let factors = factor_u64(order);
+        let q = factors.last().unwrap().0;
+        let g_q = g.power(order / q);
+
+        for x in [0, 1, 5, order - 1] {
+            let h = g.power(x);
+            assert_eq!(baby_step_giant_step(&g, &h, order), Some(x));
+            assert_eq!(pohlig_hellman(&g, &h, order, &factors), Some(x));
+
+            let x = x % q;
+            let h = g_q.power(x);
+            assert_eq!(pollard_rho(&g_q, &h, q, CycleDetection::Floyd), Some(x));
+            assert_eq!(pollard_rho(&g_q, &h, q, CycleDetection::Brent), Some(x));
+        }
+    }
+}
@@ -2,18 +2,54 @@
 pub enum Error {
     NotPrime,
     NotImplemented,
-    DifferentFields,
+    /// An encoding of the wrong size
+    InvalidLength,
+    /// A value that is not fully reduced, i.e. >= p
+    NonCanonical,
+    /// A string that is not a number in the expected radix
+    InvalidString,
+    /// An empty domain separation tag
+    InvalidDst,
+    /// No element of the requested multiplicative order exists
+    NoRootOfUnity,
+    /// A modulus polynomial that factors, so the quotient is not a field
+    NotIrreducible,
+    /// p - 1 could not be factored, so the group structure of GF(p)* is
+    /// out of reach
+    UnknownFactorization,
+    /// Matrices or vectors of incompatible shapes
+    DimensionMismatch,
+    /// A matrix with no inverse, or a linear system without a unique
+    /// solution
+    SingularMatrix,
+    /// An inconsistent linear system
+    NoSolution,
+    /// A zero denominator in a constructor
+    DivisionByZero,
 }
 
 pub type Result<T> = core::result::Result<T, Error>;
 
 // Display trait
-impl std::fmt::Display for Error {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Error::NotPrime => write!(f, "Provided prime is not a prime"),
-            Error::DifferentFields => write!(f, "Elements belong to different prime fields"),
             Error::NotImplemented => write!(f, "Feature not implemented"),
+            Error::InvalidLength => write!(f, "Encoding has the wrong length"),
+            Error::NonCanonical => write!(f, "Encoded value is not reduced modulo p"),
+            Error::InvalidString => write!(f, "String is not a valid number"),
+            Error::InvalidDst => write!(f, "Domain separation tag must not be empty"),
+            Error::NoRootOfUnity => write!(f, "Order does not divide p - 1"),
+            Error::NotIrreducible => write!(f, "Modulus polynomial is not irreducible"),
+            Error::UnknownFactorization => write!(f, "Could not factor p - 1"),
+            Error::DimensionMismatch => write!(f, "Matrix dimensions do not match"),
+            Error::SingularMatrix => write!(f, "Matrix is singular"),
+            Error::NoSolution => write!(f, "Linear system has no solution"),
+            Error::DivisionByZero => write!(f, "Division by zero"),
         }
     }
 }
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
@@ -0,0 +1,395 @@
+//! Variable-time exponentiation with public exponents, given as
+//! little-endian u64 limbs like `Field::pow`
+//!
+//! ```text
+//! method                 precomputation        cost per exponent bit
+//! pow_k_ary              2^k                   1 S + 1/k M
+//! pow_sliding_window     2^(k-1)               1 S + 1/(k+1) M
+//! FixedBase              (2^k) * bits / k      1/k M, and no squarings
+//! multi_pow (Straus)     2^k per base          1 S + n/k M for n bases
+//! shamir_pow             1                     1 S + 3/4 M for two bases
+//! ```
+//!
+//! x^0 is 1 for every x, zero included, and an empty exponent is 0.
+//! Secret exponents need the element types' `pow_ct` instead.
+use crate::field::Field;
+use alloc::vec::Vec;
+
+/// base^exp, left to right in windows of k bits. Panics unless
+/// 1 <= k <= 16.
+pub fn pow_k_ary<F: Field>(base: &F, exp: &[u64], k: u32) -> F {
+    k_ary(base, exp, k, F::one(), mul)
+}
+
+/// base^exp with a sliding window over the exponent, its width picked from
+/// the exponent size. Only odd powers of the base are precomputed and runs
+/// of zeros cost squarings only.
+pub fn pow_sliding_window<F: Field>(base: &F, exp: &[u64]) -> F {
+    sliding_window(base, exp, F::one(), mul)
+}
+
+/// prod bases[i]^exps[i] by Straus' method: the exponents are walked
+/// together in windows, so the squarings are shared among all bases.
+/// Panics if the lengths differ.
+pub fn multi_pow<F: Field>(bases: &[F], exps: &[&[u64]]) -> F {
+    assert_eq!(bases.len(), exps.len(), "one exponent per base");
+
+    let bits = exps.iter().map(|exp| bit_len(exp)).max().unwrap_or(0);
+    let k = match bases.len() {
+        0..=2 => 2,
+        3..=16 => 3,
+        _ => 4,
+    }
+    .min(window_width(bits));
+    let tables: Vec<Vec<F>> = bases
+        .iter()
+        .map(|base| powers(base, 1 << k, F::one(), mul))
+        .collect();
+
+    let mut res = F::one();
+    for w in (0..bits.div_ceil(k as usize)).rev() {
+        for _ in 0..k {
+            res = res.square();
+        }
+        for (table, exp) in tables.iter().zip(exps) {
+            let digit = bits_at(exp, w * k as usize, k);
+            if digit != 0 {
+                res = res * &table[digit];
+            }
+        }
+    }
+    res
+}
+
+/// a^x * b^y by Shamir's trick, with a single precomputed product a * b
+pub fn shamir_pow<F: Field>(a: &F, x: &[u64], b: &F, y: &[u64]) -> F {
+    let ab = a.clone() * b;
+    let mut res = F::one();
+    for i in (0..bit_len(x).max(bit_len(y))).rev() {
+        res = res.square();
+        match (bit(x, i), bit(y, i)) {
+            (true, true) => res = res * &ab,
+            (true, false) => res = res * a,
+            (false, true) => res = res * b,
+            (false, false) => {}
+        }
+    }
+    res
+}
+
+/// Precomputed powers of a base that is raised to many exponents, e.g. a
+/// generator
+///
+/// Holds base^(d * 2^(k i)) for every k-bit digit d and window i, so that
+/// base^exp is a product of one entry per window.
+#[derive(Debug, Clone)]
+pub struct FixedBase<F: Field> {
+    /// table[i][d] = base^(d * 2^(k i))
+    table: Vec<Vec<F>>,
+    k: u32,
+}
+
+impl<F: Field> FixedBase<F> {
+    /// Table for exponents of up to `bits` bits, with 4-bit windows
+    pub fn new(base: &F, bits: usize) -> Self {
+        Self::with_window(base, bits, 4)
+    }
+
+    /// Table for exponents of up to `bits` bits, with k-bit windows: 2^k
+    /// elements per window, ceil(bits / k) windows. Panics unless
+    /// 1 <= k <= 16.
+    pub fn with_window(base: &F, bits: usize, k: u32) -> Self {
+        assert!((1..=16).contains(&k), "window width out of range");
+
+        let mut table = Vec::with_capacity(bits.div_ceil(k as usize));
+        let mut window_base = base.clone();
+        for _ in 0..bits.div_ceil(k as usize) {
+            let row = powers(&window_base, 1 << k, F::one(), mul);
+            // x^(2^k) = x^(2^k - 1) * x for x = base^(2^(k i))
+            window_base = row[(1 << k) - 1].clone() * &window_base;
+            table.push(row);
+        }
+        Self { table, k }
+    }
+
+    /// Largest supported exponent size, in bits
+    pub fn bits(&self) -> usize {
+        self.table.len() * self.k as usize
+    }
+
+    /// base^exp. Panics if exp has more than `bits()` bits.
+    pub fn pow(&self, exp: &[u64]) -> F {
+        assert!(
+            bit_len(exp) <= self.bits(),
+            "exponent too large for the table"
+        );
+
+        let mut res = F::one();
+        for (i, row) in self.table.iter().enumerate() {
+            let digit = bits_at(exp, i * self.k as usize, self.k);
+            if digit != 0 {
+                res = res * &row[digit];
+            }
+        }
+        res
+    }
+}
+
+/////////////////////////////////////////////
+/////////////// Generic Over the Product
+/////////////////////////////////////////////
+// Written over any associative product with an identity, so that element
+// types with a faster variable-time product than `*` (`U64FieldElement`)
+// can use them as well.
+
+fn mul<F: Field>(a: &F, b: &F) -> F {
+    a.clone() * b
+}
+
+/// base^0, .., base^(n-1)
+fn powers<T: Clone>(base: &T, n: usize, one: T, mul: impl Fn(&T, &T) -> T) -> Vec<T> {
+    let mut powers = Vec::with_capacity(n);
+    powers.push(one);
+    for i in 1..n {
+        powers.push(mul(&powers[i - 1], base));
+    }
+    powers
+}
+
+fn k_ary<T: Clone>(base: &T, exp: &[u64], k: u32, one: T, mul: impl Fn(&T, &T) -> T) -> T {
+    assert!((1..=16).contains(&k), "window width out of range");
+
+    let table = powers(base, 1 << k, one.clone(), &mul);
+    let mut res = one;
+    for w in (0..bit_len(exp).div_ceil(k as usize)).rev() {
+        for _ in 0..k {
+            res = mul(&res, &res);
+        }
+        let digit = bits_at(exp, w * k as usize, k);
+        if digit != 0 {
+            res = mul(&res, &table[digit]);
+        }
+    }
+    res
+}
+
+pub(crate) fn sliding_window<T: Clone>(
+    base: &T,
+    exp: &[u64],
+    one: T,
+    mul: impl Fn(&T, &T) -> T,
+) -> T {
+    let bits = bit_len(exp);
+    let k = window_width(bits);
+
+    // base, base^3, .., base^(2^k - 1)
+    let base_sq = mul(base, base);
+    let mut odd = Vec::with_capacity(1 << (k - 1));
+    odd.push(base.clone());
+    for i in 1..1 << (k - 1) {
+        odd.push(mul(&odd[i - 1], &base_sq));
+    }
+
+    let mut res = one;
+    let mut i = bits;
+    while i > 0 {
+        if !bit(exp, i - 1) {
+            res = mul(&res, &res);
+            i -= 1;
+            continue;
+        }
+        // the longest window of at most k bits from bit i-1 down that ends
+        // in a one
+        let mut low = i.saturating_sub(k as usize);
+        while !bit(exp, low) {
+            low += 1;
+        }
+        for _ in low..i {
+            res = mul(&res, &res);
+        }
+        let digit = bits_at(exp, low, (i - low) as u32);
+        res = mul(&res, &odd[digit >> 1]);
+        i = low;
+    }
+    res
+}
+
+/// Sliding window width for an exponent of `bits` bits, where the
+/// precomputation starts to pay off
+fn window_width(bits: usize) -> u32 {
+    match bits {
+        0..=23 => 1,
+        24..=79 => 3,
+        80..=239 => 4,
+        240..=671 => 5,
+        _ => 6,
+    }
+}
+
+/// Bit length of a little-endian limb slice, 0 for zero
+fn bit_len(exp: &[u64]) -> usize {
+    match exp.iter().rposition(|limb| *limb != 0) {
+        Some(i) => 64 * i + 64 - exp[i].leading_zeros() as usize,
+        None => 0,
+    }
+}
+
+fn bit(exp: &[u64], i: usize) -> bool {
+    exp.get(i / 64)
+        .is_some_and(|limb| (limb >> (i % 64)) & 1 == 1)
+}
+
+/// The k <= 16 bits of exp starting at bit `start`, beyond the end reading
+/// as zeros
+fn bits_at(exp: &[u64], start: usize, k: u32) -> usize {
+    let limb = |j: usize| exp.get(j).copied().unwrap_or(0) as u128;
+    let (j, shift) = (start / 64, start % 64);
+    let window = ((limb(j + 1) << 64) | limb(j)) >> shift;
+    (window as usize) & ((1 << k) - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bn254::{Bn254Fq12, Bn254Fr};
+    use crate::fp12::Fp12;
+    use crate::p_u256::U256FieldElement;
+    use crate::p_u64::U64FieldElement;
+    use primitive_types::U256;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    test_prime_field!(P97, U256([97, 0, 0, 0]));
+
+    type Fr = U256FieldElement<Bn254Fr>;
+
+    /// Right-to-left square & multiply, the reference
+    fn naive<F: Field>(base: &F, exp: &[u64]) -> F {
+        let (mut res, mut base) = (F::one(), base.clone());
+        for limb in exp {
+            for i in 0..64 {
+                if (limb >> i) & 1 == 1 {
+                    res = res * &base;
+                }
+                base = base.square();
+            }
+        }
+        res
+    }
+
+    fn exponents(rng: &mut StdRng) -> Vec<Vec<u64>> {
+        let mut exps = vec![
+            vec![],
+            vec![0],
+            vec![0, 0, 0],
+            vec![1],
+            vec![2],
+            vec![u64::MAX],
+            vec![0, 1],
+            vec![u64::MAX; 4],
+            vec![1 << 63, 0, 0, 1 << 63],
+        ];
+        for limbs in 1..=5 {
+            exps.push((0..limbs).map(|_| rng.gen()).collect());
+            // sparse
+            exps.push(
+                (0..limbs)
+                    .map(|_| rng.gen::<u64>() & rng.gen::<u64>() & rng.gen::<u64>())
+                    .collect(),
+            );
+        }
+        exps
+    }
+
+    #[test]
+    fn single_base() {
+        let mut rng = StdRng::seed_from_u64(20);
+        for base in [Fr::ZERO, Fr::ONE, Fr::random(&mut rng)] {
+            for exp in exponents(&mut rng) {
+                let expected = naive(&base, &exp);
+                assert_eq!(pow_sliding_window(&base, &exp), expected);
+                assert_eq!(base.pow(&exp), expected);
+                for k in [1, 2, 3, 4, 5, 8, 16] {
+                    assert_eq!(pow_k_ary(&base, &exp, k), expected, "k = {}", k);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn identity_semantics() {
+        type F = U64FieldElement<P97>;
+        for pow in [pow_sliding_window::<F>, |b: &F, e: &[u64]| {
+            pow_k_ary(b, e, 4)
+        }] {
+            assert_eq!(pow(&F::ZERO, &[]), F::ONE);
+            assert_eq!(pow(&F::ZERO, &[0]), F::ONE);
+            assert_eq!(pow(&F::ZERO, &[96]), F::ZERO);
+            assert_eq!(pow(&F::new(5), &[0, 0]), F::ONE);
+            assert_eq!(pow(&F::new(5), &[96]), F::ONE);
+        }
+        assert_eq!(multi_pow::<F>(&[], &[]), F::ONE);
+        assert_eq!(FixedBase::new(&F::ZERO, 8).pow(&[0]), F::ONE);
+        assert_eq!(shamir_pow(&F::ZERO, &[], &F::ZERO, &[0]), F::ONE);
+    }
+
+    #[test]
+    fn fixed_base() {
+        let mut rng = StdRng::seed_from_u64(21);
+        let base = Fr::random(&mut rng);
+        for k in [1, 3, 4, 7] {
+            let table = FixedBase::with_window(&base, 256, k);
+            assert!(table.bits() >= 256);
+            for exp in exponents(&mut rng)
+                .into_iter()
+                .filter(|e| bit_len(e) <= 256)
+            {
+                assert_eq!(table.pow(&exp), naive(&base, &exp), "k = {}", k);
+            }
+        }
+
+        // extension fields too
+        let base = Fp12::<Bn254Fq12>::random(&mut rng);
+        let exp = [rng.gen(), rng.gen()];
+        assert_eq!(FixedBase::new(&base, 128).pow(&exp), naive(&base, &exp));
+    }
+
+    #[test]
+    #[should_panic(expected = "exponent too large for the table")]
+    fn fixed_base_too_large() {
+        FixedBase::new(&Fr::ONE, 64).pow(&[0, 1]);
+    }
+
+    #[test]
+    fn multi_exponentiation() {
+        let mut rng = StdRng::seed_from_u64(22);
+        for n in [1, 2, 3, 8, 20] {
+            let bases: Vec<Fr> = (0..n).map(|_| Fr::random(&mut rng)).collect();
+            let exps: Vec<Vec<u64>> = (0..n)
+                .map(|i| (0..=i % 4).map(|_| rng.gen()).collect())
+                .collect();
+            let exps: Vec<&[u64]> = exps.iter().map(|e| e.as_slice()).collect();
+
+            let expected = bases
+                .iter()
+                .zip(&exps)
+                .fold(Fr::ONE, |acc, (b, e)| acc * naive(b, e));
+            assert_eq!(multi_pow(&bases, &exps), expected, "n = {}", n);
+            if n == 2 {
+                assert_eq!(shamir_pow(&bases[0], exps[0], &bases[1], exps[1]), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn limb_helpers() {
+        let exp = [0x8000_0000_0000_0001, 0b1011];
+        assert_eq!(bit_len(&exp), 68);
+        assert_eq!(bit_len(&[0, 0]), 0);
+        assert!(bit(&exp, 0) && bit(&exp, 63) && bit(&exp, 64) && !bit(&exp, 66));
+        assert!(!bit(&exp, 1000));
+        assert_eq!(bits_at(&exp, 62, 4), 0b1110);
+        assert_eq!(bits_at(&exp, 64, 16), 0b1011);
+        assert_eq!(bits_at(&exp, 200, 16), 0);
+    }
+}
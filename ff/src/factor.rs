@@ -0,0 +1,463 @@
+//! Factorizations of p - 1, which the multiplicative group structure of
+//! GF(p) depends on: element orders, primitive roots, Pohlig-Hellman.
+//!
+//! Anything below 2^64 is factored on the spot, by trial division and then
+//! Pollard's rho with Brent's cycle detection, with a deterministic
+//! Miller-Rabin deciding when to stop. For 256-bit p, p - 1 usually has
+//! large prime factors that are out of reach here, so the well-known primes
+//! come with their factorization and other primes only work when p - 1 is
+//! a small-prime multiple of a prime (or of something below 2^64).
+use alloc::vec::Vec;
+use num_bigint::prime::probably_prime;
+use num_bigint::BigUint;
+use primitive_types::U256;
+
+use crate::bn254::{Bn254Fq, Bn254Fr};
+use crate::errors::{Error, Result};
+use crate::params::{u256_from_hex, PrimeFieldParams, PRIMALITY_ROUNDS};
+use crate::primes::Bls12381Fr;
+
+/// Trial division bound of `factor_p_minus_1`
+const TRIAL_BOUND: u64 = 1 << 16;
+
+/// (p, factorization of p - 1) for the primes of the popular curves, as
+/// found by a general purpose factoring tool
+const KNOWN_FACTORIZATIONS: &[(U256, &[(U256, u32)])] = &[
+    (
+        Bn254Fq::MODULUS,
+        &[
+            (U256([2, 0, 0, 0]), 1),
+            (U256([3, 0, 0, 0]), 2),
+            (U256([13, 0, 0, 0]), 1),
+            (U256([29, 0, 0, 0]), 1),
+            (U256([67, 0, 0, 0]), 1),
+            (U256([229, 0, 0, 0]), 1),
+            (U256([311, 0, 0, 0]), 1),
+            (U256([983, 0, 0, 0]), 1),
+            (U256([11003, 0, 0, 0]), 1),
+            (U256([405928799, 0, 0, 0]), 1),
+            (u256_from_hex("2ab6cbdc9"), 1),
+            (u256_from_hex("2775dec4d2fd445d02a32aa0f59b66aa11"), 1),
+        ],
+    ),
+    (
+        Bn254Fr::MODULUS,
+        &[
+            (U256([2, 0, 0, 0]), 28),
+            (U256([3, 0, 0, 0]), 2),
+            (U256([13, 0, 0, 0]), 1),
+            (U256([29, 0, 0, 0]), 1),
+            (U256([983, 0, 0, 0]), 1),
+            (U256([11003, 0, 0, 0]), 1),
+            (U256([237073, 0, 0, 0]), 1),
+            (U256([405928799, 0, 0, 0]), 1),
+            (u256_from_hex("5ef9dea338eb5"), 1),
+            (u256_from_hex("2ca6487cfcd795e8729527e1"), 1),
+        ],
+    ),
+    (
+        Bls12381Fr::MODULUS,
+        &[
+            (U256([2, 0, 0, 0]), 32),
+            (U256([3, 0, 0, 0]), 1),
+            (U256([11, 0, 0, 0]), 1),
+            (U256([19, 0, 0, 0]), 1),
+            (U256([10177, 0, 0, 0]), 1),
+            (U256([125527, 0, 0, 0]), 1),
+            (U256([859267, 0, 0, 0]), 1),
+            (U256([906349, 0, 0, 0]), 2),
+            (U256([2508409, 0, 0, 0]), 1),
+            (U256([2529403, 0, 0, 0]), 1),
+            (U256([52437899, 0, 0, 0]), 1),
+            (U256([254760293, 0, 0, 0]), 2),
+        ],
+    ),
+    // secp256k1 base field
+    (
+        u256_from_hex("fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f"),
+        &[
+            (U256([2, 0, 0, 0]), 1),
+            (U256([3, 0, 0, 0]), 1),
+            (U256([7, 0, 0, 0]), 1),
+            (U256([13441, 0, 0, 0]), 1),
+            (
+                u256_from_hex("1db8260e5e3b460a46a0088fccf6a3a5936d75d89a776d4c0da4f338aafb"),
+                1,
+            ),
+        ],
+    ),
+    // secp256k1 group order
+    (
+        u256_from_hex("fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141"),
+        &[
+            (U256([2, 0, 0, 0]), 6),
+            (U256([3, 0, 0, 0]), 1),
+            (U256([149, 0, 0, 0]), 1),
+            (U256([631, 0, 0, 0]), 1),
+            (u256_from_hex("17d6cfb8ee30c51"), 1),
+            (u256_from_hex("978c6f353c3889a79"), 1),
+            (u256_from_hex("10dbff26eab8198050172ee03275"), 1),
+        ],
+    ),
+    // P-256 base field
+    (
+        u256_from_hex("ffffffff00000001000000000000000000000000ffffffffffffffffffffffff"),
+        &[
+            (U256([2, 0, 0, 0]), 1),
+            (U256([3, 0, 0, 0]), 1),
+            (U256([5, 0, 0, 0]), 2),
+            (U256([17, 0, 0, 0]), 1),
+            (U256([257, 0, 0, 0]), 1),
+            (U256([641, 0, 0, 0]), 1),
+            (U256([1531, 0, 0, 0]), 1),
+            (U256([65537, 0, 0, 0]), 1),
+            (U256([490463, 0, 0, 0]), 1),
+            (U256([6700417, 0, 0, 0]), 1),
+            (u256_from_hex("926d1276e41fae13fdda5f78edb7802a76951509"), 1),
+        ],
+    ),
+    // P-256 group order
+    (
+        u256_from_hex("ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551"),
+        &[
+            (U256([2, 0, 0, 0]), 4),
+            (U256([3, 0, 0, 0]), 1),
+            (U256([71, 0, 0, 0]), 1),
+            (U256([131, 0, 0, 0]), 1),
+            (U256([373, 0, 0, 0]), 1),
+            (U256([3407, 0, 0, 0]), 1),
+            (U256([17449, 0, 0, 0]), 1),
+            (U256([38189, 0, 0, 0]), 1),
+            (U256([187019741, 0, 0, 0]), 1),
+            (U256([622491383, 0, 0, 0]), 1),
+            (u256_from_hex("e95f681f97"), 1),
+            (u256_from_hex("87b23e9d09d3e637b2aa341"), 1),
+        ],
+    ),
+    // 2^255 - 19
+    (
+        u256_from_hex("7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffed"),
+        &[
+            (U256([2, 0, 0, 0]), 2),
+            (U256([3, 0, 0, 0]), 1),
+            (U256([65147, 0, 0, 0]), 1),
+            (
+                u256_from_hex("abaf8c6b094fd0f32c2ccabab864dbecd99144679c1adf804898fb2042b"),
+                1,
+            ),
+        ],
+    ),
+];
+
+/// (prime, exponent) pairs of p - 1 for a prime p, in increasing order
+///
+/// Looks p up among the well-known primes first. Otherwise small factors
+/// are divided out and the cofactor must be prime or below 2^64, else
+/// `Error::UnknownFactorization`.
+pub fn factor_p_minus_1(p: U256) -> Result<Vec<(U256, u32)>> {
+    if let Some((_, factors)) = KNOWN_FACTORIZATIONS.iter().find(|(q, _)| *q == p) {
+        return Ok(factors.to_vec());
+    }
+
+    let mut n = p - 1;
+    if n <= U256::from(u64::MAX) {
+        return Ok(factor_u64(n.low_u64())
+            .into_iter()
+            .map(|(q, e)| (U256::from(q), e))
+            .collect());
+    }
+
+    let mut factors = Vec::new();
+    let mut q = 2u64;
+    while q < TRIAL_BOUND {
+        let mut e = 0;
+        while (n % q).is_zero() {
+            n /= q;
+            e += 1;
+        }
+        if e > 0 {
+            factors.push((U256::from(q), e));
+        }
+        q += 1 + (q > 2) as u64;
+    }
+
+    if n <= U256::from(u64::MAX) {
+        factors.extend(
+            factor_u64(n.low_u64())
+                .into_iter()
+                .map(|(q, e)| (U256::from(q), e)),
+        );
+    } else if is_prime_u256(&n) {
+        factors.push((n, 1));
+    } else {
+        return Err(Error::UnknownFactorization);
+    }
+    Ok(factors)
+}
+
+/// (prime, exponent) pairs of n, in increasing order. Empty for 0 and 1.
+pub fn factor_u64(mut n: u64) -> Vec<(u64, u32)> {
+    let mut primes = Vec::new();
+    if n == 0 {
+        return Vec::new();
+    }
+
+    // trial division takes care of the small factors, which rho is bad at
+    let mut q = 2u64;
+    while q < 1 << 10 && q * q <= n {
+        while n.is_multiple_of(q) {
+            n /= q;
+            primes.push(q);
+        }
+        q += 1 + (q > 2) as u64;
+    }
+    if n > 1 {
+        split(n, &mut primes);
+    }
+
+    primes.sort_unstable();
+    let mut factors: Vec<(u64, u32)> = Vec::new();
+    for q in primes {
+        match factors.last_mut() {
+            Some((last, e)) if *last == q => *e += 1,
+            _ => factors.push((q, 1)),
+        }
+    }
+    factors
+}
+
+/// Distinct prime factors of n, in increasing order. Empty for 0 and 1.
+pub fn prime_factors(n: u64) -> Vec<u64> {
+    factor_u64(n).into_iter().map(|(q, _)| q).collect()
+}
+
+/// Pushes the prime factors of n > 1, with multiplicity
+fn split(n: u64, primes: &mut Vec<u64>) {
+    if is_prime_u64(n) {
+        primes.push(n);
+        return;
+    }
+    let d = rho_divisor(n);
+    split(d, primes);
+    split(n / d, primes);
+}
+
+/// A non-trivial divisor of an odd composite n, by Pollard's rho on
+/// x^2 + c with Brent's cycle detection, trying c = 1, 2, .. until the walk
+/// does not collapse mod n as a whole
+fn rho_divisor(n: u64) -> u64 {
+    // perfect squares make every walk collapse
+    let root = n.isqrt();
+    if root * root == n {
+        return root;
+    }
+
+    for c in 1.. {
+        let f = |x: u64| ((x as u128 * x as u128 + c as u128) % n as u128) as u64;
+        let (mut tortoise, mut hare) = (2u64, f(2));
+        let (mut power, mut lambda) = (1u64, 1u64);
+        loop {
+            let d = gcd(tortoise.abs_diff(hare), n);
+            if d == n {
+                break;
+            }
+            if d > 1 {
+                return d;
+            }
+            if power == lambda {
+                tortoise = hare;
+                power *= 2;
+                lambda = 0;
+            }
+            hare = f(hare);
+            lambda += 1;
+        }
+    }
+    unreachable!()
+}
+
+/// Miller-Rabin with the first 12 primes as bases, which is deterministic
+/// for n < 2^64
+pub fn is_prime_u64(n: u64) -> bool {
+    const BASES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+    if n < 2 {
+        return false;
+    }
+    for q in BASES {
+        if n.is_multiple_of(q) {
+            return n == q;
+        }
+    }
+
+    // n - 1 = 2^s * d
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+    BASES.iter().all(|&a| {
+        let mut x = pow_mod(a, d, n);
+        if x == 1 || x == n - 1 {
+            return true;
+        }
+        for _ in 1..s {
+            x = mul_mod(x, x, n);
+            if x == n - 1 {
+                return true;
+            }
+        }
+        false
+    })
+}
+
+fn is_prime_u256(n: &U256) -> bool {
+    let mut bytes = [0u8; 32];
+    n.to_big_endian(&mut bytes);
+    probably_prime(&BigUint::from_bytes_be(&bytes), PRIMALITY_ROUNDS)
+}
+
+pub(crate) fn mul_mod(a: u64, b: u64, n: u64) -> u64 {
+    ((a as u128 * b as u128) % n as u128) as u64
+}
+
+fn pow_mod(mut base: u64, mut exp: u64, n: u64) -> u64 {
+    let mut res = 1;
+    while exp != 0 {
+        if exp & 1 == 1 {
+            res = mul_mod(res, base, n);
+        }
+        base = mul_mod(base, base, n);
+        exp >>= 1;
+    }
+    res
+}
+
+pub(crate) fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primes::Goldilocks;
+
+    fn product_u64(factors: &[(u64, u32)]) -> u128 {
+        factors.iter().map(|(q, e)| (*q as u128).pow(*e)).product()
+    }
+
+    #[test]
+    fn primality_u64() {
+        let primes = [
+            2,
+            3,
+            37,
+            41,
+            65537,
+            4294967291,
+            0xffff_ffff_0000_0001,
+            (1 << 61) - 1,
+        ];
+        let composites = [0, 1, 4, 561, 3215031751, 4294967297, 0xffff_ffff_ffff_ffff];
+        assert!(primes.iter().all(|p| is_prime_u64(*p)));
+        assert!(!composites.iter().any(|n| is_prime_u64(*n)));
+    }
+
+    #[test]
+    fn factor_small() {
+        assert_eq!(factor_u64(0), vec![]);
+        assert_eq!(factor_u64(1), vec![]);
+        assert_eq!(factor_u64(2), vec![(2, 1)]);
+        assert_eq!(factor_u64(360), vec![(2, 3), (3, 2), (5, 1)]);
+        assert_eq!(factor_u64(1 << 63), vec![(2, 63)]);
+        assert_eq!(prime_factors(1), vec![]);
+        assert_eq!(prime_factors(360), vec![2, 3, 5]);
+    }
+
+    #[test]
+    fn factor_large() {
+        for (n, factors) in [
+            // F5 and F6
+            (4294967297, vec![(641, 1), (6700417, 1)]),
+            (
+                u64::MAX,
+                vec![
+                    (3, 1),
+                    (5, 1),
+                    (17, 1),
+                    (257, 1),
+                    (641, 1),
+                    (65537, 1),
+                    (6700417, 1),
+                ],
+            ),
+            // two 32-bit primes, out of reach of trial division
+            (
+                4294967291 * 4294967279,
+                vec![(4294967279, 1), (4294967291, 1)],
+            ),
+            (4294967291 * 4294967291, vec![(4294967291, 2)]),
+            (
+                (1 << 61) - 2,
+                vec![
+                    (2, 1),
+                    (3, 2),
+                    (5, 2),
+                    (7, 1),
+                    (11, 1),
+                    (13, 1),
+                    (31, 1),
+                    (41, 1),
+                    (61, 1),
+                    (151, 1),
+                    (331, 1),
+                    (1321, 1),
+                ],
+            ),
+        ] {
+            assert_eq!(factor_u64(n), factors, "{}", n);
+            assert_eq!(product_u64(&factors), n as u128);
+        }
+    }
+
+    #[test]
+    fn known_factorizations() {
+        for (p, factors) in KNOWN_FACTORIZATIONS {
+            let product = factors.iter().fold(U256::one(), |acc, (q, e)| {
+                match *q <= U256::from(u64::MAX) {
+                    true => assert!(is_prime_u64(q.low_u64()), "{:x}", q),
+                    false => assert!(is_prime_u256(q), "{:x}", q),
+                }
+                acc * q.pow(U256::from(*e))
+            });
+            assert_eq!(product, p - 1, "{:x}", p);
+        }
+    }
+
+    #[test]
+    fn factor_p_minus_1_without_cache() {
+        assert_eq!(
+            factor_p_minus_1(Goldilocks::MODULUS).unwrap(),
+            vec![
+                (U256::from(2), 32),
+                (U256::from(3), 1),
+                (U256::from(5), 1),
+                (U256::from(17), 1),
+                (U256::from(257), 1),
+                (U256::from(65537), 1)
+            ]
+        );
+
+        // p - 1 = 2^4 * 5 * q with q of 231 bits
+        let p = u256_from_hex("1bbd735b002ff27599d4ad373b0e9b96704d931bff0358d7e4689b87a2d1");
+        let q = u256_from_hex("58c4a45666ffd4ab85dbc3e3f02ebeae342b705996711c4c7481f1b209");
+        assert_eq!(
+            factor_p_minus_1(p).unwrap(),
+            vec![(U256::from(2), 4), (U256::from(5), 1), (q, 1)]
+        );
+
+        // p - 1 has a composite cofactor of 200 bits
+        let p = u256_from_hex("90e32e82394553538cdece75921ebce6139f711060c73494ed192da3c82ad589");
+        assert!(is_prime_u256(&p));
+        assert_eq!(factor_p_minus_1(p), Err(Error::UnknownFactorization));
+    }
+}
@@ -0,0 +1,326 @@
+use alloc::vec::Vec;
+use core::fmt::Debug;
+use core::ops::{Add, Div, Mul, Neg, Sub};
+
+use rand_core::RngCore;
+use subtle::{Choice, ConditionallySelectable};
+
+use crate::errors::Result;
+use crate::parallel::{self, MaybeSync};
+use crate::{exponentiation, roots_of_unity, sqrt};
+
+/// Arithmetic every field element type in this crate provides, so that
+/// polynomials, curves etc. can be written once over any of them.
+///
+/// Operators are available both by value and with a borrowed right-hand side.
+/// Division panics on a zero divisor, use `inv` to handle that case.
+pub trait Field:
+    Sized
+    + Clone
+    + Eq
+    + Debug
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+    + for<'a> Add<&'a Self, Output = Self>
+    + for<'a> Sub<&'a Self, Output = Self>
+    + for<'a> Mul<&'a Self, Output = Self>
+    + for<'a> Div<&'a Self, Output = Self>
+{
+    fn zero() -> Self;
+
+    fn one() -> Self;
+
+    fn is_zero(&self) -> bool;
+
+    fn double(&self) -> Self;
+
+    fn square(&self) -> Self;
+
+    /// Multiplicative inverse, `None` for zero
+    fn inv(&self) -> Option<Self>;
+
+    /// self^exp, with the exponent given as little-endian u64 limbs
+    ///
+    /// Sliding window, so exponents of any size work (e.g. (p^2 - 1) / 2
+    /// for an extension field). x^0 = 1 for every x. Variable time in
+    /// `exp`, the prime field types have a `pow_ct` for secret exponents.
+    fn pow(&self, exp: &[u64]) -> Self {
+        exponentiation::pow_sliding_window(self, exp)
+    }
+
+    /// sum a[i] * b[i]. Panics if the lengths differ.
+    ///
+    /// `U256FieldElement` and `U64FieldElement` add up the products
+    /// unreduced and reduce once at the end, which makes inner products
+    /// cheaper than the obvious fold.
+    fn sum_of_products(a: &[Self], b: &[Self]) -> Self {
+        assert_eq!(a.len(), b.len(), "slices of different lengths");
+        a.iter()
+            .zip(b)
+            .fold(Self::zero(), |acc, (x, y)| acc + x.clone() * y)
+    }
+
+    /// Characteristic of the field as little-endian u64 limbs
+    fn characteristic() -> Vec<u64>;
+
+    /// Canonical fixed-width big-endian encoding
+    fn to_bytes(&self) -> Vec<u8>;
+
+    /// Inverse of `to_bytes`. `None` on a wrong length or a non-canonical
+    /// encoding, i.e. a value that is not fully reduced.
+    fn from_bytes(bytes: &[u8]) -> Option<Self>;
+
+    /// Uniformly random element, without modulo bias
+    fn random<R: RngCore + ?Sized>(rng: &mut R) -> Self;
+}
+
+/// A field of prime order, GF(p)
+pub trait PrimeField: Field {
+    /// Bit length of p
+    const NUM_BITS: u32;
+
+    fn from_u64(num: u64) -> Self;
+
+    /// Legendre symbol (self | p): 1 for a non-zero square, -1 for a
+    /// non-square and 0 for zero
+    fn legendre(&self) -> i8 {
+        sqrt::legendre(self)
+    }
+
+    fn is_square(&self) -> bool {
+        self.legendre() >= 0
+    }
+
+    /// A square root, `None` for a non-square. Which of the two roots is
+    /// returned is unspecified.
+    ///
+    /// Picks the algorithm from p: a single exponentiation for p = 3 mod 4
+    /// (e.g. secp256k1) and Atkin's for p = 5 mod 8, Tonelli-Shanks
+    /// otherwise.
+    fn sqrt(&self) -> Option<Self> {
+        sqrt::sqrt(self)
+    }
+
+    /// A generator of GF(p)*, `None` if the field parameters do not name one
+    fn multiplicative_generator() -> Option<Self>;
+
+    /// The largest s such that 2^s divides p - 1
+    fn two_adicity() -> u32 {
+        roots_of_unity::two_adicity::<Self>()
+    }
+
+    /// A primitive 2^two_adicity-th root of unity, the generator of the
+    /// largest radix-2 FFT domain
+    fn two_adic_root_of_unity() -> Self {
+        roots_of_unity::two_adic_root_of_unity()
+    }
+
+    /// A primitive n-th root of unity, `Error::NoRootOfUnity` unless n
+    /// divides p - 1
+    ///
+    /// The choice is consistent across orders: for n = 2^k the result is
+    /// the square of the one for 2^(k+1), and with a known generator g it is
+    /// always g^((p-1)/n). FFT domains of different sizes therefore nest.
+    fn root_of_unity(n: u64) -> Result<Self> {
+        roots_of_unity::root_of_unity(n)
+    }
+}
+
+/// Inverts every element of `elems` in place with a single field inversion,
+/// using Montgomery's trick: about 3n multiplications instead of n inversions.
+///
+/// Zeros have no inverse and are left as zero; they do not affect the
+/// other elements. With the `parallel` feature long inputs are inverted in
+/// independent chunks, one inversion each.
+pub fn batch_inverse<F: Field + MaybeSync>(elems: &mut [F]) {
+    parallel::for_each_chunk_mut(elems, batch_inverse_serial);
+}
+
+fn batch_inverse_serial<F: Field>(elems: &mut [F]) {
+    // prefix[i] = product of the non-zero elements before i
+    let mut prefix = Vec::with_capacity(elems.len());
+    let mut acc = F::one();
+    for x in elems.iter() {
+        prefix.push(acc.clone());
+        if !x.is_zero() {
+            acc = acc * x;
+        }
+    }
+
+    // Walking back, inv is the inverse of the product of the non-zero
+    // elements up to and including i
+    let mut inv = acc.inv().expect("product of non-zero elements");
+    for (x, prefix) in elems.iter_mut().zip(prefix).rev() {
+        if x.is_zero() {
+            continue;
+        }
+        let x_inv = prefix * &inv;
+        inv = inv * &*x;
+        *x = x_inv;
+    }
+}
+
+/// base^exp with a running time that depends only on `exp.len()`: every bit
+/// costs a squaring and a multiplication, and the product is selected rather
+/// than branched on
+pub(crate) fn pow_ct<F: Field + ConditionallySelectable>(base: &F, exp: &[u64]) -> F {
+    let mut res = F::one();
+    for limb in exp.iter().rev() {
+        for i in (0..64).rev() {
+            res = res.square();
+            let prod = res * base;
+            let bit = Choice::from(((limb >> i) & 1) as u8);
+            res = F::conditional_select(&res, &prod, bit);
+        }
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bn254::Bn254Fq12;
+    use crate::fp12::Fp12;
+    use crate::p_u256::U256FieldElement;
+    use crate::p_u64::U64FieldElement;
+    use primitive_types::U256;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    test_prime_field!(P97, U256([97, 0, 0, 0]));
+    test_prime_field!(P859, U256([859, 0, 0, 0]));
+
+    fn check_batch_inverse<F: PrimeField + MaybeSync>() {
+        let elems: Vec<F> = [0, 5, 1, 96, 0, 0, 42, 13, 0]
+            .into_iter()
+            .map(F::from_u64)
+            .collect();
+        let mut inverted = elems.clone();
+        batch_inverse(&mut inverted);
+
+        for (x, x_inv) in elems.iter().zip(&inverted) {
+            match x.inv() {
+                Some(inv) => assert_eq!(&inv, x_inv),
+                None => assert!(x_inv.is_zero()),
+            }
+        }
+
+        let mut empty: Vec<F> = vec![];
+        batch_inverse(&mut empty);
+        let mut zeros = vec![F::zero(); 3];
+        batch_inverse(&mut zeros);
+        assert!(zeros.iter().all(F::is_zero));
+    }
+
+    #[test]
+    fn batch_inverse_matches_inv() {
+        check_batch_inverse::<U256FieldElement<P97>>();
+        check_batch_inverse::<U64FieldElement<P97>>();
+    }
+
+    #[test]
+    fn batch_inverse_random_extension_elements() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let elems: Vec<Fp12<Bn254Fq12>> = (0..8).map(|_| Fp12::random(&mut rng)).collect();
+        let mut inverted = elems.clone();
+        batch_inverse(&mut inverted);
+        for (x, x_inv) in elems.iter().zip(inverted) {
+            assert_eq!(x_inv * x, Fp12::one());
+        }
+    }
+
+    /// Every residue is hit about equally often. Reducing 7 or 10 random bits
+    /// mod p instead would make the low residues twice as likely.
+    fn check_random<F: PrimeField>(p: u64) {
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut counts = vec![0u32; p as usize];
+        for _ in 0..200 * p {
+            let bytes = F::random(&mut rng).to_bytes();
+            let num = u64::from_be_bytes(bytes[bytes.len() - 8..].try_into().unwrap());
+            counts[num as usize] += 1;
+        }
+        assert!(
+            counts.iter().all(|&c| (130..270).contains(&c)),
+            "{:?}",
+            counts
+        );
+    }
+
+    #[test]
+    fn random_is_uniform() {
+        check_random::<U256FieldElement<P97>>(97);
+        check_random::<U64FieldElement<P97>>(97);
+        check_random::<U256FieldElement<P859>>(859);
+        check_random::<U64FieldElement<P859>>(859);
+    }
+
+    /// Against the naive fold, with the largest elements too so that the
+    /// wide accumulators overflow their lower part
+    fn check_sum_of_products<F>()
+    where
+        F: PrimeField + core::iter::Sum + core::iter::Product,
+        F: for<'a> core::iter::Sum<&'a F> + for<'a> core::iter::Product<&'a F>,
+        F: for<'a> core::ops::AddAssign<&'a F> + core::ops::SubAssign + core::ops::MulAssign,
+    {
+        let mut rng = StdRng::seed_from_u64(22);
+        let minus_one = F::zero() - F::one();
+        for n in [0, 1, 2, 7, 100] {
+            for extreme in [false, true] {
+                let sample = |rng: &mut StdRng| match extreme {
+                    true => minus_one.clone(),
+                    false => F::random(rng),
+                };
+                let a: Vec<F> = (0..n).map(|_| sample(&mut rng)).collect();
+                let b: Vec<F> = (0..n).map(|_| sample(&mut rng)).collect();
+                let naive = a
+                    .iter()
+                    .zip(&b)
+                    .fold(F::zero(), |acc, (x, y)| acc + x.clone() * y);
+                assert_eq!(F::sum_of_products(&a, &b), naive, "n = {}", n);
+            }
+        }
+
+        // assign operators and folds
+        let xs: Vec<F> = (0..5).map(|_| F::random(&mut rng)).collect();
+        let mut acc = F::zero();
+        let mut prod = F::one();
+        for x in &xs {
+            acc += x;
+            prod *= x.clone();
+        }
+        assert_eq!(xs.iter().sum::<F>(), acc);
+        assert_eq!(xs.clone().into_iter().sum::<F>(), acc);
+        assert_eq!(xs.iter().product::<F>(), prod);
+        assert_eq!(xs.into_iter().product::<F>(), prod);
+        acc -= acc.clone();
+        assert!(acc.is_zero());
+        assert_eq!(core::iter::empty::<F>().product::<F>(), F::one());
+    }
+
+    #[test]
+    fn sum_of_products_matches_fold() {
+        test_prime_field!(
+            Secp256k1P,
+            crate::params::u256_from_hex(
+                "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F"
+            )
+        );
+        test_prime_field!(P64, crate::params::u256_from_hex("FFFFFFFFFFFFFFC5"));
+        check_sum_of_products::<U256FieldElement<P97>>();
+        check_sum_of_products::<U256FieldElement<Secp256k1P>>();
+        check_sum_of_products::<U256FieldElement<crate::bn254::Bn254Fr>>();
+        check_sum_of_products::<U64FieldElement<P97>>();
+        check_sum_of_products::<U64FieldElement<P64>>();
+        check_sum_of_products::<crate::GoldilocksField>();
+    }
+
+    #[test]
+    #[should_panic(expected = "slices of different lengths")]
+    fn sum_of_products_length_mismatch() {
+        type F = U256FieldElement<P97>;
+        F::sum_of_products(&[F::ONE], &[]);
+    }
+}
@@ -0,0 +1,381 @@
+use alloc::vec::Vec;
+use core::ops::{Add, Div, Mul, Neg, Sub};
+use rand_core::RngCore;
+use zeroize::Zeroize;
+
+use crate::field::Field;
+use crate::fp2::Fp2;
+use crate::fp6::{Fp6, Fp6Params};
+
+type Fp2Of<P> = Fp2<<<P as Fp12Params>::Fp6 as Fp6Params>::Fp2>;
+
+/// Describes the quadratic extension Fp12 = Fp6[w]/(w^2 - v)
+pub trait Fp12Params: 'static + Send + Sync + core::fmt::Debug {
+    type Fp6: Fp6Params;
+
+    /// ξ^((p^i - 1) / 6) for i = 0..12, so that w^(p^i) = FROBENIUS_COEFF_C1[i] * w
+    const FROBENIUS_COEFF_C1: [Fp2Of<Self>; 12];
+
+    /// (p^4 - p^2 + 1) / r as little-endian u64 limbs, r being the order of
+    /// the pairing groups
+    const FINAL_EXPONENT_HARD_PART: &'static [u64];
+}
+
+/// Element c0 + c1 * w of Fp12
+pub struct Fp12<P: Fp12Params> {
+    pub c0: Fp6<P::Fp6>,
+    pub c1: Fp6<P::Fp6>,
+}
+
+impl<P: Fp12Params> Clone for Fp12<P> {
+    fn clone(&self) -> Self {
+        Self::new(self.c0.clone(), self.c1.clone())
+    }
+}
+
+impl<P: Fp12Params> Zeroize for Fp12<P> {
+    fn zeroize(&mut self) {
+        self.c0.zeroize();
+        self.c1.zeroize();
+    }
+}
+
+impl<P: Fp12Params> core::fmt::Debug for Fp12<P> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Fp12")
+            .field("c0", &self.c0)
+            .field("c1", &self.c1)
+            .finish()
+    }
+}
+
+/////////////////////////////////////////////
+/////////////// Operator Overloads
+/////////////////////////////////////////////
+///// Equality
+impl<P: Fp12Params> Eq for Fp12<P> {}
+impl<P: Fp12Params> PartialEq for Fp12<P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.c0 == other.c0 && self.c1 == other.c1
+    }
+}
+///// Addition
+impl<P: Fp12Params> Add<&Fp12<P>> for &Fp12<P> {
+    type Output = Fp12<P>;
+    fn add(self, rhs: &Fp12<P>) -> Fp12<P> {
+        Fp12::new(&self.c0 + &rhs.c0, &self.c1 + &rhs.c1)
+    }
+}
+///// Subtraction
+impl<P: Fp12Params> Sub<&Fp12<P>> for &Fp12<P> {
+    type Output = Fp12<P>;
+    fn sub(self, rhs: &Fp12<P>) -> Fp12<P> {
+        Fp12::new(&self.c0 - &rhs.c0, &self.c1 - &rhs.c1)
+    }
+}
+///// Multiplication
+impl<P: Fp12Params> Mul<&Fp12<P>> for &Fp12<P> {
+    type Output = Fp12<P>;
+
+    /// Karatsuba, as for Fp2 with v in place of β
+    fn mul(self, rhs: &Fp12<P>) -> Fp12<P> {
+        let v0 = &self.c0 * &rhs.c0;
+        let v1 = &self.c1 * &rhs.c1;
+        let c1 = (&self.c0 + &self.c1) * (&rhs.c0 + &rhs.c1) - &v0 - &v1;
+        let c0 = v1.mul_by_nonresidue() + v0;
+        Fp12::new(c0, c1)
+    }
+}
+///// Division
+impl<P: Fp12Params> Div<&Fp12<P>> for &Fp12<P> {
+    type Output = Fp12<P>;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: &Fp12<P>) -> Fp12<P> {
+        self * rhs.inv().expect("division by zero")
+    }
+}
+///// Neg
+impl<P: Fp12Params> Neg for &Fp12<P> {
+    type Output = Fp12<P>;
+    fn neg(self) -> Fp12<P> {
+        Fp12::new(-&self.c0, -&self.c1)
+    }
+}
+forward_ops!(impl<P: Fp12Params> for Fp12<P>);
+
+/////////////////////////////////////////////
+/////////////// Field Requirements
+/////////////////////////////////////////////
+impl<P: Fp12Params> Fp12<P> {
+    pub const fn new(c0: Fp6<P::Fp6>, c1: Fp6<P::Fp6>) -> Self {
+        Self { c0, c1 }
+    }
+
+    /// a0 - a1w, which is x^(p^6). On the cyclotomic subgroup this is the
+    /// inverse.
+    pub fn conjugate(&self) -> Self {
+        Self::new(self.c0.clone(), -&self.c1)
+    }
+
+    /// x -> x^(p^power)
+    ///
+    /// Maps both Fp6 coefficients and sends w to w^(p^i) = ξ^((p^i - 1)/6) w
+    pub fn frobenius_map(&self, power: usize) -> Self {
+        Self::new(
+            self.c0.frobenius_map(power),
+            self.c1
+                .frobenius_map(power)
+                .mul_by_fp2(&P::FROBENIUS_COEFF_C1[power % 12]),
+        )
+    }
+
+    /// Multiplication by the sparse element (c0, 0, 0) + (c3, c4, 0)w,
+    /// the shape of a line evaluation on a D-type twist (e.g. BN254)
+    pub fn mul_by_034(&self, c0: &Fp2Of<P>, c3: &Fp2Of<P>, c4: &Fp2Of<P>) -> Self {
+        let a = self.c0.mul_by_fp2(c0);
+        let b = self.c1.mul_by_01(c3, c4);
+        let e = (&self.c0 + &self.c1).mul_by_01(&(c0 + c3), c4);
+
+        let c1 = e - &a - &b;
+        let c0 = b.mul_by_nonresidue() + a;
+        Self::new(c0, c1)
+    }
+
+    /// Multiplication by the sparse element (c0, c1, 0) + (0, c4, 0)w,
+    /// the shape of a line evaluation on an M-type twist (e.g. BLS12-381)
+    pub fn mul_by_014(&self, c0: &Fp2Of<P>, c1: &Fp2Of<P>, c4: &Fp2Of<P>) -> Self {
+        let aa = self.c0.mul_by_01(c0, c1);
+        let bb = self.c1.mul_by_1(c4);
+        let e = (&self.c0 + &self.c1).mul_by_01(c0, &(c1 + c4));
+
+        let c1 = e - &aa - &bb;
+        let c0 = bb.mul_by_nonresidue() + aa;
+        Self::new(c0, c1)
+    }
+
+    /// Squaring in the cyclotomic subgroup, i.e. for x with
+    /// x^(p^4 - p^2 + 1) = 1, which is where the easy part of the final
+    /// exponentiation lands.
+    ///
+    /// Granger & Scott, "Faster Squaring in the Cyclotomic Subgroup of Sixth
+    /// Degree Extensions". Seen as Fp4^3, each Fp4 coefficient z_i + z_j y is
+    /// squared with 2 multiplications in Fp2 and the result is rebuilt with
+    /// additions only. Gives wrong results outside the subgroup.
+    pub fn cyclotomic_square(&self) -> Self {
+        let nr = Fp6::<P::Fp6>::mul_fp2_by_nonresidue;
+        let fp4_square = |a: &Fp2Of<P>, b: &Fp2Of<P>| {
+            let ab = a * b;
+            let t0 = (a + b) * (nr(b) + a) - &ab - nr(&ab);
+            (t0, ab.double())
+        };
+        let triple_minus_double = |t: &Fp2Of<P>, z: &Fp2Of<P>| (t - z).double() + t;
+        let triple_plus_double = |t: &Fp2Of<P>, z: &Fp2Of<P>| (t + z).double() + t;
+
+        let (z0, z4, z3) = (&self.c0.c0, &self.c0.c1, &self.c0.c2);
+        let (z2, z1, z5) = (&self.c1.c0, &self.c1.c1, &self.c1.c2);
+
+        let (t0, t1) = fp4_square(z0, z1);
+        let (t2, t3) = fp4_square(z2, z3);
+        let (t4, t5) = fp4_square(z4, z5);
+
+        Self::new(
+            Fp6::new(
+                triple_minus_double(&t0, z0),
+                triple_minus_double(&t2, z4),
+                triple_minus_double(&t4, z3),
+            ),
+            Fp6::new(
+                triple_plus_double(&nr(&t5), z2),
+                triple_plus_double(&t1, z1),
+                triple_plus_double(&t3, z5),
+            ),
+        )
+    }
+
+    /// self^exp using cyclotomic squarings, so only for elements of the
+    /// cyclotomic subgroup. Exponent as little-endian u64 limbs.
+    pub fn cyclotomic_exp(&self, exp: &[u64]) -> Self {
+        let mut res = Self::one();
+        for limb in exp.iter().rev() {
+            for i in (0..64).rev() {
+                res = res.cyclotomic_square();
+                if (limb >> i) & 1 == 1 {
+                    res = res * self;
+                }
+            }
+        }
+        res
+    }
+
+    /// f^((p^6 - 1)(p^2 + 1)), which maps into the cyclotomic subgroup.
+    /// `None` for zero.
+    pub fn final_exponentiation_easy_part(&self) -> Option<Self> {
+        // f^(p^6 - 1) = conj(f) / f
+        let f1 = self.conjugate() * self.inv()?;
+        // f1^(p^2 + 1)
+        Some(f1.frobenius_map(2) * f1)
+    }
+
+    /// f^((p^4 - p^2 + 1) / r), for f in the cyclotomic subgroup
+    pub fn final_exponentiation_hard_part(&self) -> Self {
+        self.cyclotomic_exp(P::FINAL_EXPONENT_HARD_PART)
+    }
+
+    /// f^((p^12 - 1) / r), mapping the Miller loop output to the unique
+    /// representative of its class in the order r subgroup. `None` for zero.
+    pub fn final_exponentiation(&self) -> Option<Self> {
+        Some(
+            self.final_exponentiation_easy_part()?
+                .final_exponentiation_hard_part(),
+        )
+    }
+}
+
+impl<P: Fp12Params> Field for Fp12<P> {
+    fn zero() -> Self {
+        Self::new(Fp6::zero(), Fp6::zero())
+    }
+
+    fn one() -> Self {
+        Self::new(Fp6::one(), Fp6::zero())
+    }
+
+    fn is_zero(&self) -> bool {
+        self.c0.is_zero() && self.c1.is_zero()
+    }
+
+    fn double(&self) -> Self {
+        Self::new(self.c0.double(), self.c1.double())
+    }
+
+    /// Complex squaring: (a0 + a1w)^2 = (a0 + a1)(a0 + v a1) - a0a1 - v a0a1
+    /// + 2a0a1 w
+    fn square(&self) -> Self {
+        let ab = &self.c0 * &self.c1;
+        let c0 = (&self.c0 + &self.c1) * (&self.c0 + self.c1.mul_by_nonresidue())
+            - &ab
+            - ab.mul_by_nonresidue();
+        Self::new(c0, ab.double())
+    }
+
+    /// (a0 - a1w) / (a0^2 - v a1^2)
+    fn inv(&self) -> Option<Self> {
+        let t = self.c0.square() - self.c1.square().mul_by_nonresidue();
+        let t_inv = t.inv()?;
+        Some(Self::new(&self.c0 * &t_inv, -(&self.c1 * t_inv)))
+    }
+
+    fn characteristic() -> Vec<u64> {
+        Fp6::<P::Fp6>::characteristic()
+    }
+
+    /// c0 || c1
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.c0.to_bytes();
+        bytes.extend(self.c1.to_bytes());
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != 384 {
+            return None;
+        }
+        let c0 = Fp6::from_bytes(&bytes[..192])?;
+        let c1 = Fp6::from_bytes(&bytes[192..])?;
+        Some(Self::new(c0, c1))
+    }
+
+    fn random<R: RngCore + ?Sized>(rng: &mut R) -> Self {
+        Self::new(Fp6::random(rng), Fp6::random(rng))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bn254::tests::{biguint, limbs};
+    use crate::bn254::{Bn254Fq, Bn254Fq12, Bn254Fr};
+    use crate::fp6::tests::sample as fp6_sample;
+    use crate::params::PrimeFieldParams;
+
+    fn sample(seed: u64) -> Fp12<Bn254Fq12> {
+        Fp12::new(fp6_sample(seed), fp6_sample(seed + 100))
+    }
+
+    #[test]
+    fn mul_square_inv() {
+        let (a, b, c) = (sample(1), sample(2), sample(3));
+        assert_eq!((&a * &b) * &c, &a * (&b * &c));
+        assert_eq!(&a * (&b + &c), &a * &b + &a * &c);
+        assert_eq!(a.square(), &a * &a);
+        assert_eq!(&a * a.inv().unwrap(), Fp12::one());
+        assert_eq!(Fp12::<Bn254Fq12>::zero().inv(), None);
+        assert_eq!(Fp12::from_bytes(&a.to_bytes()), Some(a));
+    }
+
+    #[test]
+    fn sparse_mul() {
+        let a = sample(4);
+        let b = fp6_sample(5);
+        let (x, y, z) = (&b.c0, &b.c1, &b.c2);
+        let zero = Fp2::zero;
+
+        let dense = Fp12::new(
+            Fp6::new(x.clone(), zero(), zero()),
+            Fp6::new(y.clone(), z.clone(), zero()),
+        );
+        assert_eq!(a.mul_by_034(x, y, z), &a * dense);
+
+        let dense = Fp12::new(
+            Fp6::new(x.clone(), y.clone(), zero()),
+            Fp6::new(zero(), z.clone(), zero()),
+        );
+        assert_eq!(a.mul_by_014(x, y, z), &a * dense);
+    }
+
+    #[test]
+    fn frobenius_is_pth_power() {
+        let a = sample(6);
+        let mut pow = a.clone();
+        for i in 0..12 {
+            assert_eq!(a.frobenius_map(i), pow);
+            pow = pow.pow(&Bn254Fq::MODULUS.0);
+        }
+        assert_eq!(pow, a);
+        assert_eq!(a.frobenius_map(6), a.conjugate());
+    }
+
+    #[test]
+    fn cyclotomic_square() {
+        let f = sample(7).final_exponentiation_easy_part().unwrap();
+        assert_eq!(f.cyclotomic_square(), f.square());
+        assert_eq!(f.conjugate(), f.inv().unwrap());
+        assert_eq!(f.cyclotomic_exp(&[12345]), f.pow(&[12345]));
+    }
+
+    #[test]
+    fn hard_part_constant() {
+        let p = biguint(&Bn254Fq::MODULUS.0);
+        let r = biguint(&Bn254Fr::MODULUS.0);
+        let p2 = &p * &p;
+        let hard = (&p2 * &p2 - &p2 + 1u32) / &r;
+        assert_eq!(limbs(&hard), Bn254Fq12::FINAL_EXPONENT_HARD_PART);
+    }
+
+    #[test]
+    fn final_exponentiation() {
+        let f = sample(8);
+        let res = f.final_exponentiation().unwrap();
+
+        assert_ne!(res, Fp12::one());
+        assert_eq!(res.pow(&Bn254Fr::MODULUS.0), Fp12::one());
+
+        let p = biguint(&Bn254Fq::MODULUS.0);
+        let r = biguint(&Bn254Fr::MODULUS.0);
+        let p6 = &p * &p * &p * &p * &p * &p;
+        let exp = (&p6 * &p6 - 1u32) / r;
+        assert_eq!(f.pow(&limbs(&exp)), res);
+
+        assert_eq!(Fp12::<Bn254Fq12>::zero().final_exponentiation(), None);
+    }
+}
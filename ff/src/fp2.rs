@@ -0,0 +1,419 @@
+use alloc::vec::Vec;
+use core::ops::{Add, Div, Mul, Neg, Sub};
+use primitive_types::U512;
+use rand_core::RngCore;
+use zeroize::Zeroize;
+
+use crate::field::{Field, PrimeField};
+use crate::p_u256::Fp;
+use crate::params::PrimeFieldParams;
+
+/// Describes the quadratic extension Fp2 = Fp[u]/(u^2 - β)
+pub trait Fp2Params: 'static + Send + Sync + core::fmt::Debug {
+    type Fp: PrimeFieldParams;
+
+    /// β, which must be a quadratic non-residue in Fp for u^2 - β to be
+    /// irreducible
+    const NON_RESIDUE: Fp<Self::Fp>;
+}
+
+/// Element c0 + c1 * u of Fp2
+pub struct Fp2<P: Fp2Params> {
+    pub c0: Fp<P::Fp>,
+    pub c1: Fp<P::Fp>,
+}
+
+impl<P: Fp2Params> Clone for Fp2<P> {
+    fn clone(&self) -> Self {
+        Self::new(self.c0, self.c1)
+    }
+}
+
+impl<P: Fp2Params> Zeroize for Fp2<P> {
+    fn zeroize(&mut self) {
+        self.c0.zeroize();
+        self.c1.zeroize();
+    }
+}
+
+impl<P: Fp2Params> core::fmt::Debug for Fp2<P> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Fp2")
+            .field("c0", &self.c0.num())
+            .field("c1", &self.c1.num())
+            .finish()
+    }
+}
+
+/////////////////////////////////////////////
+/////////////// Operator Overloads
+/////////////////////////////////////////////
+///// Equality
+impl<P: Fp2Params> Eq for Fp2<P> {}
+impl<P: Fp2Params> PartialEq for Fp2<P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.c0 == other.c0 && self.c1 == other.c1
+    }
+}
+///// Addition
+impl<P: Fp2Params> Add<&Fp2<P>> for &Fp2<P> {
+    type Output = Fp2<P>;
+    fn add(self, rhs: &Fp2<P>) -> Fp2<P> {
+        Fp2::new(self.c0 + rhs.c0, self.c1 + rhs.c1)
+    }
+}
+///// Subtraction
+impl<P: Fp2Params> Sub<&Fp2<P>> for &Fp2<P> {
+    type Output = Fp2<P>;
+    fn sub(self, rhs: &Fp2<P>) -> Fp2<P> {
+        Fp2::new(self.c0 - rhs.c0, self.c1 - rhs.c1)
+    }
+}
+///// Multiplication
+impl<P: Fp2Params> Mul<&Fp2<P>> for &Fp2<P> {
+    type Output = Fp2<P>;
+
+    /// Karatsuba
+    ///
+    /// (a0 + a1u)(b0 + b1u) = a0b0 + a1b1β + (a0b1 + a1b0)u
+    ///
+    /// and the cross term is (a0 + a1)(b0 + b1) - a0b0 - a1b1, so three base
+    /// field multiplications instead of four.
+    fn mul(self, rhs: &Fp2<P>) -> Fp2<P> {
+        let v0 = self.c0 * rhs.c0;
+        let v1 = self.c1 * rhs.c1;
+        let c1 = (self.c0 + self.c1) * (rhs.c0 + rhs.c1) - v0 - v1;
+        let c0 = v0 + Fp2::<P>::mul_by_nonresidue(&v1);
+        Fp2::new(c0, c1)
+    }
+}
+///// Division
+impl<P: Fp2Params> Div<&Fp2<P>> for &Fp2<P> {
+    type Output = Fp2<P>;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: &Fp2<P>) -> Fp2<P> {
+        self * rhs.inv().expect("division by zero")
+    }
+}
+///// Neg
+impl<P: Fp2Params> Neg for &Fp2<P> {
+    type Output = Fp2<P>;
+    fn neg(self) -> Fp2<P> {
+        Fp2::new(-&self.c0, -&self.c1)
+    }
+}
+forward_ops!(impl<P: Fp2Params> for Fp2<P>);
+
+/////////////////////////////////////////////
+/////////////// Field Requirements
+/////////////////////////////////////////////
+impl<P: Fp2Params> Fp2<P> {
+    pub const fn new(c0: Fp<P::Fp>, c1: Fp<P::Fp>) -> Self {
+        Self { c0, c1 }
+    }
+
+    /// Embeds a base field element
+    pub fn from_base(c0: Fp<P::Fp>) -> Self {
+        Self::new(c0, Fp::zero())
+    }
+
+    /// a0 - a1u, the image of the other root of u^2 - β
+    pub fn conjugate(&self) -> Self {
+        Self::new(self.c0, -&self.c1)
+    }
+
+    /// (a0 + a1u)(a0 - a1u) = a0^2 - βa1^2, which lands in Fp
+    pub fn norm(&self) -> Fp<P::Fp> {
+        self.c0.square() - Self::mul_by_nonresidue(&self.c1.square())
+    }
+
+    pub fn mul_by_base(&self, by: &Fp<P::Fp>) -> Self {
+        Self::new(self.c0 * by, self.c1 * by)
+    }
+
+    /// x -> x^(p^power)
+    ///
+    /// u^p = β^((p-1)/2) u = -u since β is a non-residue, so odd powers are
+    /// conjugation and even powers are the identity
+    pub fn frobenius_map(&self, power: usize) -> Self {
+        match power % 2 {
+            0 => self.clone(),
+            _ => self.conjugate(),
+        }
+    }
+
+    /// x is a square in Fp2 iff its norm is a square in Fp
+    pub fn is_square(&self) -> bool {
+        self.norm().is_square()
+    }
+
+    /// Tonelli-Shanks over Fp2, with q = p^2
+    ///
+    /// q - 1 = 2^s * t with t odd. Starting from x = a^((t+1)/2) and
+    /// b = a^t we have x^2 = ab, and each round multiplies by a power of
+    /// c = z^t (z a non-residue) to halve the order of b until it is 1.
+    pub fn sqrt(&self) -> Option<Self> {
+        if self.is_zero() {
+            return Some(Self::zero());
+        }
+        if !self.is_square() {
+            return None;
+        }
+
+        let q_minus_1 = P::Fp::MODULUS.full_mul(P::Fp::MODULUS) - U512::one();
+        let s = q_minus_1.trailing_zeros() as usize;
+        let t = q_minus_1 >> s;
+
+        let z = Self::non_residue();
+        let mut m = s;
+        let mut c = z.pow(&t.0);
+        let mut x = self.pow(&((t + U512::one()) >> 1).0);
+        let mut b = self.pow(&t.0);
+
+        while b != Self::one() {
+            // least i such that b^(2^i) = 1
+            let mut i = 0;
+            let mut b2i = b.clone();
+            while b2i != Self::one() {
+                b2i = b2i.square();
+                i += 1;
+            }
+
+            let mut gs = c;
+            for _ in 0..(m - i - 1) {
+                gs = gs.square();
+            }
+            x = x * &gs;
+            c = gs.square();
+            b = b * &c;
+            m = i;
+        }
+
+        Some(x)
+    }
+
+    /// First element of the form k + u that is not a square
+    fn non_residue() -> Self {
+        let mut k = 0;
+        loop {
+            let z = Self::new(Fp::from_u64(k), Fp::one());
+            if !z.is_square() {
+                return z;
+            }
+            k += 1;
+        }
+    }
+
+    fn mul_by_nonresidue(x: &Fp<P::Fp>) -> Fp<P::Fp> {
+        x * P::NON_RESIDUE
+    }
+}
+
+impl<P: Fp2Params> Field for Fp2<P> {
+    fn zero() -> Self {
+        Self::new(Fp::zero(), Fp::zero())
+    }
+
+    fn one() -> Self {
+        Self::new(Fp::one(), Fp::zero())
+    }
+
+    fn is_zero(&self) -> bool {
+        self.c0.is_zero() && self.c1.is_zero()
+    }
+
+    fn double(&self) -> Self {
+        Self::new(self.c0.double(), self.c1.double())
+    }
+
+    /// Complex squaring
+    ///
+    /// (a0 + a1u)^2 = a0^2 + βa1^2 + 2a0a1u, and
+    /// (a0 + a1)(a0 + βa1) = a0^2 + βa1^2 + (1 + β)a0a1,
+    /// so two multiplications are enough.
+    fn square(&self) -> Self {
+        let v0 = self.c0 * self.c1;
+        let c0 = (self.c0 + self.c1) * (self.c0 + Self::mul_by_nonresidue(&self.c1))
+            - v0
+            - Self::mul_by_nonresidue(&v0);
+        Self::new(c0, v0.double())
+    }
+
+    /// x^-1 = conj(x) / norm(x), which only needs an inversion in Fp
+    fn inv(&self) -> Option<Self> {
+        let norm_inv = self.norm().inv()?;
+        Some(self.conjugate().mul_by_base(&norm_inv))
+    }
+
+    fn characteristic() -> Vec<u64> {
+        P::Fp::MODULUS.0.to_vec()
+    }
+
+    /// c0 || c1
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.c0.to_bytes();
+        bytes.extend(self.c1.to_bytes());
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != 64 {
+            return None;
+        }
+        let c0 = Fp::from_bytes(&bytes[..32])?;
+        let c1 = Fp::from_bytes(&bytes[32..])?;
+        Some(Self::new(c0, c1))
+    }
+
+    fn random<R: RngCore + ?Sized>(rng: &mut R) -> Self {
+        Self::new(Fp::random(rng), Fp::random(rng))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bn254::{Bn254Fq as Bn254Base, Bn254Fq2};
+    use crate::params::u256_from_hex;
+    use primitive_types::U256;
+
+    test_prime_field!(P7, U256([7, 0, 0, 0]));
+    test_prime_field!(P13, U256([13, 0, 0, 0]));
+
+    /// p = 3 mod 4, u^2 = -1
+    #[derive(Debug)]
+    struct F49;
+    impl Fp2Params for F49 {
+        type Fp = P7;
+        const NON_RESIDUE: Fp<P7> = Fp::from_u64(6);
+    }
+
+    /// p = 1 mod 4, u^2 = 2
+    #[derive(Debug)]
+    struct F169;
+    impl Fp2Params for F169 {
+        type Fp = P13;
+        const NON_RESIDUE: Fp<P13> = Fp::from_u64(2);
+    }
+
+    fn all<P: Fp2Params>(p: u64) -> Vec<Fp2<P>> {
+        let mut res = vec![];
+        for c0 in 0..p {
+            for c1 in 0..p {
+                res.push(Fp2::new(Fp::from_u64(c0), Fp::from_u64(c1)));
+            }
+        }
+        res
+    }
+
+    fn bn254_sample() -> (Fp2<Bn254Fq2>, Fp2<Bn254Fq2>) {
+        let a = Fp2::new(
+            Fp::new(u256_from_hex(
+                "1800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed",
+            )),
+            Fp::new(u256_from_hex(
+                "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2",
+            )),
+        );
+        let b = Fp2::new(
+            Fp::new(u256_from_hex(
+                "12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa",
+            )),
+            Fp::new(u256_from_hex(
+                "090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b",
+            )),
+        );
+        (a, b)
+    }
+
+    #[test]
+    fn params_are_prime() {
+        P7::check().unwrap();
+        P13::check().unwrap();
+        Bn254Base::check().unwrap();
+    }
+
+    #[test]
+    fn mul_matches_schoolbook() {
+        let (a, b) = bn254_sample();
+        // β = -1: (a0b0 - a1b1) + (a0b1 + a1b0)u
+        let c0 = a.c0 * b.c0 - a.c1 * b.c1;
+        let c1 = a.c0 * b.c1 + a.c1 * b.c0;
+        assert_eq!(&a * &b, Fp2::new(c0, c1));
+    }
+
+    #[test]
+    fn square_matches_mul() {
+        let (a, _) = bn254_sample();
+        assert_eq!(a.square(), &a * &a);
+        for x in all::<F169>(13) {
+            assert_eq!(x.square(), &x * &x);
+        }
+    }
+
+    #[test]
+    fn inv_and_div() {
+        let (a, b) = bn254_sample();
+        assert_eq!(&a * a.inv().unwrap(), Fp2::one());
+        assert_eq!((&a / &b) * &b, a);
+        assert_eq!(Fp2::<Bn254Fq2>::zero().inv(), None);
+    }
+
+    #[test]
+    fn norm_is_multiplicative() {
+        let (a, b) = bn254_sample();
+        assert_eq!((&a * &b).norm(), a.norm() * b.norm());
+        assert_eq!(Fp2::from_base(a.norm()), &a * a.conjugate());
+    }
+
+    #[test]
+    fn frobenius_is_pth_power() {
+        for x in all::<F49>(7) {
+            assert_eq!(x.frobenius_map(1), x.pow(&[7]));
+            assert_eq!(x.frobenius_map(2), x);
+        }
+        let (a, _) = bn254_sample();
+        assert_eq!(a.frobenius_map(1), a.pow(&Bn254Base::MODULUS.0));
+    }
+
+    #[test]
+    fn sqrt_all_elements() {
+        fn check<P: Fp2Params>(p: u64) {
+            let mut squares = 0;
+            for x in all::<P>(p) {
+                match x.sqrt() {
+                    Some(r) => {
+                        assert_eq!(r.square(), x);
+                        squares += 1;
+                    }
+                    None => assert!(!x.is_square()),
+                }
+            }
+            // zero plus half of the non-zero elements
+            assert_eq!(squares, (p * p - 1) / 2 + 1);
+        }
+        check::<F49>(7);
+        check::<F169>(13);
+    }
+
+    #[test]
+    fn sqrt_bn254() {
+        let (a, b) = bn254_sample();
+        let sq = a.square();
+        let r = sq.sqrt().unwrap();
+        assert!(r == a || r == -a);
+
+        let non_square = if b.is_square() {
+            b * Fp2::non_residue()
+        } else {
+            b
+        };
+        assert_eq!(non_square.sqrt(), None);
+    }
+
+    #[test]
+    fn bytes_roundtrip() {
+        let (a, _) = bn254_sample();
+        assert_eq!(Fp2::from_bytes(&a.to_bytes()), Some(a));
+    }
+}
@@ -0,0 +1,318 @@
+use alloc::vec::Vec;
+use core::ops::{Add, Div, Mul, Neg, Sub};
+
+use rand_core::RngCore;
+use zeroize::Zeroize;
+
+use crate::field::Field;
+use crate::fp2::{Fp2, Fp2Params};
+
+/// Describes the cubic extension Fp6 = Fp2[v]/(v^3 - ξ)
+pub trait Fp6Params: 'static + Send + Sync + core::fmt::Debug {
+    type Fp2: Fp2Params;
+
+    /// ξ, which must be neither a square nor a cube in Fp2
+    const NON_RESIDUE: Fp2<Self::Fp2>;
+
+    /// ξ^((p^i - 1) / 3) for i = 0..6, so that v^(p^i) = FROBENIUS_COEFF_C1[i] * v
+    const FROBENIUS_COEFF_C1: [Fp2<Self::Fp2>; 6];
+
+    /// ξ^((2p^i - 2) / 3) for i = 0..6, the same for v^2
+    const FROBENIUS_COEFF_C2: [Fp2<Self::Fp2>; 6];
+}
+
+/// Element c0 + c1 * v + c2 * v^2 of Fp6
+pub struct Fp6<P: Fp6Params> {
+    pub c0: Fp2<P::Fp2>,
+    pub c1: Fp2<P::Fp2>,
+    pub c2: Fp2<P::Fp2>,
+}
+
+impl<P: Fp6Params> Clone for Fp6<P> {
+    fn clone(&self) -> Self {
+        Self::new(self.c0.clone(), self.c1.clone(), self.c2.clone())
+    }
+}
+
+impl<P: Fp6Params> Zeroize for Fp6<P> {
+    fn zeroize(&mut self) {
+        self.c0.zeroize();
+        self.c1.zeroize();
+        self.c2.zeroize();
+    }
+}
+
+impl<P: Fp6Params> core::fmt::Debug for Fp6<P> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Fp6")
+            .field("c0", &self.c0)
+            .field("c1", &self.c1)
+            .field("c2", &self.c2)
+            .finish()
+    }
+}
+
+/////////////////////////////////////////////
+/////////////// Operator Overloads
+/////////////////////////////////////////////
+///// Equality
+impl<P: Fp6Params> Eq for Fp6<P> {}
+impl<P: Fp6Params> PartialEq for Fp6<P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.c0 == other.c0 && self.c1 == other.c1 && self.c2 == other.c2
+    }
+}
+///// Addition
+impl<P: Fp6Params> Add<&Fp6<P>> for &Fp6<P> {
+    type Output = Fp6<P>;
+    fn add(self, rhs: &Fp6<P>) -> Fp6<P> {
+        Fp6::new(&self.c0 + &rhs.c0, &self.c1 + &rhs.c1, &self.c2 + &rhs.c2)
+    }
+}
+///// Subtraction
+impl<P: Fp6Params> Sub<&Fp6<P>> for &Fp6<P> {
+    type Output = Fp6<P>;
+    fn sub(self, rhs: &Fp6<P>) -> Fp6<P> {
+        Fp6::new(&self.c0 - &rhs.c0, &self.c1 - &rhs.c1, &self.c2 - &rhs.c2)
+    }
+}
+///// Multiplication
+impl<P: Fp6Params> Mul<&Fp6<P>> for &Fp6<P> {
+    type Output = Fp6<P>;
+
+    /// Karatsuba for cubic extensions (Devegili et al.)
+    ///
+    /// With v_i = a_i * b_i, each cross term a_i b_j + a_j b_i is recovered
+    /// as (a_i + a_j)(b_i + b_j) - v_i - v_j, so 6 multiplications in Fp2
+    /// instead of 9. Terms of v^3 and v^4 wrap around multiplied by ξ.
+    fn mul(self, rhs: &Fp6<P>) -> Fp6<P> {
+        let (a0, a1, a2) = (&self.c0, &self.c1, &self.c2);
+        let (b0, b1, b2) = (&rhs.c0, &rhs.c1, &rhs.c2);
+
+        let v0 = a0 * b0;
+        let v1 = a1 * b1;
+        let v2 = a2 * b2;
+
+        let c0 = Fp6::<P>::mul_fp2_by_nonresidue(&((a1 + a2) * (b1 + b2) - &v1 - &v2)) + &v0;
+        let c1 = (a0 + a1) * (b0 + b1) - &v0 - &v1 + Fp6::<P>::mul_fp2_by_nonresidue(&v2);
+        let c2 = (a0 + a2) * (b0 + b2) - &v0 - &v2 + &v1;
+
+        Fp6::new(c0, c1, c2)
+    }
+}
+///// Division
+impl<P: Fp6Params> Div<&Fp6<P>> for &Fp6<P> {
+    type Output = Fp6<P>;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: &Fp6<P>) -> Fp6<P> {
+        self * rhs.inv().expect("division by zero")
+    }
+}
+///// Neg
+impl<P: Fp6Params> Neg for &Fp6<P> {
+    type Output = Fp6<P>;
+    fn neg(self) -> Fp6<P> {
+        Fp6::new(-&self.c0, -&self.c1, -&self.c2)
+    }
+}
+forward_ops!(impl<P: Fp6Params> for Fp6<P>);
+
+/////////////////////////////////////////////
+/////////////// Field Requirements
+/////////////////////////////////////////////
+impl<P: Fp6Params> Fp6<P> {
+    pub const fn new(c0: Fp2<P::Fp2>, c1: Fp2<P::Fp2>, c2: Fp2<P::Fp2>) -> Self {
+        Self { c0, c1, c2 }
+    }
+
+    pub fn mul_by_fp2(&self, by: &Fp2<P::Fp2>) -> Self {
+        Self::new(&self.c0 * by, &self.c1 * by, &self.c2 * by)
+    }
+
+    /// Multiplication by v, which shifts the coefficients: v * v^2 = ξ
+    pub fn mul_by_nonresidue(&self) -> Self {
+        Self::new(
+            Self::mul_fp2_by_nonresidue(&self.c2),
+            self.c0.clone(),
+            self.c1.clone(),
+        )
+    }
+
+    /// Multiplication by b1 * v
+    pub fn mul_by_1(&self, b1: &Fp2<P::Fp2>) -> Self {
+        Self::new(
+            Self::mul_fp2_by_nonresidue(&(&self.c2 * b1)),
+            &self.c0 * b1,
+            &self.c1 * b1,
+        )
+    }
+
+    /// Multiplication by b0 + b1 * v, the shape line evaluations have
+    pub fn mul_by_01(&self, b0: &Fp2<P::Fp2>, b1: &Fp2<P::Fp2>) -> Self {
+        let v0 = &self.c0 * b0;
+        let v1 = &self.c1 * b1;
+
+        let c0 = Self::mul_fp2_by_nonresidue(&(&self.c2 * b1)) + &v0;
+        let c1 = (&self.c0 + &self.c1) * (b0 + b1) - &v0 - &v1;
+        let c2 = &self.c2 * b0 + &v1;
+
+        Self::new(c0, c1, c2)
+    }
+
+    /// x -> x^(p^power)
+    ///
+    /// Frobenius is a field automorphism, so it maps each coefficient and
+    /// sends v^k to v^(k p^i) = ξ^(k(p^i - 1)/3) v^k
+    pub fn frobenius_map(&self, power: usize) -> Self {
+        Self::new(
+            self.c0.frobenius_map(power),
+            self.c1.frobenius_map(power) * &P::FROBENIUS_COEFF_C1[power % 6],
+            self.c2.frobenius_map(power) * &P::FROBENIUS_COEFF_C2[power % 6],
+        )
+    }
+
+    pub(crate) fn mul_fp2_by_nonresidue(x: &Fp2<P::Fp2>) -> Fp2<P::Fp2> {
+        x * &P::NON_RESIDUE
+    }
+}
+
+impl<P: Fp6Params> Field for Fp6<P> {
+    fn zero() -> Self {
+        Self::new(Fp2::zero(), Fp2::zero(), Fp2::zero())
+    }
+
+    fn one() -> Self {
+        Self::new(Fp2::one(), Fp2::zero(), Fp2::zero())
+    }
+
+    fn is_zero(&self) -> bool {
+        self.c0.is_zero() && self.c1.is_zero() && self.c2.is_zero()
+    }
+
+    fn double(&self) -> Self {
+        Self::new(self.c0.double(), self.c1.double(), self.c2.double())
+    }
+
+    /// CH-SQR2 from Chung & Hasan, 2 multiplications and 3 squarings in Fp2
+    fn square(&self) -> Self {
+        let (a0, a1, a2) = (&self.c0, &self.c1, &self.c2);
+
+        let s0 = a0.square();
+        let s1 = (a0 * a1).double();
+        let s2 = (a0 - a1 + a2).square();
+        let s3 = (a1 * a2).double();
+        let s4 = a2.square();
+
+        let c0 = Self::mul_fp2_by_nonresidue(&s3) + &s0;
+        let c1 = Self::mul_fp2_by_nonresidue(&s4) + &s1;
+        let c2 = s1 + s2 + s3 - s0 - s4;
+
+        Self::new(c0, c1, c2)
+    }
+
+    /// Adjugate over determinant: the t_i are the cofactors of the
+    /// multiplication-by-x matrix and t3 its determinant, an element of Fp2
+    fn inv(&self) -> Option<Self> {
+        let (c0, c1, c2) = (&self.c0, &self.c1, &self.c2);
+
+        let t0 = c0.square() - Self::mul_fp2_by_nonresidue(&(c1 * c2));
+        let t1 = Self::mul_fp2_by_nonresidue(&c2.square()) - c0 * c1;
+        let t2 = c1.square() - c0 * c2;
+
+        let det = c0 * &t0 + Self::mul_fp2_by_nonresidue(&(c2 * &t1 + c1 * &t2));
+        let det_inv = det.inv()?;
+
+        Some(Self::new(t0 * &det_inv, t1 * &det_inv, t2 * det_inv))
+    }
+
+    fn characteristic() -> Vec<u64> {
+        Fp2::<P::Fp2>::characteristic()
+    }
+
+    /// c0 || c1 || c2
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.c0.to_bytes();
+        bytes.extend(self.c1.to_bytes());
+        bytes.extend(self.c2.to_bytes());
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != 192 {
+            return None;
+        }
+        let c0 = Fp2::from_bytes(&bytes[..64])?;
+        let c1 = Fp2::from_bytes(&bytes[64..128])?;
+        let c2 = Fp2::from_bytes(&bytes[128..])?;
+        Some(Self::new(c0, c1, c2))
+    }
+
+    fn random<R: RngCore + ?Sized>(rng: &mut R) -> Self {
+        Self::new(Fp2::random(rng), Fp2::random(rng), Fp2::random(rng))
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::bn254::{Bn254Fq, Bn254Fq6};
+    use crate::params::PrimeFieldParams;
+    use crate::Fp;
+
+    pub(crate) fn sample(seed: u64) -> Fp6<Bn254Fq6> {
+        let c = |k: u64| {
+            Fp2::new(
+                Fp::from_u64(seed * 6 + k).exp_by_u64(seed + 3),
+                Fp::from_u64(k),
+            )
+        };
+        Fp6::new(c(0), c(1), c(2))
+    }
+
+    #[test]
+    fn mul_is_associative_and_distributive() {
+        let (a, b, c) = (sample(1), sample(2), sample(3));
+        assert_eq!((&a * &b) * &c, &a * (&b * &c));
+        assert_eq!(&a * (&b + &c), &a * &b + &a * &c);
+    }
+
+    #[test]
+    fn square_matches_mul() {
+        let a = sample(4);
+        assert_eq!(a.square(), &a * &a);
+    }
+
+    #[test]
+    fn inv() {
+        let a = sample(5);
+        assert_eq!(&a * a.inv().unwrap(), Fp6::one());
+        assert_eq!(Fp6::<Bn254Fq6>::zero().inv(), None);
+    }
+
+    #[test]
+    fn sparse_mul() {
+        let a = sample(6);
+        let b = sample(7);
+        let v = Fp6::new(Fp2::zero(), Fp2::one(), Fp2::zero());
+        assert_eq!(a.mul_by_nonresidue(), &a * &v);
+        assert_eq!(
+            a.mul_by_1(&b.c1),
+            &a * Fp6::new(Fp2::zero(), b.c1.clone(), Fp2::zero())
+        );
+        assert_eq!(
+            a.mul_by_01(&b.c0, &b.c1),
+            &a * Fp6::new(b.c0.clone(), b.c1.clone(), Fp2::zero())
+        );
+    }
+
+    #[test]
+    fn frobenius_is_pth_power() {
+        let a = sample(8);
+        let mut pow = a.clone();
+        for i in 0..6 {
+            assert_eq!(a.frobenius_map(i), pow);
+            pow = pow.pow(&Bn254Fq::MODULUS.0);
+        }
+        assert_eq!(pow, a);
+    }
+}
@@ -0,0 +1,578 @@
+use alloc::{vec, vec::Vec};
+use core::marker::PhantomData;
+use core::ops::{Add, Div, Mul, Neg, Sub};
+use rand_core::RngCore;
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
+use zeroize::Zeroize;
+
+use crate::errors::{Error, Result};
+use crate::factor::prime_factors;
+use crate::field::Field;
+use crate::params::BinaryFieldParams;
+
+/// GF(2^8) of AES, x^8 + x^4 + x^3 + x + 1
+#[derive(Debug)]
+pub struct Aes;
+impl BinaryFieldParams for Aes {
+    const DEGREE: u32 = 8;
+    const MODULUS: u128 = 0x1b;
+}
+
+/// GF(2^128) of GHASH, x^128 + x^7 + x^2 + x + 1
+///
+/// GCM writes blocks with the coefficient of x^0 in the most significant bit
+/// of the first byte, so a block `b` is `u128::from_be_bytes(b).reverse_bits()`
+/// here.
+#[derive(Debug)]
+pub struct Ghash;
+impl BinaryFieldParams for Ghash {
+    const DEGREE: u32 = 128;
+    const MODULUS: u128 = 0x87;
+}
+
+/// Element of the binary field described by `P`, a polynomial over GF(2) of
+/// degree below `P::DEGREE` stored as a bit vector
+///
+/// Addition and subtraction are XOR. Multiplication is a carry-less product
+/// followed by a reduction that folds the bits above the degree back with
+/// x^n = f(x) - x^n, as often as the degree of f - x^n requires. Both loop a
+/// fixed number of times with masks, and inversion is Itoh-Tsujii, a fixed
+/// chain of squarings and multiplications, so all of the arithmetic runs in
+/// constant time.
+pub struct GF2n<P: BinaryFieldParams> {
+    num: u128,
+    _params: PhantomData<P>,
+}
+
+impl<P: BinaryFieldParams> Clone for GF2n<P> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<P: BinaryFieldParams> Copy for GF2n<P> {}
+
+impl<P: BinaryFieldParams> core::fmt::Debug for GF2n<P> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("GF2n")
+            .field("num", &format_args!("{:#x}", self.num))
+            .field("degree", &P::DEGREE)
+            .finish()
+    }
+}
+
+/////////////////////////////////////////////
+/////////////// Operator Overloads
+/////////////////////////////////////////////
+///// Equality
+impl<P: BinaryFieldParams> Eq for GF2n<P> {}
+impl<P: BinaryFieldParams> PartialEq for GF2n<P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+///// Addition
+impl<P: BinaryFieldParams> Add<&GF2n<P>> for &GF2n<P> {
+    type Output = GF2n<P>;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn add(self, rhs: &GF2n<P>) -> GF2n<P> {
+        GF2n::from_reduced(self.num ^ rhs.num)
+    }
+}
+///// Subtraction
+impl<P: BinaryFieldParams> Sub<&GF2n<P>> for &GF2n<P> {
+    type Output = GF2n<P>;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn sub(self, rhs: &GF2n<P>) -> GF2n<P> {
+        GF2n::from_reduced(self.num ^ rhs.num)
+    }
+}
+///// Multiplication
+impl<P: BinaryFieldParams> Mul<&GF2n<P>> for &GF2n<P> {
+    type Output = GF2n<P>;
+    fn mul(self, rhs: &GF2n<P>) -> GF2n<P> {
+        let (hi, lo) = clmul(self.num, rhs.num, P::DEGREE);
+        GF2n::from_reduced(GF2n::<P>::reduce(hi, lo, 2 * P::DEGREE - 2))
+    }
+}
+///// Division
+impl<P: BinaryFieldParams> Div<&GF2n<P>> for &GF2n<P> {
+    type Output = GF2n<P>;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: &GF2n<P>) -> GF2n<P> {
+        self * rhs.inv().expect("division by zero")
+    }
+}
+///// Neg
+impl<P: BinaryFieldParams> Neg for &GF2n<P> {
+    type Output = GF2n<P>;
+    /// -a = a in characteristic 2
+    fn neg(self) -> GF2n<P> {
+        *self
+    }
+}
+forward_ops!(impl<P: BinaryFieldParams> for GF2n<P>);
+
+/////////////////////////////////////////////
+/////////////// Field Requirements
+/////////////////////////////////////////////
+impl<P: BinaryFieldParams> GF2n<P> {
+    /// 2^n - 1, the bits an element may use
+    const MASK: u128 = {
+        assert!(
+            P::DEGREE >= 1 && P::DEGREE <= 128,
+            "degree must be between 1 and 128"
+        );
+        let mask = u128::MAX >> (128 - P::DEGREE);
+        assert!(P::MODULUS & !mask == 0, "MODULUS must be below x^DEGREE");
+        mask
+    };
+
+    /// Degree of f - x^n, bounding how far each fold of `reduce` reaches
+    const MODULUS_DEGREE: u32 = match P::MODULUS {
+        0 => 0,
+        r => 127 - r.leading_zeros(),
+    };
+
+    /// Bytes of `to_bytes_be`
+    const BYTES: usize = (P::DEGREE as usize).div_ceil(8);
+
+    pub const ZERO: Self = Self::from_reduced(0);
+    pub const ONE: Self = Self::from_reduced(1);
+
+    /// Any polynomial of degree below 128 is accepted and reduced mod f
+    pub const fn new(num: u128) -> Self {
+        Self::from_reduced(Self::reduce(0, num, 127))
+    }
+
+    /// The polynomial as a bit vector, bit i holding the coefficient of x^i
+    pub fn num(&self) -> u128 {
+        self.num
+    }
+
+    pub const fn degree() -> u32 {
+        P::DEGREE
+    }
+
+    /// `num` in the fewest bytes that hold n bits, big-endian
+    pub fn to_bytes_be(&self) -> Vec<u8> {
+        self.num.to_be_bytes()[16 - Self::BYTES..].to_vec()
+    }
+
+    /// Inverse of `to_bytes_be`, rejecting the wrong length and bits at or
+    /// above x^n
+    pub fn from_bytes_be(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != Self::BYTES {
+            return Err(Error::InvalidLength);
+        }
+        let mut be = [0u8; 16];
+        be[16 - Self::BYTES..].copy_from_slice(bytes);
+        let num = u128::from_be_bytes(be);
+        match num & !Self::MASK {
+            0 => Ok(Self::from_reduced(num)),
+            _ => Err(Error::NonCanonical),
+        }
+    }
+
+    /// self^(2^k), the k-th power of the Frobenius map
+    pub fn frobenius(&self, k: u32) -> Self {
+        let mut res = *self;
+        for _ in 0..k {
+            res = res.square();
+        }
+        res
+    }
+
+    /// The unique square root, self^(2^(n-1))
+    pub fn sqrt(&self) -> Self {
+        self.frobenius(P::DEGREE - 1)
+    }
+
+    /// Tr(a) = a + a^2 + a^4 + ... + a^(2^(n-1)), which is always 0 or 1
+    pub fn trace(&self) -> u8 {
+        let mut power = *self;
+        let mut sum = *self;
+        for _ in 1..P::DEGREE {
+            power = power.square();
+            sum = sum + power;
+        }
+        sum.num as u8
+    }
+
+    /// hi * 2^128 + lo mod f, where the sum has degree at most `degree`
+    ///
+    /// Splitting t = h x^n + l, with x^n = r (mod f) for r = f - x^n,
+    /// t = h r + l has degree at most max(deg t - n + deg r, n - 1). The
+    /// number of folds depends only on `degree` and f.
+    const fn reduce(mut hi: u128, mut lo: u128, mut degree: u32) -> u128 {
+        let n = P::DEGREE;
+        while degree >= n {
+            let h = match n {
+                128 => hi,
+                _ => (lo >> n) | (hi << (128 - n)),
+            };
+            let l = lo & Self::MASK;
+            (hi, lo) = clmul(P::MODULUS, h, degree - n + 1);
+            lo ^= l;
+
+            degree = degree - n + Self::MODULUS_DEGREE;
+            if degree < n - 1 {
+                degree = n - 1;
+            }
+        }
+        lo
+    }
+
+    const fn from_reduced(num: u128) -> Self {
+        Self {
+            num,
+            _params: PhantomData,
+        }
+    }
+}
+
+impl<P: BinaryFieldParams> Field for GF2n<P> {
+    fn zero() -> Self {
+        Self::ZERO
+    }
+
+    fn one() -> Self {
+        Self::ONE
+    }
+
+    fn is_zero(&self) -> bool {
+        self.num == 0
+    }
+
+    /// 2a = 0 in characteristic 2
+    fn double(&self) -> Self {
+        Self::ZERO
+    }
+
+    /// Squaring is linear over GF(2): the bits are spread to the even
+    /// positions and reduced
+    fn square(&self) -> Self {
+        let hi = spread(self.num >> 64);
+        let lo = spread(self.num & u64::MAX as u128);
+        Self::from_reduced(Self::reduce(hi, lo, 2 * P::DEGREE - 2))
+    }
+
+    /// Itoh-Tsujii: a^-1 = a^(2^n - 2) = (a^(2^(n-1) - 1))^2, with
+    /// b_k = a^(2^k - 1) built along the binary expansion of n - 1 from
+    /// b_(2k) = b_k^(2^k) b_k and b_(k+1) = b_k^2 a
+    fn inv(&self) -> Option<Self> {
+        if self.is_zero() {
+            return None;
+        }
+        let m = P::DEGREE - 1;
+        if m == 0 {
+            return Some(*self);
+        }
+
+        let mut b = *self;
+        let mut k = 1;
+        for i in (0..31 - m.leading_zeros()).rev() {
+            b = b.frobenius(k) * b;
+            k *= 2;
+            if (m >> i) & 1 == 1 {
+                b = b.square() * self;
+                k += 1;
+            }
+        }
+        Some(b.square())
+    }
+
+    fn characteristic() -> Vec<u64> {
+        vec![2]
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes_be()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        Self::from_bytes_be(bytes).ok()
+    }
+
+    /// Every n-bit vector is an element, so n random bits are uniform
+    fn random<R: RngCore + ?Sized>(rng: &mut R) -> Self {
+        let num = (rng.next_u64() as u128) | ((rng.next_u64() as u128) << 64);
+        Self::from_reduced(num & Self::MASK)
+    }
+}
+
+/////////////////////////////////////////////
+/////////////// Constant Time
+/////////////////////////////////////////////
+impl<P: BinaryFieldParams> ConstantTimeEq for GF2n<P> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        limbs(self.num).ct_eq(&limbs(other.num))
+    }
+}
+
+impl<P: BinaryFieldParams> ConditionallySelectable for GF2n<P> {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        let ([a_lo, a_hi], [b_lo, b_hi]) = (limbs(a.num), limbs(b.num));
+        let lo = u64::conditional_select(&a_lo, &b_lo, choice);
+        let hi = u64::conditional_select(&a_hi, &b_hi, choice);
+        Self::from_reduced(((hi as u128) << 64) | lo as u128)
+    }
+}
+
+impl<P: BinaryFieldParams> Zeroize for GF2n<P> {
+    fn zeroize(&mut self) {
+        self.num.zeroize();
+    }
+}
+
+/// subtle has no u128 impls without its `i128` feature
+fn limbs(num: u128) -> [u64; 2] {
+    [num as u64, (num >> 64) as u64]
+}
+
+/////////////////////////////////////////////
+/////////////// Polynomials over GF(2)
+/////////////////////////////////////////////
+/// Carry-less product a * b as a 256-bit (hi, lo) pair, visiting the low
+/// `bits` bits of b
+const fn clmul(a: u128, b: u128, bits: u32) -> (u128, u128) {
+    let (mut hi, mut lo) = (0u128, 0u128);
+    let mut i = 0;
+    while i < bits {
+        let mask = 0u128.wrapping_sub((b >> i) & 1);
+        lo ^= (a << i) & mask;
+        // a >> (128 - i), without the shift overflow at i = 0
+        hi ^= ((a >> 1) >> (127 - i)) & mask;
+        i += 1;
+    }
+    (hi, lo)
+}
+
+/// Bit i of a 64-bit x moved to bit 2i, i.e. x(t) -> x(t)^2 over GF(2)
+const fn spread(x: u128) -> u128 {
+    let mut x = x;
+    x = (x | (x << 32)) & 0x0000_0000_ffff_ffff_0000_0000_ffff_ffff;
+    x = (x | (x << 16)) & 0x0000_ffff_0000_ffff_0000_ffff_0000_ffff;
+    x = (x | (x << 8)) & 0x00ff_00ff_00ff_00ff_00ff_00ff_00ff_00ff;
+    x = (x | (x << 4)) & 0x0f0f_0f0f_0f0f_0f0f_0f0f_0f0f_0f0f_0f0f;
+    x = (x | (x << 2)) & 0x3333_3333_3333_3333_3333_3333_3333_3333;
+    x = (x | (x << 1)) & 0x5555_5555_5555_5555_5555_5555_5555_5555;
+    x
+}
+
+fn poly_degree(a: u128) -> u32 {
+    127 - a.leading_zeros()
+}
+
+/// a mod b for b != 0
+fn poly_rem(mut a: u128, b: u128) -> u128 {
+    let db = poly_degree(b);
+    while a != 0 && poly_degree(a) >= db {
+        a ^= b << (poly_degree(a) - db);
+    }
+    a
+}
+
+/// Whether gcd(g, f) = 1, for g of degree below n
+fn coprime_to_modulus<P: BinaryFieldParams>(g: u128) -> bool {
+    if g == 0 {
+        return false;
+    }
+
+    // f mod g = (x^n mod g) + (r mod g); x^n mod g is built one shift at a
+    // time so that nothing exceeds 128 bits
+    let dg = poly_degree(g);
+    let mut x_n = 1u128;
+    for _ in 0..P::DEGREE {
+        x_n <<= 1;
+        if (x_n >> dg) & 1 == 1 {
+            x_n ^= g;
+        }
+    }
+
+    let (mut a, mut b) = (g, x_n ^ poly_rem(P::MODULUS, g));
+    while b != 0 {
+        (a, b) = (b, poly_rem(a, b));
+    }
+    a == 1
+}
+
+/// See `BinaryFieldParams::check`
+pub(crate) fn is_irreducible<P: BinaryFieldParams>() -> bool {
+    let n = P::DEGREE;
+    let x = GF2n::<P>::new(2);
+    if x.frobenius(n) != x {
+        return false;
+    }
+    prime_factors(n as u64)
+        .into_iter()
+        .all(|q| coprime_to_modulus::<P>((x.frobenius(n / q as u32) - x).num))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    /// x^4 + x + 1
+    #[derive(Debug)]
+    struct Gf16;
+    impl BinaryFieldParams for Gf16 {
+        const DEGREE: u32 = 4;
+        const MODULUS: u128 = 0x3;
+    }
+
+    /// x^8 + 1 = (x + 1)^8
+    #[derive(Debug)]
+    struct Reducible;
+    impl BinaryFieldParams for Reducible {
+        const DEGREE: u32 = 8;
+        const MODULUS: u128 = 0x1;
+    }
+
+    /// x^4 + x^2 + 1 = (x^2 + x + 1)^2, no roots but not irreducible
+    #[derive(Debug)]
+    struct Square;
+    impl BinaryFieldParams for Square {
+        const DEGREE: u32 = 4;
+        const MODULUS: u128 = 0x5;
+    }
+
+    /// x^64 + x^4 + x^3 + x + 1
+    #[derive(Debug)]
+    struct Gf64;
+    impl BinaryFieldParams for Gf64 {
+        const DEGREE: u32 = 64;
+        const MODULUS: u128 = 0x1b;
+    }
+
+    /// x^2 + x + 1, the smallest proper extension
+    #[derive(Debug)]
+    struct Gf4;
+    impl BinaryFieldParams for Gf4 {
+        const DEGREE: u32 = 2;
+        const MODULUS: u128 = 0x3;
+    }
+
+    #[test]
+    fn irreducibility() {
+        Aes::check().unwrap();
+        Ghash::check().unwrap();
+        Gf16::check().unwrap();
+        Gf64::check().unwrap();
+        Gf4::check().unwrap();
+        assert_eq!(Reducible::check(), Err(Error::NotIrreducible));
+        assert_eq!(Square::check(), Err(Error::NotIrreducible));
+    }
+
+    /// AES's xtime-based multiplication
+    fn aes_reference_mul(mut a: u8, mut b: u8) -> u8 {
+        let mut res = 0;
+        while b != 0 {
+            if b & 1 == 1 {
+                res ^= a;
+            }
+            a = (a << 1) ^ if a & 0x80 != 0 { 0x1b } else { 0 };
+            b >>= 1;
+        }
+        res
+    }
+
+    #[test]
+    fn aes_field() {
+        type F = GF2n<Aes>;
+        // FIPS 197, 4.2
+        assert_eq!(F::new(0x57) * F::new(0x83), F::new(0xc1));
+        assert_eq!(F::new(0x57) * F::new(0x13), F::new(0xfe));
+        // first S-box step for 0x53
+        assert_eq!(F::new(0x53).inv(), Some(F::new(0xca)));
+
+        let mut trace_ones = 0;
+        for a in 0..=255u8 {
+            for b in 0..=255u8 {
+                assert_eq!(
+                    (F::new(a as u128) * F::new(b as u128)).num(),
+                    aes_reference_mul(a, b) as u128
+                );
+            }
+            let x = F::new(a as u128);
+            assert_eq!(x.square(), x * x);
+            assert_eq!(x.sqrt().square(), x);
+            if a != 0 {
+                assert_eq!(x * x.inv().unwrap(), F::ONE);
+            }
+            trace_ones += x.trace() as usize;
+        }
+        // the trace is balanced
+        assert_eq!(trace_ones, 128);
+    }
+
+    #[test]
+    fn ghash_field() {
+        type F = GF2n<Ghash>;
+        let block = |hex: &str| F::new(u128::from_str_radix(hex, 16).unwrap().reverse_bits());
+
+        // GCM spec, test case 2: X_1 = C * H
+        let h = block("66e94bd4ef8a2c3b884cfa59ca342b2e");
+        let c = block("0388dace60b6a392f328c2b971b2fe78");
+        assert_eq!(c * h, block("5e2ec746917062882c85b0685353deb7"));
+    }
+
+    fn check_field_laws<P: BinaryFieldParams>() {
+        let mut rng = StdRng::seed_from_u64(16);
+        for _ in 0..50 {
+            let (a, b) = (GF2n::<P>::random(&mut rng), GF2n::<P>::random(&mut rng));
+            assert_eq!(a + a, GF2n::ZERO);
+            assert_eq!(-a, a);
+            assert_eq!(a.square(), a * a);
+            assert_eq!((a + b).square(), a.square() + b.square());
+            assert_eq!(a.frobenius(P::DEGREE), a);
+            assert_eq!(a.sqrt().square(), a);
+            assert!(a.trace() <= 1);
+            assert_eq!(a.trace() ^ b.trace(), (a + b).trace());
+            assert_eq!(a.pow(&[3]), a * a * a);
+            if !a.is_zero() {
+                assert_eq!(a * a.inv().unwrap(), GF2n::ONE);
+                assert_eq!(a * b / a, b);
+            }
+        }
+        assert_eq!(GF2n::<P>::ZERO.inv(), None);
+        assert_eq!(GF2n::<P>::ONE.inv(), Some(GF2n::ONE));
+    }
+
+    #[test]
+    fn field_laws() {
+        check_field_laws::<Aes>();
+        check_field_laws::<Ghash>();
+        check_field_laws::<Gf16>();
+        check_field_laws::<Gf64>();
+        check_field_laws::<Gf4>();
+    }
+
+    #[test]
+    fn new_reduces() {
+        // x^4 = x + 1
+        assert_eq!(GF2n::<Gf16>::new(0x10), GF2n::new(0x3));
+        assert_eq!(GF2n::<Gf16>::new(1 << 100), GF2n::new(2).pow(&[100]));
+        assert_eq!(GF2n::<Ghash>::new(u128::MAX).num(), u128::MAX);
+    }
+
+    #[test]
+    fn bytes() {
+        let a = GF2n::<Gf16>::new(0xb);
+        assert_eq!(a.to_bytes(), vec![0xb]);
+        assert_eq!(GF2n::from_bytes(&a.to_bytes()), Some(a));
+        assert_eq!(
+            GF2n::<Gf16>::from_bytes_be(&[0x10]),
+            Err(Error::NonCanonical)
+        );
+        assert_eq!(
+            GF2n::<Gf16>::from_bytes_be(&[0, 1]),
+            Err(Error::InvalidLength)
+        );
+
+        let b = GF2n::<Ghash>::new(0x0102);
+        assert_eq!(b.to_bytes().len(), 16);
+        assert_eq!(GF2n::from_bytes(&b.to_bytes()), Some(b));
+    }
+}
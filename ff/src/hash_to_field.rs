@@ -0,0 +1,216 @@
+//! Hashing arbitrary messages to prime field elements, RFC 9380 section 5
+//!
+//! `expand_message_xmd` with SHA-256 stretches the message into uniform bytes,
+//! and every field element is then read from L = ceil((NUM_BITS + k) / 8)
+//! of them with k = 128, so that the bias of the final reduction mod p is
+//! at most 2^-128.
+use alloc::vec::Vec;
+use primitive_types::{U256, U512};
+use sha2::{Digest, Sha256};
+
+use crate::errors::{Error, Result};
+use crate::field::PrimeField;
+use crate::p_u256::U256FieldElement;
+use crate::params::PrimeFieldParams;
+
+/// Security parameter k of the RFC, in bits
+const SECURITY_BITS: u32 = 128;
+
+/// Output and input block sizes of SHA-256, b_in_bytes and s_in_bytes
+const B_IN_BYTES: usize = 32;
+const S_IN_BYTES: usize = 64;
+
+const MAX_DST_LEN: usize = 255;
+
+/// `count` elements of GF(p), derived from `msg` under the domain separation
+/// tag `dst`. Distinct DSTs give independent hash functions, so every
+/// protocol (and every use within one) should have its own.
+pub fn hash_to_field<P: PrimeFieldParams>(
+    msg: &[u8],
+    dst: &[u8],
+    count: usize,
+) -> Result<Vec<U256FieldElement<P>>> {
+    let len = element_len::<P>();
+    let uniform_bytes = expand_message_xmd(msg, dst, count * len)?;
+
+    let modulus = U512::from(P::MODULUS);
+    Ok(uniform_bytes
+        .chunks(len)
+        .map(|chunk| {
+            let num = U512::from_big_endian(chunk) % modulus;
+            U256FieldElement::new(U256::try_from(num).unwrap())
+        })
+        .collect())
+}
+
+/// L, the number of uniform bytes behind each element
+fn element_len<P: PrimeFieldParams>() -> usize {
+    (U256FieldElement::<P>::NUM_BITS + SECURITY_BITS).div_ceil(8) as usize
+}
+
+/// `len_in_bytes` uniformly random looking bytes from `msg` and `dst`,
+/// expand_message_xmd of RFC 9380 section 5.3.1 with SHA-256
+///
+/// Fails on an empty DST and on more than 255 * 32 output bytes. DSTs longer
+/// than 255 bytes are first hashed down as the RFC specifies.
+pub fn expand_message_xmd(msg: &[u8], dst: &[u8], len_in_bytes: usize) -> Result<Vec<u8>> {
+    if dst.is_empty() {
+        return Err(Error::InvalidDst);
+    }
+    let ell = len_in_bytes.div_ceil(B_IN_BYTES);
+    if ell > 255 {
+        return Err(Error::InvalidLength);
+    }
+
+    let dst = match dst.len() > MAX_DST_LEN {
+        true => Sha256::new()
+            .chain_update(b"H2C-OVERSIZE-DST-")
+            .chain_update(dst)
+            .finalize()
+            .to_vec(),
+        false => dst.to_vec(),
+    };
+    // DST_prime = DST || I2OSP(len(DST), 1)
+    let dst_len = [dst.len() as u8];
+
+    // b_0 = H(Z_pad || msg || l_i_b_str || I2OSP(0, 1) || DST_prime)
+    let b_0 = Sha256::new()
+        .chain_update([0u8; S_IN_BYTES])
+        .chain_update(msg)
+        .chain_update((len_in_bytes as u16).to_be_bytes())
+        .chain_update([0u8])
+        .chain_update(&dst)
+        .chain_update(dst_len)
+        .finalize();
+
+    // b_1 = H(b_0 || I2OSP(1, 1) || DST_prime) and
+    // b_i = H(strxor(b_0, b_(i-1)) || I2OSP(i, 1) || DST_prime)
+    let mut uniform_bytes = Vec::with_capacity(ell * B_IN_BYTES);
+    let mut b_i = [0u8; B_IN_BYTES];
+    for i in 1..=ell {
+        let mut xored = b_0;
+        for (x, b) in xored.iter_mut().zip(b_i) {
+            *x ^= b;
+        }
+        b_i = Sha256::new()
+            .chain_update(xored)
+            .chain_update([i as u8])
+            .chain_update(&dst)
+            .chain_update(dst_len)
+            .finalize()
+            .into();
+        uniform_bytes.extend_from_slice(&b_i);
+    }
+
+    uniform_bytes.truncate(len_in_bytes);
+    Ok(uniform_bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::params::u256_from_hex;
+    use core::str::FromStr;
+
+    test_prime_field!(P97, U256([97, 0, 0, 0]));
+    test_prime_field!(
+        Secp256k1P,
+        u256_from_hex("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F")
+    );
+    test_prime_field!(
+        P256,
+        u256_from_hex("ffffffff00000001000000000000000000000000ffffffffffffffffffffffff")
+    );
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    /// RFC 9380 appendix K.1
+    #[test]
+    fn expand_message_xmd_vectors() {
+        let dst = b"QUUX-V01-CS02-with-expander-SHA256-128";
+        assert_eq!(
+            hex(&expand_message_xmd(b"", dst, 0x20).unwrap()),
+            "68a985b87eb6b46952128911f2a4412bbc302a9d759667f87f7a21d803f07235"
+        );
+        assert_eq!(
+            hex(&expand_message_xmd(b"abc", dst, 0x20).unwrap()),
+            "d8ccab23b5985ccea865c6c97b6e5b8350e794e603b4b97902f53a8a0d605615"
+        );
+    }
+
+    #[test]
+    fn expand_message_xmd_lengths() {
+        let dst = b"DST";
+        for len in [1, 31, 32, 33, 100, 255 * 32] {
+            assert_eq!(expand_message_xmd(b"msg", dst, len).unwrap().len(), len);
+        }
+        // the output length is hashed in, so shorter outputs are not prefixes
+        let long = expand_message_xmd(b"msg", dst, 64).unwrap();
+        let short = expand_message_xmd(b"msg", dst, 32).unwrap();
+        assert_ne!(long[..32], short[..]);
+
+        assert_eq!(
+            expand_message_xmd(b"msg", dst, 255 * 32 + 1),
+            Err(Error::InvalidLength)
+        );
+        assert_eq!(expand_message_xmd(b"msg", b"", 32), Err(Error::InvalidDst));
+    }
+
+    #[test]
+    fn oversize_dst_is_hashed() {
+        let dst = [b'x'; 256];
+        let hashed = Sha256::new()
+            .chain_update(b"H2C-OVERSIZE-DST-")
+            .chain_update(dst)
+            .finalize();
+        assert_eq!(
+            expand_message_xmd(b"abc", &dst, 48),
+            expand_message_xmd(b"abc", &hashed, 48)
+        );
+    }
+
+    /// u[0] and u[1] of the first vectors of RFC 9380 appendices J.8.1 and J.1.1
+    #[test]
+    fn hash_to_field_vectors() {
+        let u = hash_to_field::<Secp256k1P>(
+            b"",
+            b"QUUX-V01-CS02-with-secp256k1_XMD:SHA-256_SSWU_RO_",
+            2,
+        )
+        .unwrap();
+        assert_eq!(
+            u[0].num(),
+            U256::from_str("6b0f9910dd2ba71c78f2ee9f04d73b5f4c5f7fc773a701abea1e573cab002fb3")
+                .unwrap()
+        );
+        assert_eq!(
+            u[1].num(),
+            U256::from_str("1ae6c212e08fe1a5937f6202f929a2cc8ef4ee5b9782db68b0d5799fd8f09e16")
+                .unwrap()
+        );
+
+        let u =
+            hash_to_field::<P256>(b"", b"QUUX-V01-CS02-with-P256_XMD:SHA-256_SSWU_RO_", 2).unwrap();
+        assert_eq!(
+            u[0].num(),
+            U256::from_str("ad5342c66a6dd0ff080df1da0ea1c04b96e0330dd89406465eeba11582515009")
+                .unwrap()
+        );
+        assert_eq!(
+            u[1].num(),
+            U256::from_str("8c0f1d43204bd6f6ea70ae8013070a1518b43873bcd850aafa0a9e220e2eea5a")
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn hash_to_small_field() {
+        assert_eq!(element_len::<P97>(), 17);
+        let u = hash_to_field::<P97>(b"abc", b"DST", 5).unwrap();
+        assert_eq!(u.len(), 5);
+        assert_ne!(u, hash_to_field::<P97>(b"abc", b"DST2", 5).unwrap());
+        assert_eq!(u, hash_to_field::<P97>(b"abc", b"DST", 5).unwrap());
+    }
+}
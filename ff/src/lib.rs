@@ -1,3 +1,162 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+/// Declares a unit struct implementing `PrimeFieldParams`, for tests
+#[cfg(test)]
+macro_rules! test_prime_field {
+    ($name:ident, $modulus:expr) => {
+        #[derive(Debug)]
+        struct $name;
+        impl $crate::params::PrimeFieldParams for $name {
+            const MODULUS: primitive_types::U256 = $modulus;
+        }
+    };
+}
+
+/// Derives the by-value and mixed operator impls of a binary operator from the
+/// `&a op &b` one, which is the only one that has to be written by hand
+macro_rules! forward_binop {
+    (impl<$p:ident: $bound:path> $imp:ident, $method:ident for $t:ty) => {
+        forward_binop!(@impl [$p: $bound] $imp, $method for $t);
+    };
+    (impl<$p:ident: $bound:path, const $n:ident: usize> $imp:ident, $method:ident for $t:ty) => {
+        forward_binop!(@impl [$p: $bound, const $n: usize] $imp, $method for $t);
+    };
+    (@impl [$($gen:tt)*] $imp:ident, $method:ident for $t:ty) => {
+        impl<$($gen)*> core::ops::$imp<$t> for $t {
+            type Output = $t;
+            fn $method(self, rhs: $t) -> $t {
+                core::ops::$imp::$method(&self, &rhs)
+            }
+        }
+        impl<$($gen)*> core::ops::$imp<$t> for &$t {
+            type Output = $t;
+            fn $method(self, rhs: $t) -> $t {
+                core::ops::$imp::$method(self, &rhs)
+            }
+        }
+        impl<$($gen)*> core::ops::$imp<&$t> for $t {
+            type Output = $t;
+            fn $method(self, rhs: &$t) -> $t {
+                core::ops::$imp::$method(&self, rhs)
+            }
+        }
+    };
+}
+
+/// All four arithmetic operators plus negation, see `forward_binop`
+macro_rules! forward_ops {
+    (impl<$p:ident: $bound:path> for $t:ty) => {
+        forward_ops!(@impl [$p: $bound] for $t);
+    };
+    (impl<$p:ident: $bound:path, const $n:ident: usize> for $t:ty) => {
+        forward_ops!(@impl [$p: $bound, const $n: usize] for $t);
+    };
+    (@impl [$($gen:tt)*] for $t:ty) => {
+        forward_binop!(@impl [$($gen)*] Add, add for $t);
+        forward_binop!(@impl [$($gen)*] Sub, sub for $t);
+        forward_binop!(@impl [$($gen)*] Mul, mul for $t);
+        forward_binop!(@impl [$($gen)*] Div, div for $t);
+        impl<$($gen)*> core::ops::Neg for $t {
+            type Output = $t;
+            fn neg(self) -> $t {
+                -&self
+            }
+        }
+    };
+}
+
+/// `+=`, `-=` and `*=`, with the right-hand side by value or borrowed, and
+/// the `Sum` and `Product` folds over values and references, all from the
+/// `&a op &b` operators
+macro_rules! forward_assign_ops {
+    (impl<$p:ident: $bound:path> for $t:ty) => {
+        forward_assign_ops!(@impl [$p: $bound] for $t);
+    };
+    (impl<$p:ident: $bound:path, const $n:ident: usize> for $t:ty) => {
+        forward_assign_ops!(@impl [$p: $bound, const $n: usize] for $t);
+    };
+    (@impl [$($gen:tt)*] for $t:ty) => {
+        forward_assign_ops!(@assign [$($gen)*] AddAssign, add_assign, Add, add for $t);
+        forward_assign_ops!(@assign [$($gen)*] SubAssign, sub_assign, Sub, sub for $t);
+        forward_assign_ops!(@assign [$($gen)*] MulAssign, mul_assign, Mul, mul for $t);
+        forward_assign_ops!(@fold [$($gen)*] Sum, sum, zero, Add, add for $t);
+        forward_assign_ops!(@fold [$($gen)*] Product, product, one, Mul, mul for $t);
+    };
+    (@assign [$($gen:tt)*] $imp:ident, $method:ident, $op:ident, $op_method:ident for $t:ty) => {
+        impl<$($gen)*> core::ops::$imp<&$t> for $t {
+            fn $method(&mut self, rhs: &$t) {
+                *self = core::ops::$op::$op_method(&*self, rhs);
+            }
+        }
+        impl<$($gen)*> core::ops::$imp<$t> for $t {
+            fn $method(&mut self, rhs: $t) {
+                *self = core::ops::$op::$op_method(&*self, &rhs);
+            }
+        }
+    };
+    (@fold [$($gen:tt)*] $imp:ident, $method:ident, $init:ident, $op:ident, $op_method:ident for $t:ty) => {
+        impl<$($gen)*> core::iter::$imp for $t {
+            fn $method<I: Iterator<Item = $t>>(iter: I) -> $t {
+                iter.fold(<$t as $crate::field::Field>::$init(), |acc, x| {
+                    core::ops::$op::$op_method(&acc, &x)
+                })
+            }
+        }
+        impl<'a, $($gen)*> core::iter::$imp<&'a $t> for $t {
+            fn $method<I: Iterator<Item = &'a $t>>(iter: I) -> $t {
+                iter.fold(<$t as $crate::field::Field>::$init(), |acc, x| {
+                    core::ops::$op::$op_method(&acc, x)
+                })
+            }
+        }
+    };
+}
+
+pub mod bn254;
+pub mod dlog;
 mod errors;
+pub mod exponentiation;
+pub mod factor;
+pub mod field;
+pub mod fp12;
+pub mod fp2;
+pub mod fp6;
+pub mod gf2n;
+pub mod hash_to_field;
+mod limbs;
+pub mod matrix;
+pub mod p_pseudo_mersenne;
+pub mod p_small;
 pub mod p_u256;
 pub mod p_u64;
+pub mod p_wide;
+pub mod parallel;
+pub mod params;
+pub mod primes;
+mod roots_of_unity;
+pub mod secret;
+#[cfg(feature = "serde")]
+mod serialization;
+mod sqrt;
+
+pub use errors::{Error, Result};
+pub use field::{batch_inverse, Field, PrimeField};
+pub use fp12::{Fp12, Fp12Params};
+pub use fp2::{Fp2, Fp2Params};
+pub use fp6::{Fp6, Fp6Params};
+pub use gf2n::GF2n;
+pub use hash_to_field::hash_to_field;
+pub use matrix::Matrix;
+pub use p_pseudo_mersenne::PseudoMersenneFieldElement;
+pub use p_small::{
+    BabyBearField, GoldilocksField, Mersenne31Field, PackedSmallField, SmallFieldElement,
+};
+pub use p_u256::Fp;
+pub use p_wide::{Fp384, Fp512, WideFieldElement};
+pub use params::{
+    BinaryFieldParams, PrimeFieldParams, PseudoMersenneParams, SmallPrimeParams,
+    WidePrimeFieldParams,
+};
+pub use secret::Secret;
@@ -0,0 +1,341 @@
+//! Constant-time multi-precision arithmetic on little-endian u64 limbs,
+//! shared by `U256FieldElement` (N = 4, on `U256.0`), `PseudoMersenneFieldElement`
+//! and `WideFieldElement`. All of them are `const fn`, so the per-field
+//! constants can be computed at compile time.
+
+pub(crate) const fn one<const N: usize>() -> [u64; N] {
+    let mut res = [0u64; N];
+    res[0] = 1;
+    res
+}
+
+/// -p^{-1} mod 2^64 by Newton iteration. Every step doubles the number of
+/// correct low bits, and x = 1 is already correct mod 2 for an odd p.
+pub(crate) const fn mont_inv(p0: u64) -> u64 {
+    let mut x: u64 = 1;
+    let mut i = 0;
+    while i < 6 {
+        x = x.wrapping_mul(2u64.wrapping_sub(p0.wrapping_mul(x)));
+        i += 1;
+    }
+    x.wrapping_neg()
+}
+
+/// Bit length of a
+pub(crate) const fn num_bits<const N: usize>(a: &[u64; N]) -> u32 {
+    let mut i = N;
+    while i > 0 {
+        i -= 1;
+        if a[i] != 0 {
+            return 64 * i as u32 + 64 - a[i].leading_zeros();
+        }
+    }
+    0
+}
+
+/// a + b and the carry out
+pub(crate) const fn adc<const N: usize>(a: &[u64; N], b: &[u64; N]) -> ([u64; N], u64) {
+    let mut res = [0u64; N];
+    let mut carry = 0u64;
+    let mut i = 0;
+    while i < N {
+        let s = a[i] as u128 + b[i] as u128 + carry as u128;
+        res[i] = s as u64;
+        carry = (s >> 64) as u64;
+        i += 1;
+    }
+    (res, carry)
+}
+
+/// a - b mod 2^(64 N) and the borrow out
+pub(crate) const fn sbb<const N: usize>(a: &[u64; N], b: &[u64; N]) -> ([u64; N], u64) {
+    let mut res = [0u64; N];
+    let mut borrow = 0u64;
+    let mut i = 0;
+    while i < N {
+        let d = (a[i] as u128).wrapping_sub(b[i] as u128 + borrow as u128);
+        res[i] = d as u64;
+        borrow = (d >> 127) as u64;
+        i += 1;
+    }
+    (res, borrow)
+}
+
+/// a if mask is all ones, b if it is zero
+pub(crate) const fn ct_select<const N: usize>(mask: u64, a: &[u64; N], b: &[u64; N]) -> [u64; N] {
+    let mut res = [0u64; N];
+    let mut i = 0;
+    while i < N {
+        res[i] = (a[i] & mask) | (b[i] & !mask);
+        i += 1;
+    }
+    res
+}
+
+/// Swaps a and b if mask is all ones, leaves them if it is zero
+pub(crate) const fn ct_swap<const N: usize>(mask: u64, a: &mut [u64; N], b: &mut [u64; N]) {
+    let mut i = 0;
+    while i < N {
+        let t = (a[i] ^ b[i]) & mask;
+        a[i] ^= t;
+        b[i] ^= t;
+        i += 1;
+    }
+}
+
+/// res - p if carry * 2^(64 N) + res >= p, else res. The only use is
+/// finishing a reduction, so it is done with a mask rather than a branch.
+pub(crate) const fn sub_if_geq<const N: usize>(
+    res: &[u64; N],
+    carry: u64,
+    p: &[u64; N],
+) -> [u64; N] {
+    let (d, borrow) = sbb(res, p);
+    let keep = (borrow & (carry ^ 1)).wrapping_neg();
+    ct_select(keep, res, &d)
+}
+
+/// a + b mod p, for a, b < p
+///
+/// On overflow the true sum is 2^(64 N) + res, which is above p, and the
+/// wrapping subtraction lands on the right value
+pub(crate) const fn add_mod<const N: usize>(a: &[u64; N], b: &[u64; N], p: &[u64; N]) -> [u64; N] {
+    let (res, carry) = adc(a, b);
+    sub_if_geq(&res, carry, p)
+}
+
+/// a - b mod p, for a, b < p: adds p back on a borrow
+pub(crate) const fn sub_mod<const N: usize>(a: &[u64; N], b: &[u64; N], p: &[u64; N]) -> [u64; N] {
+    let (res, borrow) = sbb(a, b);
+    let (wrapped, _) = adc(&res, p);
+    ct_select(borrow.wrapping_neg(), &wrapped, &res)
+}
+
+/// a * 2^k mod p, for a < p
+pub(crate) const fn pow2_mod<const N: usize>(a: &[u64; N], k: usize, p: &[u64; N]) -> [u64; N] {
+    let mut res = *a;
+    let mut i = 0;
+    while i < k {
+        res = add_mod(&res, &res, p);
+        i += 1;
+    }
+    res
+}
+
+/// a * b * R^-1 mod p with R = 2^(64 N), Coarsely Integrated Operand
+/// Scanning
+///
+/// `t` holds the running sum and `t_n`, `t_n1` the two limbs above it. At
+/// each outer step we add a * b[i], then add the multiple of p that zeroes
+/// the lowest limb and shift one limb to the right. For b < p the result is
+/// below 2p, so one conditional subtraction finishes it, even when a itself
+/// is not reduced.
+pub(crate) const fn mont_mul<const N: usize>(
+    a: &[u64; N],
+    b: &[u64; N],
+    m: &[u64; N],
+    inv: u64,
+) -> [u64; N] {
+    let mut t = [0u64; N];
+    let mut t_n = 0u64;
+
+    let mut i = 0;
+    while i < N {
+        let mut carry = 0u64;
+        let mut j = 0;
+        while j < N {
+            let uv = t[j] as u128 + a[j] as u128 * b[i] as u128 + carry as u128;
+            t[j] = uv as u64;
+            carry = (uv >> 64) as u64;
+            j += 1;
+        }
+        let uv = t_n as u128 + carry as u128;
+        t_n = uv as u64;
+        let t_n1 = (uv >> 64) as u64;
+
+        let k = t[0].wrapping_mul(inv);
+        let uv = t[0] as u128 + k as u128 * m[0] as u128;
+        let mut carry = (uv >> 64) as u64;
+        let mut j = 1;
+        while j < N {
+            let uv = t[j] as u128 + k as u128 * m[j] as u128 + carry as u128;
+            t[j - 1] = uv as u64;
+            carry = (uv >> 64) as u64;
+            j += 1;
+        }
+        let uv = t_n as u128 + carry as u128;
+        t[N - 1] = uv as u64;
+        t_n = t_n1 + (uv >> 64) as u64;
+        i += 1;
+    }
+
+    sub_if_geq(&t, t_n, m)
+}
+
+/////////////////////////////////////////////
+/////////////// Primality
+/////////////////////////////////////////////
+/// The first twelve primes. As Miller-Rabin bases they decide primality for
+/// every n < 3.18 * 10^23 (Sorenson and Webster), so for all u64.
+const MR_BASES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+/// How many of `MR_BASES` are tried above 64 bits. The test is not exact
+/// there anyway, and const evaluation is slow: base 2 alone takes seconds
+/// for a 521-bit modulus.
+const WIDE_BASES: usize = 1;
+
+/// Whether n is prime, by Miller-Rabin to the bases in `MR_BASES`, for the
+/// element types to reject a composite `MODULUS` at compile time
+///
+/// Exact for n < 2^64. Above that only base 2 is tried: a composite made by
+/// mistake fails, but one built to be a strong pseudoprime to base 2 would
+/// not. `PrimeFieldParams::check` runs Baillie-PSW.
+pub(crate) const fn is_probable_prime<const N: usize>(n: &[u64; N]) -> bool {
+    if num_bits(n) < 2 {
+        return false;
+    }
+    let mut i = 0;
+    while i < MR_BASES.len() {
+        let q = MR_BASES[i];
+        if eq(n, &from_u64(q)) {
+            return true;
+        }
+        if rem_u64(n, q) == 0 {
+            return false;
+        }
+        i += 1;
+    }
+
+    // n is odd and above 37. Everything below is in Montgomery form.
+    let inv = mont_inv(n[0]);
+    let r = pow2_mod(&one(), 64 * N, n);
+    let r2 = pow2_mod(&r, 64 * N, n);
+    let minus_one = sub_mod(&[0; N], &r, n);
+
+    // n - 1 = d 2^s with d odd
+    let n_minus_1 = sbb(n, &one()).0;
+    let s = trailing_zeros(&n_minus_1);
+    let d = shr(&n_minus_1, s);
+
+    let bases = match num_bits(n) <= 64 {
+        true => MR_BASES.len(),
+        false => WIDE_BASES,
+    };
+    let mut i = 0;
+    while i < bases {
+        let a = mont_mul(&from_u64(MR_BASES[i]), &r2, n, inv);
+
+        // x = a^d, left to right
+        let mut x = r;
+        let mut bit = num_bits(&d);
+        while bit > 0 {
+            bit -= 1;
+            x = mont_mul(&x, &x, n, inv);
+            if (d[bit as usize / 64] >> (bit % 64)) & 1 == 1 {
+                x = match MR_BASES[i] {
+                    2 => add_mod(&x, &x, n),
+                    _ => mont_mul(&x, &a, n, inv),
+                };
+            }
+        }
+
+        // a is a witness unless x = 1 or x^(2^j) = -1 for some j < s
+        if !eq(&x, &r) && !eq(&x, &minus_one) {
+            let mut j = 1;
+            loop {
+                if j == s {
+                    return false;
+                }
+                x = mont_mul(&x, &x, n, inv);
+                if eq(&x, &minus_one) {
+                    break;
+                }
+                j += 1;
+            }
+        }
+        i += 1;
+    }
+    true
+}
+
+const fn from_u64<const N: usize>(x: u64) -> [u64; N] {
+    let mut res = [0u64; N];
+    res[0] = x;
+    res
+}
+
+const fn eq<const N: usize>(a: &[u64; N], b: &[u64; N]) -> bool {
+    let mut i = 0;
+    while i < N {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// a mod q
+const fn rem_u64<const N: usize>(a: &[u64; N], q: u64) -> u64 {
+    let mut r = 0u128;
+    let mut i = N;
+    while i > 0 {
+        i -= 1;
+        r = ((r << 64) | a[i] as u128) % q as u128;
+    }
+    r as u64
+}
+
+/// Number of trailing zero bits of a non-zero a
+const fn trailing_zeros<const N: usize>(a: &[u64; N]) -> u32 {
+    let mut i = 0;
+    while a[i] == 0 {
+        i += 1;
+    }
+    64 * i as u32 + a[i].trailing_zeros()
+}
+
+/// a >> k for k < 64 N
+const fn shr<const N: usize>(a: &[u64; N], k: u32) -> [u64; N] {
+    let (limbs, bits) = ((k / 64) as usize, k % 64);
+    let mut res = [0u64; N];
+    let mut i = 0;
+    while i + limbs < N {
+        res[i] = a[i + limbs] >> bits;
+        if bits > 0 && i + limbs + 1 < N {
+            res[i] |= a[i + limbs + 1] << (64 - bits);
+        }
+        i += 1;
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_bigint::prime::probably_prime;
+    use num_bigint::BigUint;
+
+    #[test]
+    fn is_probable_prime_matches_baillie_psw() {
+        for n in (0..3000u64).chain(u64::MAX - 3000..=u64::MAX) {
+            let expected = probably_prime(&BigUint::from(n), 0);
+            assert_eq!(is_probable_prime(&[n]), expected, "{}", n);
+            assert_eq!(is_probable_prime(&[n, 0, 0, 0]), expected, "{}", n);
+        }
+
+        // Carmichael numbers and strong pseudoprimes to the first few bases
+        for n in [561, 41041, 3215031751, 2152302898747, 3474749660383] {
+            assert!(!is_probable_prime(&[n]), "{}", n);
+        }
+        // 2^127 - 1 and 2^127 + 1 = 3 * 56713727820156410577229101238628035243
+        assert!(is_probable_prime(&[u64::MAX, u64::MAX >> 1]));
+        assert!(!is_probable_prime(&[1, 1 << 63]));
+        // 2^521 - 1 and 2^521 - 3
+        let mut m521 = [u64::MAX; 9];
+        m521[8] = 0x1ff;
+        assert!(is_probable_prime(&m521));
+        m521[0] -= 2;
+        assert!(!is_probable_prime(&m521));
+    }
+}
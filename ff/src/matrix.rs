@@ -0,0 +1,604 @@
+//! Dense matrices over any `Field`, with Gaussian elimination for rank,
+//! determinant, inverse, kernel and linear systems
+//!
+//! Vectors are plain slices and `Vec`s. Elimination picks the first
+//! non-zero pivot of a column, so everything here is variable time and
+//! meant for public data (erasure codes, constraint systems).
+//!
+//! The operators panic on mismatched dimensions, like `+` on elements of
+//! different extension fields. The methods that can fail on their input
+//! return `Error::DimensionMismatch`, `Error::SingularMatrix`,
+//! `Error::NoSolution` or `Error::DivisionByZero` instead.
+use alloc::{vec, vec::Vec};
+use core::ops::{Add, Index, IndexMut, Mul, Neg, Sub};
+
+use crate::errors::{Error, Result};
+use crate::field::Field;
+
+/// A rows x cols matrix, stored row-major
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Matrix<F: Field> {
+    rows: usize,
+    cols: usize,
+    data: Vec<F>,
+}
+
+impl<F: Field> Matrix<F> {
+    /// A rows x cols matrix from its entries in row-major order,
+    /// `Error::DimensionMismatch` unless there are rows * cols of them
+    pub fn new(rows: usize, cols: usize, data: Vec<F>) -> Result<Self> {
+        if data.len() != rows * cols {
+            return Err(Error::DimensionMismatch);
+        }
+        Ok(Self { rows, cols, data })
+    }
+
+    /// `Error::DimensionMismatch` if the rows differ in length
+    pub fn from_rows(rows: Vec<Vec<F>>) -> Result<Self> {
+        let cols = rows.first().map_or(0, Vec::len);
+        if rows.iter().any(|row| row.len() != cols) {
+            return Err(Error::DimensionMismatch);
+        }
+        Ok(Self {
+            rows: rows.len(),
+            cols,
+            data: rows.into_iter().flatten().collect(),
+        })
+    }
+
+    pub fn zero(rows: usize, cols: usize) -> Self {
+        Self {
+            rows,
+            cols,
+            data: vec![F::zero(); rows * cols],
+        }
+    }
+
+    pub fn identity(n: usize) -> Self {
+        let mut id = Self::zero(n, n);
+        for i in 0..n {
+            id[(i, i)] = F::one();
+        }
+        id
+    }
+
+    /// The len(points) x cols matrix with rows 1, x, x^2, .., x^(cols-1),
+    /// which evaluates a polynomial given by its coefficients at the points.
+    /// Invertible iff it is square and the points are distinct.
+    pub fn vandermonde(points: &[F], cols: usize) -> Self {
+        let mut m = Self::zero(points.len(), cols);
+        for (i, x) in points.iter().enumerate() {
+            let mut power = F::one();
+            for j in 0..cols {
+                m[(i, j)] = power.clone();
+                power = power * x;
+            }
+        }
+        m
+    }
+
+    /// The matrix with entries 1 / (x_i - y_j). Every square submatrix of
+    /// it is invertible when the x_i and y_j are all distinct, which is what
+    /// MDS erasure codes want. `Error::DivisionByZero` if some x_i = y_j.
+    pub fn cauchy(xs: &[F], ys: &[F]) -> Result<Self> {
+        let mut data = Vec::with_capacity(xs.len() * ys.len());
+        for x in xs {
+            for y in ys {
+                data.push((x.clone() - y).inv().ok_or(Error::DivisionByZero)?);
+            }
+        }
+        Ok(Self {
+            rows: xs.len(),
+            cols: ys.len(),
+            data,
+        })
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn is_square(&self) -> bool {
+        self.rows == self.cols
+    }
+
+    pub fn row(&self, i: usize) -> &[F] {
+        &self.data[i * self.cols..(i + 1) * self.cols]
+    }
+
+    pub fn col(&self, j: usize) -> Vec<F> {
+        (0..self.rows).map(|i| self[(i, j)].clone()).collect()
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut t = Self::zero(self.cols, self.rows);
+        for i in 0..self.rows {
+            for j in 0..self.cols {
+                t[(j, i)] = self[(i, j)].clone();
+            }
+        }
+        t
+    }
+
+    /// self * v for a column vector v. Panics unless len(v) = cols.
+    pub fn mul_vec(&self, v: &[F]) -> Vec<F> {
+        assert_eq!(v.len(), self.cols, "matrix dimensions do not match");
+        (0..self.rows).map(|i| dot(self.row(i), v)).collect()
+    }
+
+    pub fn scale(&self, c: &F) -> Self {
+        self.map(|x| x.clone() * c)
+    }
+
+    /// Reduced row echelon form: every pivot is 1 and alone in its column
+    pub fn rref(&self) -> Self {
+        let mut r = self.clone();
+        r.eliminate();
+        r
+    }
+
+    pub fn rank(&self) -> usize {
+        self.rref_pivots().1.len()
+    }
+
+    /// `Error::DimensionMismatch` for a non-square matrix
+    pub fn determinant(&self) -> Result<F> {
+        if !self.is_square() {
+            return Err(Error::DimensionMismatch);
+        }
+
+        // forward elimination only, the determinant is the product of the
+        // pivots, negated once per row swap
+        let mut m = self.clone();
+        let mut det = F::one();
+        for c in 0..m.cols {
+            let Some(p) = (c..m.rows).find(|&i| !m[(i, c)].is_zero()) else {
+                return Ok(F::zero());
+            };
+            if p != c {
+                m.swap_rows(p, c);
+                det = -det;
+            }
+            let pivot = m[(c, c)].clone();
+            let pivot_inv = pivot.inv().expect("non-zero pivot");
+            for i in c + 1..m.rows {
+                let factor = m[(i, c)].clone() * &pivot_inv;
+                m.sub_row_multiple(i, c, &factor);
+            }
+            det = det * pivot;
+        }
+        Ok(det)
+    }
+
+    /// `Error::DimensionMismatch` for a non-square matrix,
+    /// `Error::SingularMatrix` if there is no inverse
+    pub fn inverse(&self) -> Result<Self> {
+        if !self.is_square() {
+            return Err(Error::DimensionMismatch);
+        }
+        let n = self.rows;
+
+        // [A | I] reduces to [I | A^-1]
+        let mut aug = self.hstack(&Self::identity(n));
+        let pivots = aug.eliminate();
+        // the pivots increase, so the left half reduced to I iff the first
+        // n of them are 0..n
+        if pivots.len() < n || pivots.iter().take(n).any(|&c| c >= n) {
+            return Err(Error::SingularMatrix);
+        }
+        Ok(aug.columns(n..2 * n))
+    }
+
+    /// A basis of { x : self * x = 0 }, empty if the columns are
+    /// independent
+    pub fn kernel(&self) -> Vec<Vec<F>> {
+        let (r, pivots) = self.rref_pivots();
+        let free = (0..self.cols).filter(|c| !pivots.contains(c));
+
+        // free variable f set to 1, the others to 0, and each pivot variable
+        // from its row of the RREF
+        free.map(|f| {
+            let mut v = vec![F::zero(); self.cols];
+            v[f] = F::one();
+            for (i, &c) in pivots.iter().enumerate() {
+                v[c] = -r[(i, f)].clone();
+            }
+            v
+        })
+        .collect()
+    }
+
+    /// The x with self * x = b
+    ///
+    /// `Error::DimensionMismatch` unless len(b) = rows, `Error::NoSolution`
+    /// for an inconsistent system and `Error::SingularMatrix` when the
+    /// solution is not unique, i.e. the rank is below the number of columns.
+    /// The full solution set is then any solution plus the `kernel`.
+    pub fn solve(&self, b: &[F]) -> Result<Vec<F>> {
+        if b.len() != self.rows {
+            return Err(Error::DimensionMismatch);
+        }
+
+        let column = Self {
+            rows: b.len(),
+            cols: 1,
+            data: b.to_vec(),
+        };
+        let mut aug = self.hstack(&column);
+        let pivots = aug.eliminate();
+        if pivots.last() == Some(&self.cols) {
+            return Err(Error::NoSolution);
+        }
+        if pivots.len() < self.cols {
+            return Err(Error::SingularMatrix);
+        }
+        Ok(aug.col(self.cols)[..self.cols].to_vec())
+    }
+
+    /// [self | rhs]. Panics unless the row counts match.
+    pub fn hstack(&self, rhs: &Self) -> Self {
+        assert_eq!(self.rows, rhs.rows, "matrix dimensions do not match");
+        let data = (0..self.rows)
+            .flat_map(|i| self.row(i).iter().chain(rhs.row(i)).cloned())
+            .collect();
+        Self {
+            rows: self.rows,
+            cols: self.cols + rhs.cols,
+            data,
+        }
+    }
+
+    fn columns(&self, range: core::ops::Range<usize>) -> Self {
+        let data = (0..self.rows)
+            .flat_map(|i| self.row(i)[range.clone()].iter().cloned())
+            .collect();
+        Self {
+            rows: self.rows,
+            cols: range.len(),
+            data,
+        }
+    }
+
+    fn rref_pivots(&self) -> (Self, Vec<usize>) {
+        let mut r = self.clone();
+        let pivots = r.eliminate();
+        (r, pivots)
+    }
+
+    /// Gauss-Jordan elimination to RREF in place, returning the pivot
+    /// column of each non-zero row
+    fn eliminate(&mut self) -> Vec<usize> {
+        let mut pivots = Vec::new();
+        for c in 0..self.cols {
+            let r = pivots.len();
+            if r == self.rows {
+                break;
+            }
+            let Some(p) = (r..self.rows).find(|&i| !self[(i, c)].is_zero()) else {
+                continue;
+            };
+            self.swap_rows(p, r);
+
+            let pivot_inv = self[(r, c)].inv().expect("non-zero pivot");
+            for x in &mut self.data[r * self.cols..(r + 1) * self.cols] {
+                *x = x.clone() * &pivot_inv;
+            }
+            for i in 0..self.rows {
+                if i != r && !self[(i, c)].is_zero() {
+                    let factor = self[(i, c)].clone();
+                    self.sub_row_multiple(i, r, &factor);
+                }
+            }
+            pivots.push(c);
+        }
+        pivots
+    }
+
+    fn swap_rows(&mut self, a: usize, b: usize) {
+        for j in 0..self.cols {
+            self.data.swap(a * self.cols + j, b * self.cols + j);
+        }
+    }
+
+    /// row[i] -= factor * row[src]
+    fn sub_row_multiple(&mut self, i: usize, src: usize, factor: &F) {
+        for j in 0..self.cols {
+            let delta = self[(src, j)].clone() * factor;
+            self[(i, j)] = self[(i, j)].clone() - delta;
+        }
+    }
+
+    fn map(&self, f: impl Fn(&F) -> F) -> Self {
+        Self {
+            rows: self.rows,
+            cols: self.cols,
+            data: self.data.iter().map(f).collect(),
+        }
+    }
+
+    fn zip_with(&self, rhs: &Self, f: impl Fn(&F, &F) -> F) -> Self {
+        assert!(
+            self.rows == rhs.rows && self.cols == rhs.cols,
+            "matrix dimensions do not match"
+        );
+        Self {
+            rows: self.rows,
+            cols: self.cols,
+            data: self
+                .data
+                .iter()
+                .zip(&rhs.data)
+                .map(|(a, b)| f(a, b))
+                .collect(),
+        }
+    }
+}
+
+fn dot<F: Field>(a: &[F], b: &[F]) -> F {
+    a.iter()
+        .zip(b)
+        .fold(F::zero(), |acc, (x, y)| acc + x.clone() * y)
+}
+
+/////////////////////////////////////////////
+/////////////// Operator Overloads
+/////////////////////////////////////////////
+///// Indexing
+impl<F: Field> Index<(usize, usize)> for Matrix<F> {
+    type Output = F;
+    fn index(&self, (i, j): (usize, usize)) -> &F {
+        assert!(i < self.rows && j < self.cols, "matrix index out of range");
+        &self.data[i * self.cols + j]
+    }
+}
+impl<F: Field> IndexMut<(usize, usize)> for Matrix<F> {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut F {
+        assert!(i < self.rows && j < self.cols, "matrix index out of range");
+        &mut self.data[i * self.cols + j]
+    }
+}
+///// Addition
+impl<F: Field> Add<&Matrix<F>> for &Matrix<F> {
+    type Output = Matrix<F>;
+    fn add(self, rhs: &Matrix<F>) -> Matrix<F> {
+        self.zip_with(rhs, |a, b| a.clone() + b)
+    }
+}
+///// Subtraction
+impl<F: Field> Sub<&Matrix<F>> for &Matrix<F> {
+    type Output = Matrix<F>;
+    fn sub(self, rhs: &Matrix<F>) -> Matrix<F> {
+        self.zip_with(rhs, |a, b| a.clone() - b)
+    }
+}
+///// Multiplication
+impl<F: Field> Mul<&Matrix<F>> for &Matrix<F> {
+    type Output = Matrix<F>;
+    fn mul(self, rhs: &Matrix<F>) -> Matrix<F> {
+        assert_eq!(self.cols, rhs.rows, "matrix dimensions do not match");
+        let rhs_t = rhs.transpose();
+        let mut prod = Matrix::zero(self.rows, rhs.cols);
+        for i in 0..self.rows {
+            for j in 0..rhs.cols {
+                prod[(i, j)] = dot(self.row(i), rhs_t.row(j));
+            }
+        }
+        prod
+    }
+}
+///// Neg
+impl<F: Field> Neg for &Matrix<F> {
+    type Output = Matrix<F>;
+    fn neg(self) -> Matrix<F> {
+        self.map(|x| -x.clone())
+    }
+}
+impl<F: Field> Neg for Matrix<F> {
+    type Output = Matrix<F>;
+    fn neg(self) -> Matrix<F> {
+        -&self
+    }
+}
+forward_binop!(impl<F: Field> Add, add for Matrix<F>);
+forward_binop!(impl<F: Field> Sub, sub for Matrix<F>);
+forward_binop!(impl<F: Field> Mul, mul for Matrix<F>);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bn254::Bn254Fr;
+    use crate::gf2n::{Aes, GF2n};
+    use crate::p_u256::U256FieldElement;
+    use crate::p_u64::U64FieldElement;
+    use primitive_types::U256;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    test_prime_field!(P97, U256([97, 0, 0, 0]));
+
+    type F = U64FieldElement<P97>;
+
+    fn m(rows: &[&[u64]]) -> Matrix<F> {
+        Matrix::from_rows(
+            rows.iter()
+                .map(|row| row.iter().map(|x| F::new(*x)).collect())
+                .collect(),
+        )
+        .unwrap()
+    }
+
+    fn v(xs: &[u64]) -> Vec<F> {
+        xs.iter().map(|x| F::new(*x)).collect()
+    }
+
+    fn random<G: Field>(rows: usize, cols: usize, rng: &mut StdRng) -> Matrix<G> {
+        let data = (0..rows * cols).map(|_| G::random(rng)).collect();
+        Matrix::new(rows, cols, data).unwrap()
+    }
+
+    #[test]
+    fn construction() {
+        assert_eq!(
+            Matrix::new(2, 2, v(&[1, 2, 3])),
+            Err(Error::DimensionMismatch)
+        );
+        assert_eq!(
+            Matrix::from_rows(vec![v(&[1, 2]), v(&[3])]),
+            Err(Error::DimensionMismatch)
+        );
+        let a = m(&[&[1, 2, 3], &[4, 5, 6]]);
+        assert_eq!((a.rows(), a.cols()), (2, 3));
+        assert_eq!(a.row(1), v(&[4, 5, 6]).as_slice());
+        assert_eq!(a.col(2), v(&[3, 6]));
+        assert_eq!(a[(1, 0)], F::new(4));
+        assert_eq!(a.transpose(), m(&[&[1, 4], &[2, 5], &[3, 6]]));
+        assert_eq!(a.transpose().transpose(), a);
+    }
+
+    #[test]
+    fn arithmetic() {
+        let a = m(&[&[1, 2], &[3, 4]]);
+        let b = m(&[&[0, 1], &[1, 0]]);
+        assert_eq!(&a * &b, m(&[&[2, 1], &[4, 3]]));
+        assert_eq!(&b * &a, m(&[&[3, 4], &[1, 2]]));
+        assert_eq!(&a + &b, m(&[&[1, 3], &[4, 4]]));
+        assert_eq!(&a - &a, Matrix::zero(2, 2));
+        assert_eq!(-&a + &a, Matrix::zero(2, 2));
+        assert_eq!(&a * Matrix::identity(2), a);
+        assert_eq!(a.scale(&F::new(2)), &a + &a);
+        assert_eq!(a.mul_vec(&v(&[1, 1])), v(&[3, 7]));
+
+        // (AB)^T = B^T A^T on non-square shapes
+        let c = m(&[&[1, 2, 3], &[4, 5, 6]]);
+        let d = m(&[&[7, 8], &[9, 10], &[11, 12]]);
+        assert_eq!((&c * &d).transpose(), d.transpose() * c.transpose());
+    }
+
+    #[test]
+    #[should_panic(expected = "matrix dimensions do not match")]
+    fn mismatched_product() {
+        let _ = Matrix::<F>::identity(2) * Matrix::identity(3);
+    }
+
+    #[test]
+    fn elimination() {
+        let a = m(&[&[1, 2, 3], &[2, 4, 6], &[1, 0, 1]]);
+        assert_eq!(a.rank(), 2);
+        assert_eq!(a.determinant(), Ok(F::ZERO));
+        assert_eq!(a.inverse(), Err(Error::SingularMatrix));
+        // x + 2y + 3z = 0 and x + z = 0
+        let r = a.rref();
+        assert_eq!(r, m(&[&[1, 0, 1], &[0, 1, 1], &[0, 0, 0]]));
+        assert_eq!(a.kernel(), vec![v(&[96, 96, 1])]);
+        for k in a.kernel() {
+            assert_eq!(a.mul_vec(&k), v(&[0, 0, 0]));
+        }
+
+        let b = m(&[&[0, 2, 1], &[1, 0, 0], &[3, 1, 4]]);
+        // cofactor expansion along the second row: -1 * (2 * 4 - 1 * 1)
+        assert_eq!(b.determinant(), Ok(-F::new(7)));
+        assert_eq!(b.rank(), 3);
+        assert!(b.kernel().is_empty());
+        let b_inv = b.inverse().unwrap();
+        assert_eq!(&b * &b_inv, Matrix::identity(3));
+        assert_eq!(&b_inv * &b, Matrix::identity(3));
+
+        assert_eq!(Matrix::<F>::zero(2, 3).rank(), 0);
+        assert_eq!(Matrix::<F>::zero(2, 3).kernel().len(), 3);
+        assert_eq!(Matrix::<F>::zero(0, 0).determinant(), Ok(F::ONE));
+        assert_eq!(Matrix::<F>::identity(0).inverse(), Ok(Matrix::zero(0, 0)));
+        assert_eq!(
+            Matrix::<F>::zero(2, 3).determinant(),
+            Err(Error::DimensionMismatch)
+        );
+        assert_eq!(
+            Matrix::<F>::zero(2, 3).inverse(),
+            Err(Error::DimensionMismatch)
+        );
+    }
+
+    #[test]
+    fn solving() {
+        let a = m(&[&[2, 1], &[1, 3]]);
+        let x = a.solve(&v(&[5, 10])).unwrap();
+        assert_eq!(x, v(&[1, 3]));
+        assert_eq!(a.solve(&v(&[1])), Err(Error::DimensionMismatch));
+
+        // overdetermined but consistent
+        let tall = m(&[&[1, 0], &[0, 1], &[1, 1]]);
+        assert_eq!(tall.solve(&v(&[4, 5, 9])), Ok(v(&[4, 5])));
+        assert_eq!(tall.solve(&v(&[4, 5, 10])), Err(Error::NoSolution));
+
+        // singular: inconsistent or not unique
+        let s = m(&[&[1, 2], &[2, 4]]);
+        assert_eq!(s.solve(&v(&[1, 3])), Err(Error::NoSolution));
+        assert_eq!(s.solve(&v(&[1, 2])), Err(Error::SingularMatrix));
+        let wide = m(&[&[1, 2, 3]]);
+        assert_eq!(wide.solve(&v(&[1])), Err(Error::SingularMatrix));
+    }
+
+    #[test]
+    fn random_matrices() {
+        type Fr = U256FieldElement<Bn254Fr>;
+        let mut rng = StdRng::seed_from_u64(21);
+        for n in [1, 2, 5, 8] {
+            // a random matrix over a 254-bit field is invertible but with
+            // negligible probability
+            let a: Matrix<Fr> = random(n, n, &mut rng);
+            let b: Matrix<Fr> = random(n, n, &mut rng);
+            let a_inv = a.inverse().unwrap();
+            assert_eq!(&a * &a_inv, Matrix::identity(n));
+            assert_eq!(
+                (&a * &b).determinant().unwrap(),
+                a.determinant().unwrap() * b.determinant().unwrap()
+            );
+            assert_eq!(a.transpose().determinant(), a.determinant());
+
+            let x: Vec<Fr> = (0..n).map(|_| Fr::random(&mut rng)).collect();
+            assert_eq!(a.solve(&a.mul_vec(&x)), Ok(x));
+
+            // n x 2n has rank n and an n-dimensional kernel
+            let wide = a.hstack(&b);
+            assert_eq!(wide.rank(), n);
+            let kernel = wide.kernel();
+            assert_eq!(kernel.len(), n);
+            for k in kernel {
+                assert!(wide.mul_vec(&k).iter().all(Fr::is_zero));
+            }
+        }
+    }
+
+    #[test]
+    fn vandermonde_and_cauchy() {
+        // interpolation: solving V c = y gives the coefficients back
+        let points = v(&[1, 2, 3, 4]);
+        let vm = Matrix::vandermonde(&points, 4);
+        let coefficients = v(&[5, 0, 7, 1]);
+        let values = vm.mul_vec(&coefficients);
+        assert_eq!(values[1], F::new(5 + 7 * 4 + 8));
+        assert_eq!(vm.solve(&values), Ok(coefficients));
+        // det = prod_{i < j} (x_j - x_i) = 1 * 2 * 3 * 1 * 2 * 1
+        assert_eq!(vm.determinant(), Ok(F::new(12)));
+        assert_eq!(
+            Matrix::vandermonde(&v(&[1, 2, 1]), 3).inverse(),
+            Err(Error::SingularMatrix)
+        );
+
+        // every square submatrix of a Cauchy matrix is invertible, over a
+        // binary field as used by erasure codes
+        type G = GF2n<Aes>;
+        let xs: Vec<G> = (0..4).map(G::new).collect();
+        let ys: Vec<G> = (4..8).map(G::new).collect();
+        let c = Matrix::cauchy(&xs, &ys).unwrap();
+        assert_eq!((c.rows(), c.cols()), (4, 4));
+        assert_eq!(c[(1, 2)], (G::new(1) - G::new(6)).inv().unwrap());
+        assert!(c.inverse().is_ok());
+        let top_left = Matrix::from_rows(vec![c.row(0)[..2].to_vec(), c.row(1)[..2].to_vec()]);
+        assert!(top_left.unwrap().determinant().unwrap() != G::zero());
+        assert_eq!(Matrix::cauchy(&xs, &xs[..1]), Err(Error::DivisionByZero));
+    }
+}
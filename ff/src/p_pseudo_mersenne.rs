@@ -0,0 +1,481 @@
+//! Field backend for primes p = 2^256 - c with a small c, c < 2^64, which
+//! covers secp256k1's base field.
+//!
+//! Only this pseudo-Mersenne form is supported. Solinas primes whose c has
+//! more terms and is not small, such as P-256's
+//! 2^256 - 2^224 + 2^192 + 2^96 - 1, need a reduction written for their
+//! particular terms and are not handled here: `PseudoMersenneParams`
+//! rejects them at compile time, and they go through the Montgomery
+//! reduction of `U256FieldElement`.
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+use core::ops::{Add, Div, Mul, Neg, Sub};
+use core::str::FromStr;
+use primitive_types::U256;
+use rand_core::RngCore;
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
+use zeroize::Zeroize;
+
+use crate::errors::{Error, Result};
+use crate::field::{Field, PrimeField};
+use crate::limbs::{add_mod, is_probable_prime, num_bits, sbb, sub_if_geq, sub_mod};
+use crate::p_u256::{binary_inv, ct_inv, U256FieldElement};
+use crate::params::PseudoMersenneParams;
+
+/// Element of a prime field with a modulus p = 2^256 - c, c < 2^64, such as
+/// secp256k1's base field
+///
+/// Computes the same values as `U256FieldElement<P>`, but keeps them in
+/// canonical form and reduces products with the identity 2^256 = c (mod p):
+/// the high half of a 512-bit product is multiplied by c and added to the
+/// low half, twice, which is much cheaper than a Montgomery reduction.
+///
+/// The constant-time guarantees are the same as `U256FieldElement`'s.
+pub struct PseudoMersenneFieldElement<P: PseudoMersenneParams> {
+    num: U256,
+    _params: PhantomData<P>,
+}
+
+impl<P: PseudoMersenneParams> Clone for PseudoMersenneFieldElement<P> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<P: PseudoMersenneParams> Copy for PseudoMersenneFieldElement<P> {}
+
+impl<P: PseudoMersenneParams> core::fmt::Debug for PseudoMersenneFieldElement<P> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("PseudoMersenneFieldElement")
+            .field("num", &self.num)
+            .field("prime", &P::MODULUS)
+            .finish()
+    }
+}
+
+impl<P: PseudoMersenneParams> FromStr for PseudoMersenneFieldElement<P> {
+    type Err = Error;
+    /// Same as `from_hex_str`
+    fn from_str(num: &str) -> Result<Self> {
+        Self::from_hex_str(num)
+    }
+}
+
+impl<P: PseudoMersenneParams> From<U256FieldElement<P>> for PseudoMersenneFieldElement<P> {
+    fn from(elem: U256FieldElement<P>) -> Self {
+        Self::from_reduced(elem.num())
+    }
+}
+
+impl<P: PseudoMersenneParams> From<PseudoMersenneFieldElement<P>> for U256FieldElement<P> {
+    fn from(elem: PseudoMersenneFieldElement<P>) -> Self {
+        Self::new(elem.num)
+    }
+}
+
+/////////////////////////////////////////////
+/////////////// Operator Overloads
+/////////////////////////////////////////////
+///// Equality
+impl<P: PseudoMersenneParams> Eq for PseudoMersenneFieldElement<P> {}
+impl<P: PseudoMersenneParams> PartialEq for PseudoMersenneFieldElement<P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+///// Addition
+impl<P: PseudoMersenneParams> Add<&PseudoMersenneFieldElement<P>>
+    for &PseudoMersenneFieldElement<P>
+{
+    type Output = PseudoMersenneFieldElement<P>;
+    fn add(self, rhs: &PseudoMersenneFieldElement<P>) -> PseudoMersenneFieldElement<P> {
+        PseudoMersenneFieldElement::from_reduced(U256(add_mod(
+            &self.num.0,
+            &rhs.num.0,
+            &P::MODULUS.0,
+        )))
+    }
+}
+///// Subtraction
+impl<P: PseudoMersenneParams> Sub<&PseudoMersenneFieldElement<P>>
+    for &PseudoMersenneFieldElement<P>
+{
+    type Output = PseudoMersenneFieldElement<P>;
+    fn sub(self, rhs: &PseudoMersenneFieldElement<P>) -> PseudoMersenneFieldElement<P> {
+        PseudoMersenneFieldElement::from_reduced(U256(sub_mod(
+            &self.num.0,
+            &rhs.num.0,
+            &P::MODULUS.0,
+        )))
+    }
+}
+///// Multiplication
+impl<P: PseudoMersenneParams> Mul<&PseudoMersenneFieldElement<P>>
+    for &PseudoMersenneFieldElement<P>
+{
+    type Output = PseudoMersenneFieldElement<P>;
+    fn mul(self, rhs: &PseudoMersenneFieldElement<P>) -> PseudoMersenneFieldElement<P> {
+        let wide = mul_wide(&self.num, &rhs.num);
+        PseudoMersenneFieldElement::from_reduced(reduce_wide(
+            &wide,
+            PseudoMersenneFieldElement::<P>::C,
+            &P::MODULUS,
+        ))
+    }
+}
+///// Division
+impl<P: PseudoMersenneParams> Div<&PseudoMersenneFieldElement<P>>
+    for &PseudoMersenneFieldElement<P>
+{
+    type Output = PseudoMersenneFieldElement<P>;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: &PseudoMersenneFieldElement<P>) -> PseudoMersenneFieldElement<P> {
+        self * rhs.inv().expect("division by zero")
+    }
+}
+///// Neg
+impl<P: PseudoMersenneParams> Neg for &PseudoMersenneFieldElement<P> {
+    type Output = PseudoMersenneFieldElement<P>;
+    fn neg(self) -> PseudoMersenneFieldElement<P> {
+        PseudoMersenneFieldElement::ZERO - self
+    }
+}
+forward_ops!(impl<P: PseudoMersenneParams> for PseudoMersenneFieldElement<P>);
+forward_assign_ops!(impl<P: PseudoMersenneParams> for PseudoMersenneFieldElement<P>);
+
+/////////////////////////////////////////////
+/////////////// Field Requirements
+/////////////////////////////////////////////
+impl<P: PseudoMersenneParams> PseudoMersenneFieldElement<P> {
+    /// c = 2^256 - p
+    const C: u64 = {
+        let c = sbb(&[0; 4], &P::MODULUS.0).0;
+        assert!(
+            c[1] == 0 && c[2] == 0 && c[3] == 0,
+            "modulus is not of the form 2^256 - c with c < 2^64"
+        );
+        assert!(is_probable_prime(&P::MODULUS.0), "modulus is not prime");
+        c[0]
+    };
+
+    pub const ZERO: Self = Self::from_reduced(U256([0, 0, 0, 0]));
+    pub const ONE: Self = Self::from_reduced(U256([1, 0, 0, 0]));
+
+    /// Any `num` is accepted and reduced mod p
+    pub const fn new(num: U256) -> Self {
+        // 2^256 < 2p, so one subtraction is enough
+        Self::from_reduced(U256(sub_if_geq(&num.0, 0, &P::MODULUS.0)))
+    }
+
+    pub const fn from_u64(num: u64) -> Self {
+        Self::new(U256([num, 0, 0, 0]))
+    }
+
+    pub const fn prime() -> U256 {
+        P::MODULUS
+    }
+
+    /// Canonical representative in [0, prime)
+    pub fn num(&self) -> U256 {
+        self.num
+    }
+
+    pub fn to_bytes_be(&self) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        self.num.to_big_endian(&mut bytes);
+        bytes
+    }
+
+    pub fn to_bytes_le(&self) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        self.num.to_little_endian(&mut bytes);
+        bytes
+    }
+
+    /// See `U256FieldElement::from_bytes_be`
+    pub fn from_bytes_be(bytes: &[u8]) -> Result<Self> {
+        U256FieldElement::<P>::from_bytes_be(bytes).map(Self::from)
+    }
+
+    /// See `U256FieldElement::from_bytes_le`
+    pub fn from_bytes_le(bytes: &[u8]) -> Result<Self> {
+        U256FieldElement::<P>::from_bytes_le(bytes).map(Self::from)
+    }
+
+    /// See `U256FieldElement::from_hex_str`
+    pub fn from_hex_str(num: &str) -> Result<Self> {
+        U256FieldElement::<P>::from_hex_str(num).map(Self::from)
+    }
+
+    /// See `U256FieldElement::from_dec_str`
+    pub fn from_dec_str(num: &str) -> Result<Self> {
+        U256FieldElement::<P>::from_dec_str(num).map(Self::from)
+    }
+
+    /// self^exp in constant time for a fixed `exp.len()`, exponent as
+    /// little-endian u64 limbs
+    pub fn pow_ct(&self, exp: &[u64]) -> Self {
+        crate::field::pow_ct(self, exp)
+    }
+
+    pub fn exp_by_u64(&self, exp: u64) -> Self {
+        self.exp(&U256::from(exp))
+    }
+
+    /// Same as `U256FieldElement::exp`, variable time
+    pub fn exp(&self, exp: &U256) -> Self {
+        self.pow(&exp.0)
+    }
+
+    /// Constant-time inverse, see `U256FieldElement::inv_ct`
+    pub fn inv_ct(&self) -> Option<Self> {
+        let inv = ct_inv(&self.num, &P::MODULUS, 2 * Self::NUM_BITS);
+        match self.is_zero() {
+            true => None,
+            false => Some(Self::from_reduced(inv)),
+        }
+    }
+
+    const fn from_reduced(num: U256) -> Self {
+        Self {
+            num,
+            _params: PhantomData,
+        }
+    }
+}
+
+impl<P: PseudoMersenneParams> Field for PseudoMersenneFieldElement<P> {
+    fn zero() -> Self {
+        Self::ZERO
+    }
+
+    fn one() -> Self {
+        Self::ONE
+    }
+
+    fn is_zero(&self) -> bool {
+        self.num.is_zero()
+    }
+
+    fn double(&self) -> Self {
+        self + self
+    }
+
+    fn square(&self) -> Self {
+        self * self
+    }
+
+    /// Binary extended Euclid, variable-time. Use `inv_ct` for secrets.
+    fn inv(&self) -> Option<Self> {
+        if self.is_zero() {
+            return None;
+        }
+        Some(Self::from_reduced(binary_inv(&self.num, &P::MODULUS)))
+    }
+
+    fn characteristic() -> Vec<u64> {
+        P::MODULUS.0.to_vec()
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes_be().to_vec()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        Self::from_bytes_be(bytes).ok()
+    }
+
+    fn random<R: RngCore + ?Sized>(rng: &mut R) -> Self {
+        U256FieldElement::<P>::random(rng).into()
+    }
+}
+
+impl<P: PseudoMersenneParams> PrimeField for PseudoMersenneFieldElement<P> {
+    const NUM_BITS: u32 = num_bits(&P::MODULUS.0);
+
+    fn from_u64(num: u64) -> Self {
+        Self::from_u64(num)
+    }
+
+    fn multiplicative_generator() -> Option<Self> {
+        P::MULTIPLICATIVE_GENERATOR.map(Self::new)
+    }
+}
+
+/////////////////////////////////////////////
+/////////////// Constant Time
+/////////////////////////////////////////////
+impl<P: PseudoMersenneParams> ConstantTimeEq for PseudoMersenneFieldElement<P> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.num.0.ct_eq(&other.num.0)
+    }
+}
+
+impl<P: PseudoMersenneParams> ConditionallySelectable for PseudoMersenneFieldElement<P> {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        let mut num = [0u64; 4];
+        for (i, limb) in num.iter_mut().enumerate() {
+            *limb = u64::conditional_select(&a.num.0[i], &b.num.0[i], choice);
+        }
+        Self::from_reduced(U256(num))
+    }
+}
+
+impl<P: PseudoMersenneParams> Zeroize for PseudoMersenneFieldElement<P> {
+    fn zeroize(&mut self) {
+        self.num.0.zeroize();
+    }
+}
+
+/////////////////////////////////////////////
+/////////////// Reduction
+/////////////////////////////////////////////
+/// Full 512-bit product, schoolbook
+const fn mul_wide(a: &U256, b: &U256) -> [u64; 8] {
+    let (a, b) = (&a.0, &b.0);
+    let mut t = [0u64; 8];
+    let mut i = 0;
+    while i < 4 {
+        let mut carry = 0u64;
+        let mut j = 0;
+        while j < 4 {
+            let uv = t[i + j] as u128 + a[j] as u128 * b[i] as u128 + carry as u128;
+            t[i + j] = uv as u64;
+            carry = (uv >> 64) as u64;
+            j += 1;
+        }
+        t[i + 4] = carry;
+        i += 1;
+    }
+    t
+}
+
+/// t mod p for p = 2^256 - c and t < p^2
+///
+/// t = h * 2^256 + l = h * c + l (mod p). With h < 2^256 the first fold
+/// leaves at most 320 bits, so its top limb times c, below 2^128, is folded
+/// in the same way. That can carry out of 256 bits once more only if the
+/// low part wrapped, and then adding c cannot overflow again. The result is
+/// below 2^256 < 2p, so one conditional subtraction finishes it.
+const fn reduce_wide(t: &[u64; 8], c: u64, p: &U256) -> U256 {
+    let mut r = [0u64; 4];
+    let mut carry = 0u64;
+    let mut i = 0;
+    while i < 4 {
+        let uv = t[i] as u128 + t[i + 4] as u128 * c as u128 + carry as u128;
+        r[i] = uv as u64;
+        carry = (uv >> 64) as u64;
+        i += 1;
+    }
+
+    let uv = r[0] as u128 + carry as u128 * c as u128;
+    r[0] = uv as u64;
+    let mut carry = (uv >> 64) as u64;
+    let mut i = 1;
+    while i < 4 {
+        let uv = r[i] as u128 + carry as u128;
+        r[i] = uv as u64;
+        carry = (uv >> 64) as u64;
+        i += 1;
+    }
+
+    // carry is 0 or 1 here
+    let uv = r[0] as u128 + (carry * c) as u128;
+    r[0] = uv as u64;
+    let mut carry = (uv >> 64) as u64;
+    let mut i = 1;
+    while i < 4 {
+        let uv = r[i] as u128 + carry as u128;
+        r[i] = uv as u64;
+        carry = (uv >> 64) as u64;
+        i += 1;
+    }
+
+    U256(sub_if_geq(&r, 0, &p.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::params::{u256_from_hex, PrimeFieldParams};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    test_prime_field!(
+        Secp256k1P,
+        u256_from_hex("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F")
+    );
+    impl PseudoMersenneParams for Secp256k1P {}
+
+    // 2^256 - 189, the largest prime below 2^256
+    test_prime_field!(
+        P256m189,
+        u256_from_hex("ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff43")
+    );
+    impl PseudoMersenneParams for P256m189 {}
+
+    #[test]
+    fn params_are_prime() {
+        Secp256k1P::check().unwrap();
+        P256m189::check().unwrap();
+        assert_eq!(PseudoMersenneFieldElement::<Secp256k1P>::C, 0x1_0000_03d1);
+        assert_eq!(PseudoMersenneFieldElement::<P256m189>::C, 189);
+    }
+
+    /// Every operation agrees with the Montgomery backend
+    fn check_matches_montgomery<P: PseudoMersenneParams>() {
+        type Fast<P> = PseudoMersenneFieldElement<P>;
+        let minus_one = U256FieldElement::<P>::ZERO - U256FieldElement::ONE;
+        let mut rng = StdRng::seed_from_u64(5);
+        let mut samples: Vec<U256FieldElement<P>> = (0..30)
+            .map(|_| U256FieldElement::random(&mut rng))
+            .collect();
+        samples.extend([U256FieldElement::ZERO, U256FieldElement::ONE, minus_one]);
+
+        for a in &samples {
+            for b in &samples {
+                let (x, y) = (Fast::from(*a), Fast::from(*b));
+                assert_eq!(U256FieldElement::from(x + y), a + b);
+                assert_eq!(U256FieldElement::from(x - y), a - b);
+                assert_eq!(U256FieldElement::from(x * y), a * b);
+            }
+            let x = Fast::from(*a);
+            assert_eq!(U256FieldElement::from(-x), -a);
+            assert_eq!(x.inv().map(U256FieldElement::from), a.inv());
+            assert_eq!(x.inv_ct(), x.inv());
+            assert_eq!(x.sqrt().is_some(), a.sqrt().is_some());
+            if let Some(root) = x.sqrt() {
+                assert_eq!(root.square(), x);
+            }
+            for e in [U256::zero(), U256::one(), P::MODULUS - 1, U256::MAX] {
+                assert_eq!(U256FieldElement::from(x.exp(&e)), a.exp(&e));
+            }
+            assert_eq!(x.exp(&U256::zero()), Fast::ONE);
+        }
+
+        // values above p are reduced
+        assert_eq!(Fast::<P>::new(U256::MAX), Fast::new(U256::MAX - P::MODULUS));
+        assert_eq!(Fast::<P>::new(P::MODULUS), Fast::ZERO);
+    }
+
+    #[test]
+    fn matches_montgomery() {
+        check_matches_montgomery::<Secp256k1P>();
+        check_matches_montgomery::<P256m189>();
+    }
+
+    #[test]
+    fn bytes_and_strings() {
+        type F = PseudoMersenneFieldElement<Secp256k1P>;
+        let a = F::from_hex_str("0xdeadbeef").unwrap();
+        assert_eq!(a, F::from_dec_str("3735928559").unwrap());
+        assert_eq!(F::from_bytes_be(&a.to_bytes_be()), Ok(a));
+        assert_eq!(F::from_bytes_le(&a.to_bytes_le()), Ok(a));
+        assert_eq!(F::from_bytes(&a.to_bytes()), Some(a));
+        assert_eq!(a.to_bytes(), U256FieldElement::from(a).to_bytes());
+
+        let mut p = [0u8; 32];
+        Secp256k1P::MODULUS.to_big_endian(&mut p);
+        assert_eq!(F::from_bytes_be(&p), Err(Error::NonCanonical));
+        assert_eq!(F::from_hex_str(""), Err(Error::InvalidString));
+    }
+}
@@ -0,0 +1,745 @@
+use alloc::{vec, vec::Vec};
+use core::marker::PhantomData;
+use core::ops::{Add, Div, Mul, Neg, Sub};
+use core::str::FromStr;
+use rand_core::RngCore;
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
+use zeroize::Zeroize;
+
+use crate::errors::{Error, Result};
+use crate::field::{Field, PrimeField};
+use crate::limbs::is_probable_prime;
+use crate::p_u64::{redc_u64, U64FieldElement};
+use crate::parallel::{self, MaybeSync};
+use crate::params::SmallPrimeParams;
+use crate::primes::{BabyBear, Goldilocks, Mersenne31};
+
+pub type GoldilocksField = SmallFieldElement<Goldilocks>;
+pub type BabyBearField = SmallFieldElement<BabyBear>;
+pub type Mersenne31Field = SmallFieldElement<Mersenne31>;
+
+/// Lanes of a `PackedSmallField` used by the `batch_*` functions
+pub const PACK_WIDTH: usize = 8;
+
+/// Element of a prime field below 2^64 with a fast reduction, such as the
+/// STARK-friendly Goldilocks, BabyBear and Mersenne-31 fields
+///
+/// Computes the same values as `U64FieldElement<P>` and has the same API, but
+/// multiplies with one native 128-bit product followed by `P::reduce`
+/// instead of two generic Montgomery reductions. All operators are branch-free, so like
+/// `U64FieldElement`'s they run in constant time, and `exp`, `pow` and `inv`
+/// are variable time.
+pub struct SmallFieldElement<P: SmallPrimeParams> {
+    num: u64,
+    _params: PhantomData<P>,
+}
+
+impl<P: SmallPrimeParams> Clone for SmallFieldElement<P> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<P: SmallPrimeParams> Copy for SmallFieldElement<P> {}
+
+impl<P: SmallPrimeParams> core::fmt::Debug for SmallFieldElement<P> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SmallFieldElement")
+            .field("num", &self.num)
+            .field("prime", &Self::PRIME)
+            .finish()
+    }
+}
+
+impl<P: SmallPrimeParams> FromStr for SmallFieldElement<P> {
+    type Err = Error;
+    /// Same as `from_hex_str`
+    fn from_str(num: &str) -> Result<Self> {
+        Self::from_hex_str(num)
+    }
+}
+
+impl<P: SmallPrimeParams> From<U64FieldElement<P>> for SmallFieldElement<P> {
+    fn from(elem: U64FieldElement<P>) -> Self {
+        Self::from_reduced(elem.num())
+    }
+}
+
+impl<P: SmallPrimeParams> From<SmallFieldElement<P>> for U64FieldElement<P> {
+    fn from(elem: SmallFieldElement<P>) -> Self {
+        Self::new(elem.num)
+    }
+}
+
+/////////////////////////////////////////////
+/////////////// Operator Overloads
+/////////////////////////////////////////////
+///// Equality
+impl<P: SmallPrimeParams> Eq for SmallFieldElement<P> {}
+impl<P: SmallPrimeParams> PartialEq for SmallFieldElement<P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+///// Addition
+impl<P: SmallPrimeParams> Add<&SmallFieldElement<P>> for &SmallFieldElement<P> {
+    type Output = SmallFieldElement<P>;
+    fn add(self, rhs: &SmallFieldElement<P>) -> SmallFieldElement<P> {
+        SmallFieldElement::from_reduced(add_mod(self.num, rhs.num, SmallFieldElement::<P>::PRIME))
+    }
+}
+///// Subtraction
+impl<P: SmallPrimeParams> Sub<&SmallFieldElement<P>> for &SmallFieldElement<P> {
+    type Output = SmallFieldElement<P>;
+    fn sub(self, rhs: &SmallFieldElement<P>) -> SmallFieldElement<P> {
+        SmallFieldElement::from_reduced(sub_mod(self.num, rhs.num, SmallFieldElement::<P>::PRIME))
+    }
+}
+///// Multiplication
+impl<P: SmallPrimeParams> Mul<&SmallFieldElement<P>> for &SmallFieldElement<P> {
+    type Output = SmallFieldElement<P>;
+    fn mul(self, rhs: &SmallFieldElement<P>) -> SmallFieldElement<P> {
+        SmallFieldElement::from_reduced(P::reduce(self.num as u128 * rhs.num as u128))
+    }
+}
+///// Division
+impl<P: SmallPrimeParams> Div<&SmallFieldElement<P>> for &SmallFieldElement<P> {
+    type Output = SmallFieldElement<P>;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: &SmallFieldElement<P>) -> SmallFieldElement<P> {
+        self * rhs.inv().expect("division by zero")
+    }
+}
+///// Neg
+impl<P: SmallPrimeParams> Neg for &SmallFieldElement<P> {
+    type Output = SmallFieldElement<P>;
+    fn neg(self) -> SmallFieldElement<P> {
+        SmallFieldElement::ZERO - self
+    }
+}
+forward_ops!(impl<P: SmallPrimeParams> for SmallFieldElement<P>);
+forward_assign_ops!(impl<P: SmallPrimeParams> for SmallFieldElement<P>);
+
+/////////////////////////////////////////////
+/////////////// Field Requirements
+/////////////////////////////////////////////
+impl<P: SmallPrimeParams> SmallFieldElement<P> {
+    const PRIME: u64 = {
+        let m = P::MODULUS.0;
+        assert!(
+            m[1] == 0 && m[2] == 0 && m[3] == 0,
+            "modulus does not fit in a u64"
+        );
+        assert!(is_probable_prime(&[m[0]]), "modulus is not prime");
+        m[0]
+    };
+
+    pub const ZERO: Self = Self::from_reduced(0);
+    pub const ONE: Self = Self::from_reduced(1);
+
+    pub const fn new(num: u64) -> Self {
+        Self::from_reduced(num % Self::PRIME)
+    }
+
+    pub const fn prime() -> u64 {
+        Self::PRIME
+    }
+
+    pub fn num(&self) -> u64 {
+        self.num
+    }
+
+    pub fn to_bytes_be(&self) -> [u8; 8] {
+        self.num.to_be_bytes()
+    }
+
+    pub fn to_bytes_le(&self) -> [u8; 8] {
+        self.num.to_le_bytes()
+    }
+
+    /// See `U64FieldElement::from_bytes_be`
+    pub fn from_bytes_be(bytes: &[u8]) -> Result<Self> {
+        U64FieldElement::<P>::from_bytes_be(bytes).map(Self::from)
+    }
+
+    /// See `U64FieldElement::from_bytes_le`
+    pub fn from_bytes_le(bytes: &[u8]) -> Result<Self> {
+        U64FieldElement::<P>::from_bytes_le(bytes).map(Self::from)
+    }
+
+    /// See `U64FieldElement::from_hex_str`
+    pub fn from_hex_str(num: &str) -> Result<Self> {
+        U64FieldElement::<P>::from_hex_str(num).map(Self::from)
+    }
+
+    /// See `U64FieldElement::from_dec_str`
+    pub fn from_dec_str(num: &str) -> Result<Self> {
+        U64FieldElement::<P>::from_dec_str(num).map(Self::from)
+    }
+
+    /// Same as `*`, which is already a single 128-bit product here. Kept so
+    /// that code written against `U64FieldElement` compiles unchanged.
+    pub fn mul_vartime(&self, rhs: &Self) -> Self {
+        self * rhs
+    }
+
+    /// self^exp in constant time for a fixed `exp.len()`, exponent as
+    /// little-endian u64 limbs
+    pub fn pow_ct(&self, exp: &[u64]) -> Self {
+        crate::field::pow_ct(self, exp)
+    }
+
+    /// self^exp by a sliding window, variable time
+    pub fn exp(&self, exp: &u64) -> Self {
+        self.pow(&[*exp])
+    }
+
+    /// a[i] += b[i], `PACK_WIDTH` elements at a time. Panics if the lengths
+    /// differ.
+    pub fn batch_add_assign(a: &mut [Self], b: &[Self]) {
+        batch_assign(a, b, |x, y| x + y, |x, y| x + y);
+    }
+
+    /// a[i] -= b[i], see `batch_add_assign`
+    pub fn batch_sub_assign(a: &mut [Self], b: &[Self]) {
+        batch_assign(a, b, |x, y| x - y, |x, y| x - y);
+    }
+
+    /// a[i] *= b[i], see `batch_add_assign`
+    pub fn batch_mul_assign(a: &mut [Self], b: &[Self]) {
+        batch_assign(a, b, |x, y| x * y, |x, y| x * y);
+    }
+
+    const fn from_reduced(num: u64) -> Self {
+        Self {
+            num,
+            _params: PhantomData,
+        }
+    }
+}
+
+impl<P: SmallPrimeParams> Field for SmallFieldElement<P> {
+    fn zero() -> Self {
+        Self::ZERO
+    }
+
+    fn one() -> Self {
+        Self::ONE
+    }
+
+    fn is_zero(&self) -> bool {
+        self.num == 0
+    }
+
+    fn double(&self) -> Self {
+        self + self
+    }
+
+    fn square(&self) -> Self {
+        self * self
+    }
+
+    // Uses Fermat's little
+    fn inv(&self) -> Option<Self> {
+        if self.is_zero() {
+            return None;
+        }
+        Some(self.exp(&(Self::PRIME - 2)))
+    }
+
+    fn characteristic() -> Vec<u64> {
+        vec![Self::PRIME]
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes_be().to_vec()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        Self::from_bytes_be(bytes).ok()
+    }
+
+    fn random<R: RngCore + ?Sized>(rng: &mut R) -> Self {
+        U64FieldElement::<P>::random(rng).into()
+    }
+}
+
+impl<P: SmallPrimeParams> PrimeField for SmallFieldElement<P> {
+    const NUM_BITS: u32 = 64 - Self::PRIME.leading_zeros();
+
+    fn from_u64(num: u64) -> Self {
+        Self::new(num)
+    }
+
+    fn multiplicative_generator() -> Option<Self> {
+        P::MULTIPLICATIVE_GENERATOR.map(|g| Self::new(g.low_u64()))
+    }
+}
+
+/////////////////////////////////////////////
+/////////////// Constant Time
+/////////////////////////////////////////////
+impl<P: SmallPrimeParams> ConstantTimeEq for SmallFieldElement<P> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.num.ct_eq(&other.num)
+    }
+}
+
+impl<P: SmallPrimeParams> ConditionallySelectable for SmallFieldElement<P> {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        Self::from_reduced(u64::conditional_select(&a.num, &b.num, choice))
+    }
+}
+
+impl<P: SmallPrimeParams> Zeroize for SmallFieldElement<P> {
+    fn zeroize(&mut self) {
+        self.num.zeroize();
+    }
+}
+
+/////////////////////////////////////////////
+/////////////// Packed
+/////////////////////////////////////////////
+/// `W` elements of the same field operated on lane by lane
+///
+/// The lanes are plain u64s and every operation is a fixed, branch-free
+/// loop over them, which the compiler turns into SIMD code where the target
+/// has it. Division is left out on purpose, use `batch_inverse`.
+pub struct PackedSmallField<P: SmallPrimeParams, const W: usize> {
+    lanes: [u64; W],
+    _params: PhantomData<P>,
+}
+
+impl<P: SmallPrimeParams, const W: usize> Clone for PackedSmallField<P, W> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<P: SmallPrimeParams, const W: usize> Copy for PackedSmallField<P, W> {}
+
+impl<P: SmallPrimeParams, const W: usize> core::fmt::Debug for PackedSmallField<P, W> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("PackedSmallField")
+            .field("lanes", &self.lanes)
+            .field("prime", &SmallFieldElement::<P>::PRIME)
+            .finish()
+    }
+}
+
+impl<P: SmallPrimeParams, const W: usize> Eq for PackedSmallField<P, W> {}
+impl<P: SmallPrimeParams, const W: usize> PartialEq for PackedSmallField<P, W> {
+    fn eq(&self, other: &Self) -> bool {
+        self.lanes.ct_eq(&other.lanes).into()
+    }
+}
+
+impl<P: SmallPrimeParams, const W: usize> PackedSmallField<P, W> {
+    pub const ZERO: Self = Self::broadcast(SmallFieldElement::ZERO);
+    pub const ONE: Self = Self::broadcast(SmallFieldElement::ONE);
+
+    /// Every lane set to `elem`
+    pub const fn broadcast(elem: SmallFieldElement<P>) -> Self {
+        Self::from_lanes([elem.num; W])
+    }
+
+    pub fn from_array(elems: [SmallFieldElement<P>; W]) -> Self {
+        Self::from_lanes(elems.map(|e| e.num))
+    }
+
+    /// Panics unless `elems.len() == W`
+    pub fn from_slice(elems: &[SmallFieldElement<P>]) -> Self {
+        assert_eq!(elems.len(), W, "slice length is not the packing width");
+        let mut lanes = [0u64; W];
+        for (lane, e) in lanes.iter_mut().zip(elems) {
+            *lane = e.num;
+        }
+        Self::from_lanes(lanes)
+    }
+
+    pub fn to_array(&self) -> [SmallFieldElement<P>; W] {
+        self.lanes.map(SmallFieldElement::from_reduced)
+    }
+
+    /// Panics unless `out.len() == W`
+    pub fn write_to_slice(&self, out: &mut [SmallFieldElement<P>]) {
+        assert_eq!(out.len(), W, "slice length is not the packing width");
+        for (e, lane) in out.iter_mut().zip(self.lanes) {
+            *e = SmallFieldElement::from_reduced(lane);
+        }
+    }
+
+    pub fn lane(&self, i: usize) -> SmallFieldElement<P> {
+        SmallFieldElement::from_reduced(self.lanes[i])
+    }
+
+    pub fn square(&self) -> Self {
+        self * self
+    }
+
+    fn zip_with(&self, rhs: &Self, op: impl Fn(u64, u64) -> u64) -> Self {
+        let mut lanes = self.lanes;
+        for (a, b) in lanes.iter_mut().zip(rhs.lanes) {
+            *a = op(*a, b);
+        }
+        Self::from_lanes(lanes)
+    }
+
+    const fn from_lanes(lanes: [u64; W]) -> Self {
+        Self {
+            lanes,
+            _params: PhantomData,
+        }
+    }
+}
+
+impl<P: SmallPrimeParams, const W: usize> Add<&PackedSmallField<P, W>> for &PackedSmallField<P, W> {
+    type Output = PackedSmallField<P, W>;
+    fn add(self, rhs: &PackedSmallField<P, W>) -> PackedSmallField<P, W> {
+        self.zip_with(rhs, |a, b| add_mod(a, b, SmallFieldElement::<P>::PRIME))
+    }
+}
+impl<P: SmallPrimeParams, const W: usize> Sub<&PackedSmallField<P, W>> for &PackedSmallField<P, W> {
+    type Output = PackedSmallField<P, W>;
+    fn sub(self, rhs: &PackedSmallField<P, W>) -> PackedSmallField<P, W> {
+        self.zip_with(rhs, |a, b| sub_mod(a, b, SmallFieldElement::<P>::PRIME))
+    }
+}
+impl<P: SmallPrimeParams, const W: usize> Mul<&PackedSmallField<P, W>> for &PackedSmallField<P, W> {
+    type Output = PackedSmallField<P, W>;
+    fn mul(self, rhs: &PackedSmallField<P, W>) -> PackedSmallField<P, W> {
+        self.zip_with(rhs, |a, b| P::reduce(a as u128 * b as u128))
+    }
+}
+impl<P: SmallPrimeParams, const W: usize> Neg for &PackedSmallField<P, W> {
+    type Output = PackedSmallField<P, W>;
+    fn neg(self) -> PackedSmallField<P, W> {
+        PackedSmallField::ZERO - self
+    }
+}
+impl<P: SmallPrimeParams, const W: usize> Neg for PackedSmallField<P, W> {
+    type Output = PackedSmallField<P, W>;
+    fn neg(self) -> PackedSmallField<P, W> {
+        -&self
+    }
+}
+forward_binop!(impl<P: SmallPrimeParams, const W: usize> Add, add for PackedSmallField<P, W>);
+forward_binop!(impl<P: SmallPrimeParams, const W: usize> Sub, sub for PackedSmallField<P, W>);
+forward_binop!(impl<P: SmallPrimeParams, const W: usize> Mul, mul for PackedSmallField<P, W>);
+
+/// Applies `packed` to full `PACK_WIDTH` chunks and `scalar` to the tail.
+/// Parallel chunks are a multiple of `PACK_WIDTH` long, so only the last one
+/// has a tail.
+fn batch_assign<P: SmallPrimeParams>(
+    a: &mut [SmallFieldElement<P>],
+    b: &[SmallFieldElement<P>],
+    packed: impl Fn(
+            PackedSmallField<P, PACK_WIDTH>,
+            PackedSmallField<P, PACK_WIDTH>,
+        ) -> PackedSmallField<P, PACK_WIDTH>
+        + MaybeSync,
+    scalar: impl Fn(SmallFieldElement<P>, SmallFieldElement<P>) -> SmallFieldElement<P> + MaybeSync,
+) {
+    parallel::for_each_chunk_zip_mut(a, b, |a, b| batch_assign_serial(a, b, &packed, &scalar));
+}
+
+fn batch_assign_serial<P: SmallPrimeParams>(
+    a: &mut [SmallFieldElement<P>],
+    b: &[SmallFieldElement<P>],
+    packed: impl Fn(
+        PackedSmallField<P, PACK_WIDTH>,
+        PackedSmallField<P, PACK_WIDTH>,
+    ) -> PackedSmallField<P, PACK_WIDTH>,
+    scalar: impl Fn(SmallFieldElement<P>, SmallFieldElement<P>) -> SmallFieldElement<P>,
+) {
+    let mut a_chunks = a.chunks_exact_mut(PACK_WIDTH);
+    let mut b_chunks = b.chunks_exact(PACK_WIDTH);
+    for (x, y) in (&mut a_chunks).zip(&mut b_chunks) {
+        let res = packed(
+            PackedSmallField::from_slice(x),
+            PackedSmallField::from_slice(y),
+        );
+        res.write_to_slice(x);
+    }
+    for (x, y) in a_chunks
+        .into_remainder()
+        .iter_mut()
+        .zip(b_chunks.remainder())
+    {
+        *x = scalar(*x, *y);
+    }
+}
+
+/////////////////////////////////////////////
+/////////////// Reduction
+/////////////////////////////////////////////
+/// All-ones if `bit` is 1, zero if it is 0
+const fn mask(bit: bool) -> u64 {
+    0u64.wrapping_sub(bit as u64)
+}
+
+/// x - p if x >= p, x otherwise
+const fn sub_if_geq(x: u64, p: u64) -> u64 {
+    let (reduced, borrow) = x.overflowing_sub(p);
+    reduced.wrapping_add(p & mask(borrow))
+}
+
+/// a + b mod p for a, b < p, see `U64FieldElement`'s addition for the
+/// overflow case
+const fn add_mod(a: u64, b: u64, p: u64) -> u64 {
+    let (sum, overflow) = a.overflowing_add(b);
+    let (reduced, borrow) = sum.overflowing_sub(p);
+    let keep = mask(borrow & !overflow);
+    (sum & keep) | (reduced & !keep)
+}
+
+/// a - b mod p for a, b < p
+const fn sub_mod(a: u64, b: u64, p: u64) -> u64 {
+    let (diff, borrow) = a.overflowing_sub(b);
+    diff.wrapping_add(p & mask(borrow))
+}
+
+/// x mod p for x < 2^64 and p < 2^63, given m = floor(2^64 / p)
+///
+/// q = floor(x m / 2^64) is more than x / p - 1, so it undershoots the true
+/// quotient by at most one and x - q p < 2p.
+pub const fn reduce_barrett(x: u64, p: u64, m: u64) -> u64 {
+    let q = ((x as u128 * m as u128) >> 64) as u64;
+    sub_if_geq(x - q * p, p)
+}
+
+/// x mod p for odd p and x < p 2^64, given inv = -p^-1 mod 2^64 and
+/// r2 = 2^128 mod p
+///
+/// The first Montgomery reduction gives x / 2^64, the second multiplies that
+/// by 2^128 and divides by 2^64 again, back to x.
+pub const fn reduce_montgomery(x: u128, p: u64, inv: u64, r2: u64) -> u64 {
+    let t = redc_u64(x, p, inv);
+    redc_u64(t as u128 * r2 as u128, p, inv)
+}
+
+/// x mod p for p = 2^64 - 2^32 + 1 and any x
+///
+/// With x = l + 2^64 h_lo + 2^96 h_hi, where h_lo < 2^32, the identities
+/// 2^64 = 2^32 - 1 and 2^96 = -1 (mod p) give x = l - h_hi + h_lo (2^32 - 1).
+/// A wrap-around in either step is off by 2^64 = 2^32 - 1 and is corrected
+/// by subtracting or adding it back, which cannot wrap again.
+pub const fn reduce_goldilocks(x: u128) -> u64 {
+    const EPSILON: u64 = 0xffff_ffff;
+    const P: u64 = 0xffff_ffff_0000_0001;
+    let (lo, hi) = (x as u64, (x >> 64) as u64);
+    let (hi_hi, hi_lo) = (hi >> 32, hi & EPSILON);
+
+    let (t0, borrow) = lo.overflowing_sub(hi_hi);
+    let t0 = t0.wrapping_sub(EPSILON & mask(borrow));
+    let (t1, carry) = t0.overflowing_add(hi_lo * EPSILON);
+    let t1 = t1.wrapping_add(EPSILON & mask(carry));
+
+    sub_if_geq(t1, P)
+}
+
+/// x mod p for p = 2^31 - 1 and x < 2^64
+///
+/// 2^31 = 1 (mod p), so folding the bits above 31 onto the low ones keeps
+/// the residue. Two folds bring x below 2^31 + 8 < 2p.
+pub const fn reduce_mersenne31(x: u128) -> u64 {
+    const P: u64 = 0x7fff_ffff;
+    let x = x as u64;
+    let x = (x & P) + (x >> 31);
+    let x = (x & P) + (x >> 31);
+    sub_if_geq(x, P)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::factor::prime_factors;
+    use crate::params::PrimeFieldParams;
+    use primitive_types::U256;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    // above 2^32, so the default `reduce` takes the Montgomery path
+    test_prime_field!(P40, U256([1099511627791, 0, 0, 0]));
+    impl SmallPrimeParams for P40 {}
+    test_prime_field!(P64, U256([0xffff_ffff_ffff_ffc5, 0, 0, 0]));
+    impl SmallPrimeParams for P64 {}
+
+    #[test]
+    fn params_are_prime() {
+        P40::check().unwrap();
+        P64::check().unwrap();
+    }
+
+    /// Values around the edges of [0, p^2) plus random ones
+    fn reduce_inputs(p: u64, rng: &mut StdRng) -> Vec<u128> {
+        let p = p as u128;
+        let mut inputs = vec![0, 1, p - 1, p, p + 1, 2 * p, (p - 1) * (p - 1)];
+        inputs.extend((0..1000).map(|_| rng.gen_range(0..p * p)));
+        inputs
+    }
+
+    #[test]
+    fn reductions() {
+        let mut rng = StdRng::seed_from_u64(15);
+        for (p, reduce) in [
+            (
+                Goldilocks::MODULUS.0[0],
+                Goldilocks::reduce as fn(u128) -> u64,
+            ),
+            (BabyBear::MODULUS.0[0], BabyBear::reduce),
+            (Mersenne31::MODULUS.0[0], Mersenne31::reduce),
+            (P40::MODULUS.0[0], P40::reduce),
+            (P64::MODULUS.0[0], P64::reduce),
+        ] {
+            for x in reduce_inputs(p, &mut rng) {
+                assert_eq!(reduce(x) as u128, x % p as u128, "{} mod {}", x, p);
+            }
+        }
+
+        // Goldilocks takes any u128
+        for x in [u128::MAX, u128::MAX - 1, 1 << 96, (1 << 96) - 1, 1 << 64] {
+            assert_eq!(reduce_goldilocks(x) as u128, x % 0xffff_ffff_0000_0001);
+        }
+        // Mersenne-31 any u64
+        assert_eq!(reduce_mersenne31(u64::MAX as u128), u64::MAX % 0x7fff_ffff);
+    }
+
+    /// Every operation agrees with `U64FieldElement`
+    fn check_matches_u64<P: SmallPrimeParams>() {
+        let mut rng = StdRng::seed_from_u64(16);
+        let mut samples: Vec<U64FieldElement<P>> =
+            (0..30).map(|_| U64FieldElement::random(&mut rng)).collect();
+        samples.extend([
+            U64FieldElement::ZERO,
+            U64FieldElement::ONE,
+            -U64FieldElement::ONE,
+        ]);
+
+        for a in &samples {
+            for b in &samples {
+                let (x, y) = (SmallFieldElement::from(*a), SmallFieldElement::from(*b));
+                assert_eq!(U64FieldElement::from(x + y), a + b);
+                assert_eq!(U64FieldElement::from(x - y), a - b);
+                assert_eq!(U64FieldElement::from(x * y), a * b);
+            }
+            let x = SmallFieldElement::from(*a);
+            assert_eq!(U64FieldElement::from(-x), -a);
+            assert_eq!(x.inv().map(U64FieldElement::from), a.inv());
+            assert_eq!(x.exp(&0), SmallFieldElement::ONE);
+            assert_eq!(x.pow_ct(&[12345]), x.exp(&12345));
+            if let Some(root) = x.sqrt() {
+                assert_eq!(root.square(), x);
+            }
+        }
+    }
+
+    #[test]
+    fn matches_u64() {
+        check_matches_u64::<Goldilocks>();
+        check_matches_u64::<BabyBear>();
+        check_matches_u64::<Mersenne31>();
+        check_matches_u64::<P40>();
+        check_matches_u64::<P64>();
+
+        let minus_one = -SmallFieldElement::<P40>::ONE;
+        assert_eq!(minus_one * minus_one, SmallFieldElement::ONE);
+    }
+
+    #[test]
+    fn generators_and_roots() {
+        fn check<P: SmallPrimeParams>(s: u32) {
+            type F<P> = SmallFieldElement<P>;
+            let p_minus_1 = F::<P>::PRIME - 1;
+            let g = F::<P>::multiplicative_generator().unwrap();
+            for q in prime_factors(p_minus_1) {
+                assert_ne!(g.exp(&(p_minus_1 / q)), F::ONE);
+            }
+            assert_eq!(F::<P>::two_adicity(), s);
+            let root = F::<P>::two_adic_root_of_unity();
+            assert_eq!(root.exp(&(1 << (s - 1))), -F::ONE);
+        }
+        check::<Goldilocks>(32);
+        check::<BabyBear>(27);
+        check::<Mersenne31>(1);
+
+        assert_eq!(
+            U64FieldElement::from(GoldilocksField::root_of_unity(3 << 10).unwrap()),
+            U64FieldElement::root_of_unity(3 << 10).unwrap()
+        );
+    }
+
+    fn check_packed<P: SmallPrimeParams>() {
+        let mut rng = StdRng::seed_from_u64(17);
+        let a: [SmallFieldElement<P>; 8] = core::array::from_fn(|_| Field::random(&mut rng));
+        let mut b: [SmallFieldElement<P>; 8] = core::array::from_fn(|_| Field::random(&mut rng));
+        b[0] = SmallFieldElement::ZERO;
+        b[1] = -SmallFieldElement::ONE;
+        let (x, y) = (
+            PackedSmallField::from_array(a),
+            PackedSmallField::from_slice(&b),
+        );
+
+        let (sum, diff, prod, neg) = (x + y, x - y, x * y, -x);
+        for i in 0..8 {
+            assert_eq!(sum.lane(i), a[i] + b[i]);
+            assert_eq!(diff.lane(i), a[i] - b[i]);
+            assert_eq!(prod.lane(i), a[i] * b[i]);
+            assert_eq!(neg.lane(i), -a[i]);
+            assert_eq!(x.square().lane(i), a[i].square());
+        }
+        assert_eq!(PackedSmallField::broadcast(a[3]).to_array(), [a[3]; 8]);
+        assert_eq!(x + neg, PackedSmallField::ZERO);
+        assert_eq!(x * PackedSmallField::ONE, x);
+    }
+
+    #[test]
+    fn packed_matches_scalar() {
+        check_packed::<Goldilocks>();
+        check_packed::<BabyBear>();
+        check_packed::<Mersenne31>();
+    }
+
+    #[test]
+    fn batch_ops() {
+        let mut rng = StdRng::seed_from_u64(18);
+        // two full chunks and a tail
+        let a: Vec<BabyBearField> = (0..19).map(|_| Field::random(&mut rng)).collect();
+        let b: Vec<BabyBearField> = (0..19).map(|_| Field::random(&mut rng)).collect();
+
+        let mut sum = a.clone();
+        BabyBearField::batch_add_assign(&mut sum, &b);
+        let mut diff = a.clone();
+        BabyBearField::batch_sub_assign(&mut diff, &b);
+        let mut prod = a.clone();
+        BabyBearField::batch_mul_assign(&mut prod, &b);
+        for i in 0..19 {
+            assert_eq!(sum[i], a[i] + b[i]);
+            assert_eq!(diff[i], a[i] - b[i]);
+            assert_eq!(prod[i], a[i] * b[i]);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "different lengths")]
+    fn batch_length_mismatch() {
+        let mut a = vec![Mersenne31Field::ONE; 3];
+        Mersenne31Field::batch_add_assign(&mut a, &[Mersenne31Field::ONE; 2]);
+    }
+
+    #[test]
+    fn bytes_and_strings() {
+        let a = GoldilocksField::from_hex_str("0xdeadbeef").unwrap();
+        assert_eq!(a, GoldilocksField::from_dec_str("3735928559").unwrap());
+        assert_eq!(GoldilocksField::from_bytes_be(&a.to_bytes_be()), Ok(a));
+        assert_eq!(GoldilocksField::from_bytes_le(&a.to_bytes_le()), Ok(a));
+        assert_eq!(GoldilocksField::from_bytes(&a.to_bytes()), Some(a));
+        assert_eq!(
+            Mersenne31Field::from_bytes_be(&0x7fff_ffffu64.to_be_bytes()),
+            Err(Error::NonCanonical)
+        );
+        assert_eq!(
+            "80000000".parse::<Mersenne31Field>(),
+            Ok(Mersenne31Field::ONE)
+        );
+        assert_eq!(Mersenne31Field::new(u64::MAX).num(), u64::MAX % 0x7fff_ffff);
+    }
+}
@@ -1,362 +1,716 @@
-use std::str::FromStr;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
 use core::ops::{Add, Div, Mul, Neg, Sub};
-use num_bigint::BigUint;
+use core::str::FromStr;
 use primitive_types::U256;
+use rand_core::RngCore;
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
+use zeroize::Zeroize;
 
 use crate::errors::{Error, Result};
-use utils::primality_test;
+use crate::factor::factor_p_minus_1;
+use crate::field::{Field, PrimeField};
+use crate::limbs::{
+    adc, add_mod, ct_select, ct_swap, is_probable_prime, mont_inv, mont_mul, num_bits, pow2_mod,
+    sbb, sub_if_geq, sub_mod,
+};
+use crate::params::PrimeFieldParams;
+
+/// Element of the prime field described by `P`.
+///
+/// The value is kept in Montgomery form, `num * R mod p` with `R = 2^256`,
+/// so that multiplication can be done with a CIOS reduction instead of a
+/// generic `%`. Conversions only happen in `new` and `num`.
+///
+/// The arithmetic operators, comparisons, `pow_ct` and `inv_ct` run in
+/// constant time. `exp`, `pow` and `inv` are faster but their running time
+/// depends on the exponent or the value, so they are for public data only.
+pub struct U256FieldElement<P: PrimeFieldParams> {
+    mont: U256,
+    _params: PhantomData<P>,
+}
+
+/// `Fp<Secp256k1Base>` reads better at call sites that only care about the field
+pub type Fp<P> = U256FieldElement<P>;
+
+impl<P: PrimeFieldParams> Clone for U256FieldElement<P> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<P: PrimeFieldParams> Copy for U256FieldElement<P> {}
+
+impl<P: PrimeFieldParams> core::fmt::Debug for U256FieldElement<P> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("U256FieldElement")
+            .field("num", &self.num())
+            .field("prime", &P::MODULUS)
+            .finish()
+    }
+}
 
-#[derive(Debug, Clone)]
-pub struct U256FieldElement {
-    pub num: U256,
-    pub prime: U256,
+impl<P: PrimeFieldParams> FromStr for U256FieldElement<P> {
+    type Err = Error;
+    /// Same as `from_hex_str`
+    fn from_str(num: &str) -> Result<Self> {
+        Self::from_hex_str(num)
+    }
 }
 
 /////////////////////////////////////////////
 /////////////// Operator Overloads
 /////////////////////////////////////////////
 ///// Equality
-impl Eq for U256FieldElement {}
-impl PartialEq for U256FieldElement {
+impl<P: PrimeFieldParams> Eq for U256FieldElement<P> {}
+impl<P: PrimeFieldParams> PartialEq for U256FieldElement<P> {
     fn eq(&self, other: &Self) -> bool {
-        self.num == other.num && self.prime == other.prime
+        self.ct_eq(other).into()
     }
 }
 ///// Addition
-impl Add<&U256FieldElement> for &U256FieldElement {
-    type Output = U256FieldElement;
+impl<P: PrimeFieldParams> Add<&U256FieldElement<P>> for &U256FieldElement<P> {
+    type Output = U256FieldElement<P>;
 
     ///    [a]. a(modp) + b(modp)
     ///    [b]. ( a(modp) + b(modp) )(modp)
     ///
-    /// Result of [a]. can at most be 2p-2. If we pick a p value close to 2^64, this
-    /// will clearly cause an overflow, given that we're operating in U256.
+    /// Result of [a]. can at most be 2p-2. If p is close to 2^256, this
+    /// overflows the 256 bits of a U256.
     ///
     /// |______________|===|*********|________|
-    /// 0              p  U256       2p-2     U256
-    ///  
-    /// In that case, the result will be ******, and we'll need to add === to the
-    /// result to make up for the overflow.
+    /// 0              p  2^256      2p-2     2^256
+    ///
+    /// In that case the wrapped result is ******, and the carry out of the
+    /// addition stands for the missing ===.
+    ///
+    /// Both operands are already reduced, so a single conditional subtraction
+    /// is enough and no `%` is needed. Montgomery form is preserved by addition.
     fn add(self, rhs: &Self::Output) -> Self::Output {
-        Self::Output::is_same_field(&self.prime, &rhs.prime).unwrap();
-
-        let (mut res, overflow) = self.num.overflowing_add(rhs.num);
-
-        if overflow {
-            res += (U256::MAX - self.prime) + 1;
-        }
-
-        res %= self.prime;
-
-        Self::Output {
-            num: res,
-            prime: self.prime,
-        }
+        U256FieldElement::from_mont(U256(add_mod(&self.mont.0, &rhs.mont.0, &P::MODULUS.0)))
     }
 }
-impl Add<U256FieldElement> for U256FieldElement {
-    type Output = U256FieldElement;
+impl<P: PrimeFieldParams> Add<U256FieldElement<P>> for U256FieldElement<P> {
+    type Output = U256FieldElement<P>;
     fn add(self, rhs: Self::Output) -> Self::Output {
         &self + &rhs
     }
 }
-impl Add<U256FieldElement> for &U256FieldElement {
-    type Output = U256FieldElement;
+impl<P: PrimeFieldParams> Add<U256FieldElement<P>> for &U256FieldElement<P> {
+    type Output = U256FieldElement<P>;
     fn add(self, rhs: Self::Output) -> Self::Output {
-        self + &rhs
+        *self + rhs
     }
 }
-impl Add<&U256FieldElement> for U256FieldElement {
-    type Output = U256FieldElement;
+impl<P: PrimeFieldParams> Add<&U256FieldElement<P>> for U256FieldElement<P> {
+    type Output = U256FieldElement<P>;
     fn add(self, rhs: &Self::Output) -> Self::Output {
-        &self + rhs
+        self + *rhs
     }
 }
 ///// Subtraction
-impl Sub<&U256FieldElement> for &U256FieldElement {
-    type Output = U256FieldElement;
+impl<P: PrimeFieldParams> Sub<&U256FieldElement<P>> for &U256FieldElement<P> {
+    type Output = U256FieldElement<P>;
 
     /// (a(modp))-(b(modp)) (modp)  ==>  amodp + (-b)modp = amodp + (p-b)modp
     ///
     /// (a + p - b) is subject to overflows, but our addition function is already
     /// precautious against such situations
     fn sub(self, rhs: &Self::Output) -> Self::Output {
-        Self::Output::is_same_field(&self.prime, &rhs.prime).unwrap();
         self + (-rhs)
     }
 }
-impl Sub<U256FieldElement> for U256FieldElement {
-    type Output = U256FieldElement;
+impl<P: PrimeFieldParams> Sub<U256FieldElement<P>> for U256FieldElement<P> {
+    type Output = U256FieldElement<P>;
     fn sub(self, rhs: Self::Output) -> Self::Output {
         &self - &rhs
     }
 }
-impl Sub<U256FieldElement> for &U256FieldElement {
-    type Output = U256FieldElement;
+impl<P: PrimeFieldParams> Sub<U256FieldElement<P>> for &U256FieldElement<P> {
+    type Output = U256FieldElement<P>;
     fn sub(self, rhs: Self::Output) -> Self::Output {
-        self - &rhs
+        *self - rhs
     }
 }
-impl Sub<&U256FieldElement> for U256FieldElement {
-    type Output = U256FieldElement;
+impl<P: PrimeFieldParams> Sub<&U256FieldElement<P>> for U256FieldElement<P> {
+    type Output = U256FieldElement<P>;
     fn sub(self, rhs: &Self::Output) -> Self::Output {
-        &self - rhs
+        self - *rhs
     }
 }
 ///// Multiplication
-impl Mul<&U256FieldElement> for &U256FieldElement {
-    type Output = U256FieldElement;
+impl<P: PrimeFieldParams> Mul<&U256FieldElement<P>> for &U256FieldElement<P> {
+    type Output = U256FieldElement<P>;
 
-    /// Double & add algorithm
-    ///
-    /// Example: 5 * 45 = 5 * (101101)_2
-    /// Iterate all bits of 45 starting from the LSB, and hold 2 aggregators:
-    ///     base: this ticker will get doubled at each bit, unconditionally.
-    ///             `base` will start from "5"
-    ///     res:  we'll add `base` to this variable whenever the current bit is 1
-    ///             `res` will start from "0"
+    /// Montgomery multiplication
     ///
-    /// res = 5 + 20 + 40 + 160 = 225
+    /// Operands are aR and bR (mod p). CIOS interleaves the schoolbook product
+    /// of the 4x64-bit limbs with the reduction, so what comes out is
+    /// aR * bR * R^-1 = abR (mod p), again in Montgomery form.
     fn mul(self, rhs: &Self::Output) -> Self::Output {
-        Self::Output::is_same_field(&self.prime, &rhs.prime).unwrap();
-
-        let mut rhs = rhs.num;
-        if rhs == U256::zero() {
-            return Self::Output {
-                num: U256::zero(),
-                prime: self.prime,
-            };
-        }
-
-        let mut base = self.clone();
-        let mut res = Self::Output {
-            num: U256::zero(),
-            prime: self.prime,
-        };
-
-        while rhs != U256::zero() {
-            if rhs & U256::one() == U256::one() {
-                res = res + &base;
-            }
-            base = (&base).double();
-            rhs >>= 1;
-        }
-
-        res
+        U256FieldElement::from_mont(U256(mont_mul(
+            &self.mont.0,
+            &rhs.mont.0,
+            &P::MODULUS.0,
+            U256FieldElement::<P>::INV,
+        )))
     }
 }
-impl Mul<U256FieldElement> for U256FieldElement {
-    type Output = U256FieldElement;
+impl<P: PrimeFieldParams> Mul<U256FieldElement<P>> for U256FieldElement<P> {
+    type Output = U256FieldElement<P>;
     fn mul(self, rhs: Self::Output) -> Self::Output {
         &self * &rhs
     }
 }
-impl Mul<U256FieldElement> for &U256FieldElement {
-    type Output = U256FieldElement;
+impl<P: PrimeFieldParams> Mul<U256FieldElement<P>> for &U256FieldElement<P> {
+    type Output = U256FieldElement<P>;
     fn mul(self, rhs: Self::Output) -> Self::Output {
-        self * &rhs
+        *self * rhs
     }
 }
-impl Mul<&U256FieldElement> for U256FieldElement {
-    type Output = U256FieldElement;
+impl<P: PrimeFieldParams> Mul<&U256FieldElement<P>> for U256FieldElement<P> {
+    type Output = U256FieldElement<P>;
     fn mul(self, rhs: &Self::Output) -> Self::Output {
-        &self * rhs
+        self * *rhs
     }
 }
 ///// Division
-impl Div<&U256FieldElement> for &U256FieldElement {
-    type Output = U256FieldElement;
+impl<P: PrimeFieldParams> Div<&U256FieldElement<P>> for &U256FieldElement<P> {
+    type Output = U256FieldElement<P>;
+    #[allow(clippy::suspicious_arithmetic_impl)]
     fn div(self, rhs: &Self::Output) -> Self::Output {
-        self * rhs.inv()
+        self * rhs.inv().expect("division by zero")
     }
 }
-impl Div<U256FieldElement> for U256FieldElement {
-    type Output = U256FieldElement;
+impl<P: PrimeFieldParams> Div<U256FieldElement<P>> for U256FieldElement<P> {
+    type Output = U256FieldElement<P>;
     fn div(self, rhs: Self::Output) -> Self::Output {
         &self / &rhs
     }
 }
-impl Div<&U256FieldElement> for U256FieldElement {
-    type Output = U256FieldElement;
+impl<P: PrimeFieldParams> Div<&U256FieldElement<P>> for U256FieldElement<P> {
+    type Output = U256FieldElement<P>;
     fn div(self, rhs: &Self::Output) -> Self::Output {
-        &self / rhs
+        self / *rhs
     }
 }
-impl Div<U256FieldElement> for &U256FieldElement {
-    type Output = U256FieldElement;
+impl<P: PrimeFieldParams> Div<U256FieldElement<P>> for &U256FieldElement<P> {
+    type Output = U256FieldElement<P>;
     fn div(self, rhs: Self::Output) -> Self::Output {
-        self / &rhs
+        *self / rhs
     }
 }
 ///// Neg
-impl Neg for &U256FieldElement {
-    type Output = U256FieldElement;
+impl<P: PrimeFieldParams> Neg for &U256FieldElement<P> {
+    type Output = U256FieldElement<P>;
     fn neg(self) -> Self::Output {
-        Self::Output {
-            num: self.prime - self.num,
-            prime: self.prime,
-        }
+        // p - 0 is not reduced, so zero is selected back in
+        let neg = U256FieldElement::from_mont(U256(sbb(&P::MODULUS.0, &self.mont.0).0));
+        U256FieldElement::conditional_select(&neg, self, self.ct_eq(&U256FieldElement::ZERO))
     }
 }
-impl Neg for U256FieldElement {
-    type Output = U256FieldElement;
+impl<P: PrimeFieldParams> Neg for U256FieldElement<P> {
+    type Output = U256FieldElement<P>;
     fn neg(self) -> Self::Output {
-        Self::Output {
-            num: self.prime - self.num,
-            prime: self.prime,
-        }
+        -&self
     }
 }
 
+forward_assign_ops!(impl<P: PrimeFieldParams> for U256FieldElement<P>);
 
 /////////////////////////////////////////////
 /////////////// Field Requirements
 /////////////////////////////////////////////
-impl U256FieldElement {
-    pub fn from_u64(num: u64, prime: u64) -> Result<Self> {
-        Self::new(U256::from(num), U256::from(prime))
+impl<P: PrimeFieldParams> U256FieldElement<P> {
+    /// -p^{-1} mod 2^64, the Montgomery reduction constant
+    const INV: u64 = {
+        assert!(
+            P::MODULUS.0[0] & 1 == 1,
+            "Montgomery form needs an odd prime"
+        );
+        assert!(is_probable_prime(&P::MODULUS.0), "modulus is not prime");
+        mont_inv(P::MODULUS.0[0])
+    };
+    /// R mod p, which is also 1 in Montgomery form
+    const R: U256 = U256(pow2_mod(&[1, 0, 0, 0], 256, &P::MODULUS.0));
+    /// R^2 mod p, used to move values into Montgomery form
+    const R2: U256 = U256(pow2_mod(&Self::R.0, 256, &P::MODULUS.0));
+
+    pub const ZERO: Self = Self::from_mont(U256([0, 0, 0, 0]));
+    pub const ONE: Self = Self::from_mont(Self::R);
+
+    /// Any `num` is accepted: multiplying by R^2 also reduces it mod p
+    pub const fn new(num: U256) -> Self {
+        Self::from_mont(U256(mont_mul(
+            &num.0,
+            &Self::R2.0,
+            &P::MODULUS.0,
+            Self::INV,
+        )))
     }
 
-    pub fn from_str(num: &str, prime: &str) -> Result<Self> {
-        Self::new(U256::from_str(num).unwrap(), U256::from_str(prime).unwrap())
+    pub const fn from_u64(num: u64) -> Self {
+        Self::new(U256([num, 0, 0, 0]))
     }
 
-    pub fn from_u64_and_u256_prime(num: u64, prime: U256) -> Result<Self> {
-        Self::new(U256::from(num), prime)
+    pub const fn prime() -> U256 {
+        P::MODULUS
     }
 
-    pub fn new(num: U256, prime: U256) -> Result<Self> {
-        match primality_test::miller_rabin(BigUint::from_str(&prime.to_string()).unwrap()) {
-            false => Err(Error::NotPrime),
-            true => Ok(Self {
-                num: num % prime,
-                prime,
-            }),
-        }
+    /// Canonical representative in [0, prime)
+    pub fn num(&self) -> U256 {
+        U256(mont_mul(
+            &self.mont.0,
+            &[1, 0, 0, 0],
+            &P::MODULUS.0,
+            Self::INV,
+        ))
     }
 
-    pub fn zero(prime: U256) -> Self {
-        Self { num: U256::zero(), prime }
+    pub fn to_bytes_be(&self) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        self.num().to_big_endian(&mut bytes);
+        bytes
     }
 
-    pub fn one(prime: U256) -> Self {
-        Self { num: U256::one(), prime }
+    pub fn to_bytes_le(&self) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        self.num().to_little_endian(&mut bytes);
+        bytes
     }
 
-    pub fn double(&self) -> Self {
-        self + self
+    /// Inverse of `to_bytes_be`, rejecting anything but exactly 32 bytes
+    /// holding a value below p
+    pub fn from_bytes_be(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != 32 {
+            return Err(Error::InvalidLength);
+        }
+        Self::from_canonical(U256::from_big_endian(bytes))
     }
 
-    pub fn sq(&self) -> Self {
-        self * self
+    /// Inverse of `to_bytes_le`, with the same checks as `from_bytes_be`
+    pub fn from_bytes_le(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != 32 {
+            return Err(Error::InvalidLength);
+        }
+        Self::from_canonical(U256::from_little_endian(bytes))
+    }
+
+    /// Parses up to 64 hex digits, with or without `0x`. Like `new`, the
+    /// value is reduced mod p.
+    pub fn from_hex_str(num: &str) -> Result<Self> {
+        let digits = num.strip_prefix("0x").unwrap_or(num);
+        if digits.is_empty() {
+            return Err(Error::InvalidString);
+        }
+        let num = U256::from_str(digits).map_err(|_| Error::InvalidString)?;
+        Ok(Self::new(num))
+    }
+
+    /// Parses a decimal number below 2^256. Like `new`, the value is
+    /// reduced mod p.
+    pub fn from_dec_str(num: &str) -> Result<Self> {
+        if num.is_empty() {
+            return Err(Error::InvalidString);
+        }
+        let num = U256::from_dec_str(num).map_err(|_| Error::InvalidString)?;
+        Ok(Self::new(num))
     }
 
-    // Uses Fermat's little
-    pub fn inv(&self) -> Self {
-        self.exp(&(&self.prime - 2))
+    /// self^exp in constant time for a fixed `exp.len()`, exponent as
+    /// little-endian u64 limbs
+    pub fn pow_ct(&self, exp: &[u64]) -> Self {
+        crate::field::pow_ct(self, exp)
     }
 
     pub fn exp_by_u64(&self, exp: u64) -> Self {
         self.exp(&U256::from(exp))
     }
 
-    // Square & add algorithm
+    /// self^exp by a sliding window, variable time. exp(0) is one, also
+    /// for zero.
     pub fn exp(&self, exp: &U256) -> Self {
-        // Use fermat's little theorem
-        let mut exp = *exp % (self.prime - 1);
-
-        if exp == U256::zero() {
-            return Self {
-                num: U256::zero(),
-                prime: self.prime,
-            };
+        self.pow(&exp.0)
+    }
+
+    /// Inverse by Fermat's little theorem, a^(p-2). Slow and branches on the
+    /// bits of p only, kept as a reference for the other inversions.
+    pub fn inv_fermat(&self) -> Option<Self> {
+        if self.is_zero() {
+            return None;
+        }
+        Some(self.exp(&(P::MODULUS - 2)))
+    }
+
+    /// Constant-time inverse, for secret values. Runs a fixed number of
+    /// iterations and has no secret-dependent branches or memory accesses;
+    /// only whether `self` is zero shows in the result.
+    pub fn inv_ct(&self) -> Option<Self> {
+        let inv = ct_inv(&self.num(), &P::MODULUS, 2 * Self::NUM_BITS);
+        match self.is_zero() {
+            true => None,
+            false => Some(Self::new(inv)),
+        }
+    }
+
+    fn from_canonical(num: U256) -> Result<Self> {
+        match num < P::MODULUS {
+            true => Ok(Self::new(num)),
+            false => Err(Error::NonCanonical),
         }
+    }
+
+    const fn from_mont(mont: U256) -> Self {
+        Self {
+            mont,
+            _params: PhantomData,
+        }
+    }
+}
 
-        let mut base = self.clone();
-        let mut res = Self {
-            num: U256::one(),
-            prime: self.prime,
-        };
+impl<P: PrimeFieldParams> Field for U256FieldElement<P> {
+    fn zero() -> Self {
+        Self::ZERO
+    }
+
+    fn one() -> Self {
+        Self::ONE
+    }
 
-        while exp != U256::zero() {
-            if exp & U256::one() == U256::one() {
-                res = res * &base;
+    fn is_zero(&self) -> bool {
+        self.mont.is_zero()
+    }
+
+    fn double(&self) -> Self {
+        self + self
+    }
+
+    fn square(&self) -> Self {
+        self * self
+    }
+
+    /// Binary extended Euclid, variable-time. Use `inv_ct` for secrets.
+    fn inv(&self) -> Option<Self> {
+        if self.is_zero() {
+            return None;
+        }
+        Some(Self::new(binary_inv(&self.num(), &P::MODULUS)))
+    }
+
+    /// The 512-bit products of the Montgomery forms are added up in 576
+    /// bits: sum (a_i R)(b_i R) = (sum a_i b_i) R^2. Two Montgomery
+    /// reductions divide that by R^2, and a Montgomery multiplication by R^2
+    /// brings the sum back to Montgomery form, all in constant time.
+    fn sum_of_products(a: &[Self], b: &[Self]) -> Self {
+        assert_eq!(a.len(), b.len(), "slices of different lengths");
+        let mut acc = [0u64; 9];
+        for (x, y) in a.iter().zip(b) {
+            let mut carry = 0u128;
+            for (limb, prod) in acc.iter_mut().zip(x.mont.full_mul(y.mont).0) {
+                let sum = *limb as u128 + prod as u128 + carry;
+                *limb = sum as u64;
+                carry = sum >> 64;
+            }
+            acc[8] += carry as u64;
+        }
+
+        let t = mont_reduce(&acc, &P::MODULUS, Self::INV);
+        let t = mont_reduce(
+            &[t[0], t[1], t[2], t[3], t[4], t[5], 0, 0, 0],
+            &P::MODULUS,
+            Self::INV,
+        );
+        // t < 2^65 + p, so one subtraction brings it below 2^256
+        let t = sub_if_geq(&[t[0], t[1], t[2], t[3]], t[4], &P::MODULUS.0);
+        Self::from_mont(U256(mont_mul(&t, &Self::R2.0, &P::MODULUS.0, Self::INV)))
+    }
+
+    fn characteristic() -> Vec<u64> {
+        P::MODULUS.0.to_vec()
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes_be().to_vec()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        Self::from_bytes_be(bytes).ok()
+    }
+
+    /// Rejection sampling on NUM_BITS random bits, so fewer than two draws
+    /// are needed on average. Only the rejected draws affect the running time.
+    fn random<R: RngCore + ?Sized>(rng: &mut R) -> Self {
+        let mut bytes = [0u8; 32];
+        loop {
+            rng.fill_bytes(&mut bytes);
+            let num = U256::from_little_endian(&bytes) >> (256 - Self::NUM_BITS);
+            if num < P::MODULUS {
+                return Self::new(num);
             }
-            base = base.sq();
-            exp >>= 1;
         }
+    }
+}
+
+impl<P: PrimeFieldParams> PrimeField for U256FieldElement<P> {
+    const NUM_BITS: u32 = num_bits(&P::MODULUS.0);
+
+    fn from_u64(num: u64) -> Self {
+        Self::from_u64(num)
+    }
+
+    fn multiplicative_generator() -> Option<Self> {
+        P::MULTIPLICATIVE_GENERATOR.map(Self::new)
+    }
+}
+
+/////////////////////////////////////////////
+/////////////// Constant Time
+/////////////////////////////////////////////
+impl<P: PrimeFieldParams> ConstantTimeEq for U256FieldElement<P> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.mont.0.ct_eq(&other.mont.0)
+    }
+}
+
+impl<P: PrimeFieldParams> ConditionallySelectable for U256FieldElement<P> {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        let mut mont = [0u64; 4];
+        for (i, limb) in mont.iter_mut().enumerate() {
+            *limb = u64::conditional_select(&a.mont.0[i], &b.mont.0[i], choice);
+        }
+        Self::from_mont(U256(mont))
+    }
+}
 
-        return res;
+impl<P: PrimeFieldParams> Zeroize for U256FieldElement<P> {
+    fn zeroize(&mut self) {
+        self.mont.0.zeroize();
     }
+}
 
-    fn is_same_field(p_1: &U256, p_2: &U256) -> Result<()> {
-        if *p_1 != *p_2 {
-            return Err(Error::DifferentFields);
+/////////////////////////////////////////////
+/////////////// Multiplicative Group
+/////////////////////////////////////////////
+/// Structure of GF(p)*, from a factorization of p - 1. All of these fail
+/// with `Error::UnknownFactorization` when p - 1 cannot be factored, see
+/// `factor::factor_p_minus_1`. Variable time.
+impl<P: PrimeFieldParams> U256FieldElement<P> {
+    /// The least k > 0 with self^k = 1, `None` for zero
+    pub fn order(&self) -> Result<Option<U256>> {
+        let factors = factor_p_minus_1(P::MODULUS)?;
+        if self.is_zero() {
+            return Ok(None);
+        }
+
+        // strip each prime from p - 1 for as long as the power stays 1
+        let mut order = P::MODULUS - 1;
+        for (q, e) in factors {
+            for _ in 0..e {
+                if self.pow(&(order / q).0) != Self::ONE {
+                    break;
+                }
+                order /= q;
+            }
         }
-        Ok(())
+        Ok(Some(order))
+    }
+
+    /// Whether self generates GF(p)*, i.e. has order p - 1
+    pub fn is_primitive_root(&self) -> Result<bool> {
+        Ok(self.generates(&factor_p_minus_1(P::MODULUS)?))
+    }
+
+    /// The smallest primitive root mod p
+    pub fn find_generator() -> Result<Self> {
+        let factors = factor_p_minus_1(P::MODULUS)?;
+        Ok((1..)
+            .map(Self::from_u64)
+            .find(|x| x.generates(&factors))
+            .unwrap())
+    }
+
+    /// x generates GF(p)* iff x^((p-1)/q) != 1 for every prime q | p - 1
+    fn generates(&self, factors: &[(U256, u32)]) -> bool {
+        !self.is_zero()
+            && factors
+                .iter()
+                .all(|(q, _)| self.pow(&((P::MODULUS - 1) / q).0) != Self::ONE)
     }
 }
 
+/////////////////////////////////////////////
+/////////////// Montgomery Arithmetic
+/////////////////////////////////////////////
+// The limb-generic helpers are in `limbs`, called on `U256.0`; only the
+// reduction of the 576-bit `sum_of_products` accumulator is U256 specific.
+
+/// t * R^-1 mod p for a 576-bit t, not fully reduced: four word-by-word
+/// Montgomery steps, each adding the multiple of p that zeroes the lowest
+/// limb. The result is below t / R + p and fits in six limbs.
+const fn mont_reduce(t: &[u64; 9], p: &U256, inv: u64) -> [u64; 6] {
+    let m = &p.0;
+    let mut t = [t[0], t[1], t[2], t[3], t[4], t[5], t[6], t[7], t[8], 0];
+
+    let mut i = 0;
+    while i < 4 {
+        let k = t[i].wrapping_mul(inv);
+        let mut carry = 0u64;
+        let mut j = 0;
+        while j < 4 {
+            let uv = t[i + j] as u128 + k as u128 * m[j] as u128 + carry as u128;
+            t[i + j] = uv as u64;
+            carry = (uv >> 64) as u64;
+            j += 1;
+        }
+        let mut j = i + 4;
+        while j < 10 {
+            let uv = t[j] as u128 + carry as u128;
+            t[j] = uv as u64;
+            carry = (uv >> 64) as u64;
+            j += 1;
+        }
+        i += 1;
+    }
+
+    [t[4], t[5], t[6], t[7], t[8], t[9]]
+}
+
+/////////////////////////////////////////////
+/////////////// Inversion
+/////////////////////////////////////////////
+// Both work on canonical values, not on Montgomery form. They keep the
+// invariants a = u * x and b = v * x (mod p) while driving a and b down to
+// gcd(x, p) = 1 with halvings and subtractions.
+
+/// x^-1 mod p for 0 < x < p, binary extended Euclid
+pub(crate) fn binary_inv(x: &U256, p: &U256) -> U256 {
+    let (mut a, mut b) = (*x, *p);
+    let (mut u, mut v) = (U256::one(), U256::zero());
+
+    while a != U256::one() && b != U256::one() {
+        while a.0[0] & 1 == 0 {
+            a >>= 1;
+            u = halve_mod(&u, p);
+        }
+        while b.0[0] & 1 == 0 {
+            b >>= 1;
+            v = halve_mod(&v, p);
+        }
+        if a >= b {
+            a -= b;
+            u = U256(sub_mod(&u.0, &v.0, &p.0));
+        } else {
+            b -= a;
+            v = U256(sub_mod(&v.0, &u.0, &p.0));
+        }
+    }
+
+    match a == U256::one() {
+        true => u,
+        false => v,
+    }
+}
+
+/// x^-1 mod p for x < p (0 for x = 0), in constant time
+///
+/// Möller's fixed-iteration binary GCD (the one behind GMP's
+/// `mpn_sec_invert`). Every step: if a is odd, make a >= b by swapping, and
+/// subtract b; then halve a, which is now even. b stays odd and
+/// bits(a) + bits(b) drops by at least one per step, so 2 * bits(p) steps
+/// leave a = 0 and b = 1. All conditionals are done with masks.
+pub(crate) fn ct_inv(x: &U256, p: &U256, iterations: u32) -> U256 {
+    let (mut a, mut b) = (*x, *p);
+    let (mut u, mut v) = (U256::one(), U256::zero());
+
+    for _ in 0..iterations {
+        let odd = (a.0[0] & 1).wrapping_neg();
+        let (_, borrow) = sbb(&a.0, &b.0);
+        let swap = odd & borrow.wrapping_neg();
+        ct_swap(swap, &mut a.0, &mut b.0);
+        ct_swap(swap, &mut u.0, &mut v.0);
+
+        a = U256(ct_select(odd, &sbb(&a.0, &b.0).0, &a.0));
+        u = U256(ct_select(odd, &sub_mod(&u.0, &v.0, &p.0), &u.0));
+
+        a >>= 1;
+        u = halve_mod(&u, p);
+    }
+
+    v
+}
+
+/// a / 2 mod p for a < p, constant time: a odd becomes (a + p) / 2, where
+/// the carry of a + p is shifted back in as the top bit
+fn halve_mod(a: &U256, p: &U256) -> U256 {
+    let odd = (a.0[0] & 1).wrapping_neg();
+    let (s, carry) = adc(&a.0, &ct_select(odd, &p.0, &[0; 4]));
+    let mut res = U256(s) >> 1;
+    res.0[3] |= carry << 63;
+    res
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::params::u256_from_hex;
+    use primitive_types::U512;
+    use subtle::ConditionallyNegatable;
+
+    test_prime_field!(P11, U256([11, 0, 0, 0]));
+    test_prime_field!(P19, U256([19, 0, 0, 0]));
+    test_prime_field!(P23, U256([23, 0, 0, 0]));
+    test_prime_field!(P97, U256([97, 0, 0, 0]));
+    test_prime_field!(P859, U256([859, 0, 0, 0]));
+    test_prime_field!(P63949, u256_from_hex("0xf9cd"));
+    test_prime_field!(P64, u256_from_hex("0xFFFFFFFFFFFFFFC5"));
+    test_prime_field!(
+        Secp256k1P,
+        u256_from_hex("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F")
+    );
 
     #[test]
-    fn new_err_not_a_prime() {
-        let err = U256FieldElement::from_u64(17, 21).unwrap_err();
-        assert_eq!(err, Error::NotPrime);
+    fn params_are_prime() {
+        P11::check().unwrap();
+        P19::check().unwrap();
+        P23::check().unwrap();
+        P97::check().unwrap();
+        P859::check().unwrap();
+        P63949::check().unwrap();
+        P64::check().unwrap();
+        Secp256k1P::check().unwrap();
     }
 
     #[test]
     fn new_1() {
-        let prime = U256::from(23);
         let num = U256::from(7871238);
-        let a = U256FieldElement::new(num, prime).unwrap();
-        assert_eq!(
-            a,
-            U256FieldElement {
-                num: U256::from(17),
-                prime
-            }
-        );
+        let a = U256FieldElement::<P23>::new(num);
+        assert_eq!(a.num(), U256::from(17));
     }
 
     #[test]
     fn cmp_neq_1() {
-        let a = U256FieldElement::from_u64(17, 23).unwrap();
-        let b = U256FieldElement::from_u64(16, 23).unwrap();
+        let a = U256FieldElement::<P23>::from_u64(17);
+        let b = U256FieldElement::<P23>::from_u64(16);
         assert_ne!(a, b);
     }
 
-    #[test]
-    fn cmp_neq_2() {
-        let c = U256FieldElement::from_u64(17, 23).unwrap();
-        let d = U256FieldElement::from_u64(17, 29).unwrap();
-        assert_ne!(c, d);
-    }
-
     #[test]
     fn eq_1() {
-        let a = U256FieldElement::from_u64(17, 23).unwrap();
-        let b = U256FieldElement::from_u64(17, 23).unwrap();
+        let a = U256FieldElement::<P23>::from_u64(17);
+        let b = U256FieldElement::<P23>::from_u64(17);
         assert_eq!(a, b);
     }
 
     #[test]
     fn add_0() {
-        let p = "0xB";
-
-        let a = U256FieldElement::from_str("0xBD", &p).unwrap();
-        let b = U256FieldElement::from_str("0x2B", &p).unwrap();
+        let a = U256FieldElement::<P11>::from_str("0xBD").unwrap();
+        let b = U256FieldElement::<P11>::from_str("0x2B").unwrap();
 
         let r = a + b;
 
         assert_eq!(
             r,
             U256FieldElement::from_str(
-                "0000000000000000000000000000000000000000000000000000000000000001",
-                &p
+                "0000000000000000000000000000000000000000000000000000000000000001"
             )
             .unwrap()
         );
@@ -364,18 +718,15 @@ mod tests {
 
     #[test]
     fn add_1() {
-        let p = "0xf9cd";
-
-        let a = U256FieldElement::from_str("0xa167f055ff75c", &p).unwrap();
-        let b = U256FieldElement::from_str("0xacc457752e4ed", &p).unwrap();
+        let a = U256FieldElement::<P63949>::from_str("0xa167f055ff75c").unwrap();
+        let b = U256FieldElement::<P63949>::from_str("0xacc457752e4ed").unwrap();
 
         let r = a + b;
 
         assert_eq!(
             r,
             U256FieldElement::from_str(
-                "0000000000000000000000000000000000000000000000000000000000006bb0",
-                &p
+                "0000000000000000000000000000000000000000000000000000000000006bb0"
             )
             .unwrap()
         );
@@ -383,16 +734,12 @@ mod tests {
 
     #[test]
     fn add_2() {
-        let p = "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F";
-
-        let a = U256FieldElement::from_str(
+        let a = U256FieldElement::<Secp256k1P>::from_str(
             "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2E",
-            &p,
         )
         .unwrap();
-        let b = U256FieldElement::from_str(
+        let b = U256FieldElement::<Secp256k1P>::from_str(
             "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2E",
-            &p,
         )
         .unwrap();
 
@@ -401,149 +748,287 @@ mod tests {
         assert_eq!(
             r,
             U256FieldElement::from_str(
-                "fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2d",
-                &p
+                "fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2d"
             )
             .unwrap()
         );
     }
 
-    // #[test]
-    // fn add_3() {
-    //     let a = U256FieldElement::from_u64(17, 797).unwrap();
-    //     let b = U256FieldElement::from_u64(17, 859).unwrap();
-
-    //     let err = a.add(&b).unwrap_err();
-    //     assert_eq!(err, Error::DifferentFields);
-    // }
-
     #[test]
     fn add_4() {
-        let prime: U256 = U256::from(859);
         let num1: U256 = U256::from(17);
         let num2: U256 = U256::from(2222223); // 849 mod 859
 
-        let a = U256FieldElement::new(num1, prime).unwrap();
-        let b = U256FieldElement::new(num2, prime).unwrap();
+        let a = U256FieldElement::<P859>::new(num1);
+        let b = U256FieldElement::<P859>::new(num2);
 
         let res = a + b;
-        assert_eq!(
-            res,
-            U256FieldElement {
-                prime,
-                num: U256::from(7)
-            }
-        );
+        assert_eq!(res, U256FieldElement::new(U256::from(7)));
     }
 
-    // #[test]
-    // fn sub_err_different_primes() {
-    //     let a = U256FieldElement::from_u64(17, 797).unwrap();
-    //     let b = U256FieldElement::from_u64(17, 859).unwrap();
-
-    //     let err = a.add(&b).unwrap_err();
-    //     assert_eq!(err, Error::DifferentFields);
-    // }
-
     #[test]
     fn sub_1() {
-        let prime: U256 = U256::from(859);
         let num1: U256 = U256::from(17);
         let num2: U256 = U256::from(2222223); // 849 mod 859
 
-        let a = U256FieldElement::new(num1, prime).unwrap();
-        let b = U256FieldElement::new(num2, prime).unwrap();
+        let a = U256FieldElement::<P859>::new(num1);
+        let b = U256FieldElement::<P859>::new(num2);
 
         let res = a - b;
-        assert_eq!(
-            res,
-            U256FieldElement {
-                prime,
-                num: U256::from(27)
-            }
-        );
+        assert_eq!(res, U256FieldElement::new(U256::from(27)));
     }
 
     #[test]
     fn mul_1() {
-        let prime: U256 = U256::from(859);
         let num1: U256 = U256::from(17);
         let num2: U256 = U256::from(2222223); // 849 mod 859
 
         let expected_result: U256 = U256::from(689);
 
-        let a = U256FieldElement::new(num1, prime).unwrap();
-        let b = U256FieldElement::new(num2, prime).unwrap();
+        let a = U256FieldElement::<P859>::new(num1);
+        let b = U256FieldElement::<P859>::new(num2);
 
-        let res = a * &b;
-        assert_eq!(
-            res,
-            U256FieldElement {
-                prime,
-                num: expected_result
-            }
-        );
+        let res = a * b;
+        assert_eq!(res, U256FieldElement::new(expected_result));
     }
 
     #[test]
     fn exp_1() {
-        let prime = U256::from(97);
-        let a = U256FieldElement::new(U256::from(3), prime).unwrap();
+        let a = U256FieldElement::<P97>::new(U256::from(3));
         let res = a.exp(&U256::from(4));
-        assert_eq!(
-            res,
-            U256FieldElement {
-                prime,
-                num: U256::from(81)
-            }
-        );
+        assert_eq!(res, U256FieldElement::new(U256::from(81)));
     }
 
     #[test]
     fn exp_2() {
-        let prime = U256::from(97);
-        let a = U256FieldElement::new(U256::one(), prime).unwrap();
+        let a = U256FieldElement::<P97>::new(U256::one());
         let res = a.exp(&U256::from(326423784));
-        assert_eq!(
-            res,
-            U256FieldElement {
-                prime,
-                num: U256::one()
-            }
-        );
+        assert_eq!(res, U256FieldElement::new(U256::one()));
     }
 
     #[test]
     fn exp_3() {
-        let prime = U256::from_str("0xFFFFFFFFFFFFFFC5").unwrap();
-        let a = U256FieldElement::new(U256::from(2), prime).unwrap();
+        let a = U256FieldElement::<P64>::new(U256::from(2));
         let res = a.exp(&U256::from(35));
         assert_eq!(
             res,
-            U256FieldElement {
-                prime,
-                num: U256::from_str("0x800000000").unwrap()
-            }
+            U256FieldElement::new(U256::from_str("0x800000000").unwrap())
         );
     }
 
+    #[test]
+    fn exp_zero() {
+        type F = U256FieldElement<P97>;
+        assert_eq!(F::from_u64(3).exp(&U256::zero()), F::ONE);
+        assert_eq!(F::ZERO.exp(&U256::zero()), F::ONE);
+        assert_eq!(F::ZERO.exp(&U256::from(96)), F::ZERO);
+        assert_eq!(F::from_u64(3).exp(&U256::from(96)), F::ONE);
+        assert_eq!(F::from_u64(3).exp_by_u64(0), F::ONE);
+    }
+
     #[test]
     fn test_div_ez() {
-        let prime = 19;
-        let a = U256FieldElement::from_u64(2, prime).unwrap();
-        let b = U256FieldElement::from_u64(7, prime).unwrap();
-        let c = U256FieldElement::from_u64(3, prime).unwrap();
+        let a = U256FieldElement::<P19>::from_u64(2);
+        let b = U256FieldElement::<P19>::from_u64(7);
+        let c = U256FieldElement::<P19>::from_u64(3);
 
         assert_eq!(a / b, c);
     }
 
     #[test]
     fn test_div_hard() {
-        let prime = 19;
-        let a = U256FieldElement::from_u64(2, prime).unwrap();
-        let b = U256FieldElement::from_u64(7, prime).unwrap();
-        let c = U256FieldElement::from_u64(3, prime).unwrap();
+        let a = U256FieldElement::<P19>::from_u64(2);
+        let b = U256FieldElement::<P19>::from_u64(7);
+        let c = U256FieldElement::<P19>::from_u64(3);
 
         assert_eq!(a / b, c);
     }
+
+    #[test]
+    fn neg_zero() {
+        let zero = U256FieldElement::<P19>::zero();
+        assert_eq!(-&zero, zero);
+        assert_eq!((-zero).num(), U256::zero());
+    }
+
+    #[test]
+    fn constant_time_ops() {
+        let a = U256FieldElement::<Secp256k1P>::from_u64(3).exp_by_u64(1_000_003);
+        let b = U256FieldElement::<Secp256k1P>::from_u64(42);
+        assert!(bool::from(a.ct_eq(&a)));
+        assert!(!bool::from(a.ct_eq(&b)));
+        assert_eq!(
+            U256FieldElement::conditional_select(&a, &b, Choice::from(0)),
+            a
+        );
+        assert_eq!(
+            U256FieldElement::conditional_select(&a, &b, Choice::from(1)),
+            b
+        );
+
+        let mut c = a;
+        c.conditional_negate(Choice::from(1));
+        assert_eq!(c, -a);
+        c.conditional_negate(Choice::from(0));
+        assert_eq!(c, -a);
+
+        let e = Secp256k1P::MODULUS - 2;
+        assert_eq!(a.pow_ct(&e.0), a.inv().unwrap());
+        assert_eq!(a.pow_ct(&[0, 0]), U256FieldElement::one());
+    }
+
+    #[test]
+    fn const_constructors() {
+        const TWO: U256FieldElement<P19> = U256FieldElement::from_u64(21);
+        assert_eq!(TWO, U256FieldElement::one().double());
+        assert_eq!(U256FieldElement::<P19>::ONE.num(), U256::one());
+    }
+
+    #[test]
+    fn inv_zero() {
+        assert_eq!(U256FieldElement::<P19>::zero().inv(), None);
+        assert_eq!(U256FieldElement::<P19>::zero().inv_ct(), None);
+        assert_eq!(U256FieldElement::<P19>::zero().inv_fermat(), None);
+    }
+
+    fn check_inv<P: PrimeFieldParams>(nums: impl Iterator<Item = U256>) {
+        for num in nums {
+            let a = U256FieldElement::<P>::new(num);
+            if a.is_zero() {
+                continue;
+            }
+            let expected = a.inv_fermat().unwrap();
+            assert_eq!(a.inv().unwrap(), expected, "{:?}", a);
+            assert_eq!(a.inv_ct().unwrap(), expected, "{:?}", a);
+            assert_eq!(a * expected, U256FieldElement::one());
+        }
+    }
+
+    #[test]
+    fn inv_matches_fermat() {
+        check_inv::<P11>((1..11).map(U256::from));
+        check_inv::<P859>((1..859).map(U256::from));
+        check_inv::<P64>((1..200).map(|i| U256::from(u64::MAX - 58 - i)));
+
+        let p = Secp256k1P::MODULUS;
+        let x = U256::from_str("0x1234567890abcdef1234567890abcdef").unwrap();
+        let edge = [U256::one(), U256::from(2), p - 1, p - 2, p >> 1, x, x * x];
+        check_inv::<Secp256k1P>(edge.into_iter());
+        check_inv::<Secp256k1P>((1..100u64).map(|i| (U256::MAX / i) % p));
+    }
+
+    #[test]
+    fn pow_matches_exp() {
+        let a = U256FieldElement::<Secp256k1P>::from_u64(3);
+        let e = U256::from_str("0x1234567890abcdef1234567890abcdef").unwrap();
+        assert_eq!(a.pow(&e.0), a.exp(&e));
+    }
+
+    #[test]
+    fn bytes_roundtrip() {
+        let a = U256FieldElement::<Secp256k1P>::from_u64(0xdeadbeef);
+        let bytes = a.to_bytes();
+        assert_eq!(bytes.len(), 32);
+        assert_eq!(U256FieldElement::from_bytes(&bytes), Some(a));
+
+        let mut p = vec![0u8; 32];
+        Secp256k1P::MODULUS.to_big_endian(&mut p);
+        assert_eq!(U256FieldElement::<Secp256k1P>::from_bytes(&p), None);
+        assert_eq!(U256FieldElement::<Secp256k1P>::from_bytes(&p[1..]), None);
+    }
+
+    #[test]
+    fn num_bits() {
+        assert_eq!(U256FieldElement::<P19>::NUM_BITS, 5);
+        assert_eq!(U256FieldElement::<Secp256k1P>::NUM_BITS, 256);
+    }
+
+    #[test]
+    fn mont_mul_matches_wide_mul() {
+        let x = "79BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798";
+        let y = "483ADA7726A3C4655DA4FBFC0E1108A8FD17B448A68554199C47D08FFB10D4B8";
+
+        let a = U256FieldElement::<Secp256k1P>::from_str(x).unwrap();
+        let b = U256FieldElement::<Secp256k1P>::from_str(y).unwrap();
+        assert_eq!(a.num(), U256::from_str(x).unwrap());
+
+        let expected = a.num().full_mul(b.num()) % U512::from(Secp256k1P::MODULUS);
+        assert_eq!((a * b).num(), U256::try_from(expected).unwrap());
+    }
+
+    #[test]
+    fn bytes_le_be() {
+        let a = U256FieldElement::<Secp256k1P>::from_u64(0x0102);
+        let be = a.to_bytes_be();
+        let le = a.to_bytes_le();
+        assert_eq!(be[30..], [1, 2]);
+        assert_eq!(le[..2], [2, 1]);
+        assert_eq!(U256FieldElement::from_bytes_be(&be), Ok(a));
+        assert_eq!(U256FieldElement::from_bytes_le(&le), Ok(a));
+
+        let mut p = [0u8; 32];
+        Secp256k1P::MODULUS.to_little_endian(&mut p);
+        assert_eq!(
+            U256FieldElement::<Secp256k1P>::from_bytes_le(&p),
+            Err(Error::NonCanonical)
+        );
+        assert_eq!(
+            U256FieldElement::<Secp256k1P>::from_bytes_le(&[0u8; 33]),
+            Err(Error::InvalidLength)
+        );
+    }
+
+    #[test]
+    fn parse_strings() {
+        type F = U256FieldElement<P97>;
+        assert_eq!(F::from_hex_str("0x64"), Ok(F::from_u64(3)));
+        assert_eq!(F::from_hex_str("64"), Ok(F::from_u64(3)));
+        assert_eq!(F::from_dec_str("100"), Ok(F::from_u64(3)));
+        assert_eq!("0x64".parse::<F>(), Ok(F::from_u64(3)));
+
+        for bad in ["", "0x", "0xzz", "1".repeat(65).as_str()] {
+            assert_eq!(F::from_hex_str(bad), Err(Error::InvalidString));
+        }
+        for bad in ["", "-1", "1a", "0x10"] {
+            assert_eq!(F::from_dec_str(bad), Err(Error::InvalidString));
+        }
+    }
+
+    #[test]
+    fn multiplicative_group() {
+        use crate::bn254::Bn254Fr;
+
+        type F = U256FieldElement<P97>;
+        assert_eq!(F::find_generator(), Ok(F::from_u64(5)));
+        assert_eq!(F::from_u64(2).is_primitive_root(), Ok(false));
+        assert_eq!(F::ZERO.order(), Ok(None));
+        assert_eq!(F::from_u64(2).order(), Ok(Some(U256::from(48))));
+
+        // cached factorizations
+        type R = U256FieldElement<Bn254Fr>;
+        let g = R::find_generator().unwrap();
+        assert_eq!(g, R::multiplicative_generator().unwrap());
+        assert_eq!(g.order(), Ok(Some(R::prime() - 1)));
+        assert_eq!(
+            R::two_adic_root_of_unity().order(),
+            Ok(Some(U256::one() << 28))
+        );
+        type S = U256FieldElement<Secp256k1P>;
+        let g = S::find_generator().unwrap();
+        assert_eq!(g.is_primitive_root(), Ok(true));
+        assert_eq!(g.square().order(), Ok(Some((S::prime() - 1) / 2)));
+        assert_eq!((-S::ONE).order(), Ok(Some(U256::from(2))));
+    }
+
+    #[test]
+    fn multiplicative_group_unknown_factorization() {
+        // p - 1 has a 200-bit composite cofactor
+        test_prime_field!(
+            Hard,
+            u256_from_hex("90e32e82394553538cdece75921ebce6139f711060c73494ed192da3c82ad589")
+        );
+        type F = U256FieldElement<Hard>;
+        assert_eq!(F::ONE.order(), Err(Error::UnknownFactorization));
+        assert_eq!(F::find_generator(), Err(Error::UnknownFactorization));
+    }
 }
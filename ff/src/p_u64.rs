@@ -1,30 +1,86 @@
-use num_bigint::BigUint;
+use alloc::{vec, vec::Vec};
+use core::marker::PhantomData;
+use core::ops::{Add, Div, Mul, Neg, Sub};
+use core::str::FromStr;
+use rand_core::RngCore;
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
+use zeroize::Zeroize;
 
 use crate::errors::{Error, Result};
+use crate::exponentiation;
+use crate::factor::factor_u64;
+use crate::field::{Field, PrimeField};
+use crate::limbs::{is_probable_prime, mont_inv};
+use crate::params::PrimeFieldParams;
+
+/// Element of the prime field described by `P`, whose modulus must fit in a u64
+///
+/// The arithmetic operators, comparisons and `pow_ct` run in constant time.
+/// `mul_vartime`, `exp`, `pow` and `inv` are faster but their running time
+/// depends on the operands, so they are for public data only.
+pub struct U64FieldElement<P: PrimeFieldParams> {
+    num: u64,
+    _params: PhantomData<P>,
+}
+
+impl<P: PrimeFieldParams> Clone for U64FieldElement<P> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<P: PrimeFieldParams> Copy for U64FieldElement<P> {}
 
-use utils::primality_test;
+/// Consistent with `==`, for hash tables of public values
+impl<P: PrimeFieldParams> core::hash::Hash for U64FieldElement<P> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.num.hash(state)
+    }
+}
 
-#[derive(Debug, Clone)]
-pub struct U64FieldElement {
-    pub num: u64,
-    pub prime: u64,
+/// The order of the canonical values in [0, p), variable time. It has no
+/// meaning in the field; it is there for sorted tables of public values
+/// such as the baby-step table of `dlog`.
+impl<P: PrimeFieldParams> Ord for U64FieldElement<P> {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.num.cmp(&other.num)
+    }
+}
+impl<P: PrimeFieldParams> PartialOrd for U64FieldElement<P> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
 }
 
-impl U64FieldElement {
-    const ZERO: u64 = 0;
-    const ONE: u64 = 1;
+impl<P: PrimeFieldParams> core::fmt::Debug for U64FieldElement<P> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("U64FieldElement")
+            .field("num", &self.num)
+            .field("prime", &Self::PRIME)
+            .finish()
+    }
+}
 
-    pub fn new(num: u64, prime: u64) -> Result<Self> {
-        let is_prime = primality_test::miller_rabin(BigUint::from(prime));
-        if !is_prime {
-            return Err(Error::NotPrime);
-        }
+impl<P: PrimeFieldParams> FromStr for U64FieldElement<P> {
+    type Err = Error;
+    /// Same as `from_hex_str`
+    fn from_str(num: &str) -> Result<Self> {
+        Self::from_hex_str(num)
+    }
+}
 
-        Ok(Self {
-            num: num % prime,
-            prime,
-        })
+/////////////////////////////////////////////
+/////////////// Operator Overloads
+/////////////////////////////////////////////
+///// Equality
+impl<P: PrimeFieldParams> Eq for U64FieldElement<P> {}
+impl<P: PrimeFieldParams> PartialEq for U64FieldElement<P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
     }
+}
+///// Addition
+impl<P: PrimeFieldParams> Add<&U64FieldElement<P>> for &U64FieldElement<P> {
+    type Output = U64FieldElement<P>;
 
     ///    [a]. a(modp) + b(modp)
     ///    [b]. ( a(modp) + b(modp) )(modp)
@@ -34,297 +90,715 @@ impl U64FieldElement {
     ///
     /// |______________|===|*********|________|
     /// 0              p  u64       2p-2     u64
-    ///  
+    ///
     /// In that case, the result will be ******, and we'll need to add === to the
-    /// result to make up for the overflow.
-    pub fn add(&self, other: &Self) -> Result<Self> {
-        Self::is_same_field(&self.prime, &other.prime)?;
-
-        let (mut res, overflow) = self.num.overflowing_add(other.num);
+    /// result to make up for the overflow. Subtracting p with wrapping does
+    /// exactly that, and is also the reduction needed when there is no
+    /// overflow but the sum is >= p. The two candidates are selected with a
+    /// mask, so the timing does not depend on the operands.
+    fn add(self, rhs: &Self::Output) -> Self::Output {
+        let prime = U64FieldElement::<P>::PRIME;
+        let (sum, overflow) = self.num.overflowing_add(rhs.num);
+        let (reduced, borrow) = sum.overflowing_sub(prime);
+
+        // The sum is kept only if it did not overflow and is below p
+        let keep = Choice::from((borrow & !overflow) as u8);
+        U64FieldElement::from_reduced(u64::conditional_select(&reduced, &sum, keep))
+    }
+}
+impl<P: PrimeFieldParams> Add<U64FieldElement<P>> for U64FieldElement<P> {
+    type Output = U64FieldElement<P>;
+    fn add(self, rhs: Self::Output) -> Self::Output {
+        &self + &rhs
+    }
+}
+impl<P: PrimeFieldParams> Add<U64FieldElement<P>> for &U64FieldElement<P> {
+    type Output = U64FieldElement<P>;
+    fn add(self, rhs: Self::Output) -> Self::Output {
+        *self + rhs
+    }
+}
+impl<P: PrimeFieldParams> Add<&U64FieldElement<P>> for U64FieldElement<P> {
+    type Output = U64FieldElement<P>;
+    fn add(self, rhs: &Self::Output) -> Self::Output {
+        self + *rhs
+    }
+}
+///// Subtraction
+impl<P: PrimeFieldParams> Sub<&U64FieldElement<P>> for &U64FieldElement<P> {
+    type Output = U64FieldElement<P>;
 
-        if overflow {
-            res += u64::MAX - self.prime;
+    /// (a(modp))-(b(modp)) (modp)  ==>  amodp + (-b)modp = amodp + (p-b)modp
+    ///
+    /// (a + p - b) is subject to overflows, but our addition function is already
+    /// precautious against such situations
+    fn sub(self, rhs: &Self::Output) -> Self::Output {
+        self + (-rhs)
+    }
+}
+impl<P: PrimeFieldParams> Sub<U64FieldElement<P>> for U64FieldElement<P> {
+    type Output = U64FieldElement<P>;
+    fn sub(self, rhs: Self::Output) -> Self::Output {
+        &self - &rhs
+    }
+}
+impl<P: PrimeFieldParams> Sub<U64FieldElement<P>> for &U64FieldElement<P> {
+    type Output = U64FieldElement<P>;
+    fn sub(self, rhs: Self::Output) -> Self::Output {
+        *self - rhs
+    }
+}
+impl<P: PrimeFieldParams> Sub<&U64FieldElement<P>> for U64FieldElement<P> {
+    type Output = U64FieldElement<P>;
+    fn sub(self, rhs: &Self::Output) -> Self::Output {
+        self - *rhs
+    }
+}
+///// Multiplication
+impl<P: PrimeFieldParams> Mul<&U64FieldElement<P>> for &U64FieldElement<P> {
+    type Output = U64FieldElement<P>;
+
+    /// Two Montgomery reductions of the 128-bit product: the first gives
+    /// ab/R, the second multiplies that by R^2 and reduces again, back to ab.
+    /// Only wrapping arithmetic and selects, so the timing does not depend on
+    /// the operands. See `mul_vartime` for the variable-time path.
+    ///
+    /// R = 2^64 needs an odd modulus; the only even prime is 2, where the
+    /// product of two bits is their AND.
+    fn mul(self, rhs: &Self::Output) -> Self::Output {
+        if U64FieldElement::<P>::PRIME & 1 == 0 {
+            return U64FieldElement::from_reduced(self.num & rhs.num);
         }
+        let t = U64FieldElement::<P>::redc(self.num as u128 * rhs.num as u128);
+        let num = U64FieldElement::<P>::redc(t as u128 * U64FieldElement::<P>::R2 as u128);
+        U64FieldElement::from_reduced(num)
+    }
+}
+impl<P: PrimeFieldParams> Mul<U64FieldElement<P>> for U64FieldElement<P> {
+    type Output = U64FieldElement<P>;
+    fn mul(self, rhs: Self::Output) -> Self::Output {
+        &self * &rhs
+    }
+}
+impl<P: PrimeFieldParams> Mul<U64FieldElement<P>> for &U64FieldElement<P> {
+    type Output = U64FieldElement<P>;
+    fn mul(self, rhs: Self::Output) -> Self::Output {
+        *self * rhs
+    }
+}
+impl<P: PrimeFieldParams> Mul<&U64FieldElement<P>> for U64FieldElement<P> {
+    type Output = U64FieldElement<P>;
+    fn mul(self, rhs: &Self::Output) -> Self::Output {
+        self * *rhs
+    }
+}
+///// Division
+impl<P: PrimeFieldParams> Div<&U64FieldElement<P>> for &U64FieldElement<P> {
+    type Output = U64FieldElement<P>;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: &Self::Output) -> Self::Output {
+        self * rhs.inv().expect("division by zero")
+    }
+}
+impl<P: PrimeFieldParams> Div<U64FieldElement<P>> for U64FieldElement<P> {
+    type Output = U64FieldElement<P>;
+    fn div(self, rhs: Self::Output) -> Self::Output {
+        &self / &rhs
+    }
+}
+impl<P: PrimeFieldParams> Div<&U64FieldElement<P>> for U64FieldElement<P> {
+    type Output = U64FieldElement<P>;
+    fn div(self, rhs: &Self::Output) -> Self::Output {
+        self / *rhs
+    }
+}
+impl<P: PrimeFieldParams> Div<U64FieldElement<P>> for &U64FieldElement<P> {
+    type Output = U64FieldElement<P>;
+    fn div(self, rhs: Self::Output) -> Self::Output {
+        *self / rhs
+    }
+}
+///// Neg
+impl<P: PrimeFieldParams> Neg for &U64FieldElement<P> {
+    type Output = U64FieldElement<P>;
+    fn neg(self) -> Self::Output {
+        // p - 0 is not reduced, so zero is selected back in
+        let neg = U64FieldElement::from_reduced(U64FieldElement::<P>::PRIME - self.num);
+        U64FieldElement::conditional_select(&neg, self, self.num.ct_eq(&0))
+    }
+}
+impl<P: PrimeFieldParams> Neg for U64FieldElement<P> {
+    type Output = U64FieldElement<P>;
+    fn neg(self) -> Self::Output {
+        -&self
+    }
+}
 
-        res %= self.prime;
+forward_assign_ops!(impl<P: PrimeFieldParams> for U64FieldElement<P>);
+
+/////////////////////////////////////////////
+/////////////// Field Requirements
+/////////////////////////////////////////////
+impl<P: PrimeFieldParams> U64FieldElement<P> {
+    const PRIME: u64 = {
+        let m = P::MODULUS.0;
+        assert!(
+            m[1] == 0 && m[2] == 0 && m[3] == 0,
+            "modulus does not fit in a u64"
+        );
+        assert!(is_probable_prime(&[m[0]]), "modulus is not prime");
+        m[0]
+    };
+
+    const INV: u64 = mont_inv(Self::PRIME);
+    const R2: u64 = mont_r2_u64(Self::PRIME);
+
+    pub const ZERO: Self = Self::from_reduced(0);
+    pub const ONE: Self = Self::from_reduced(1);
 
-        Ok(Self {
-            num: res,
-            prime: self.prime,
-        })
+    pub const fn new(num: u64) -> Self {
+        Self::from_reduced(num % Self::PRIME)
     }
 
-    pub fn double(&self) -> Result<Self> {
-        self.add(&self)
+    pub const fn prime() -> u64 {
+        Self::PRIME
     }
 
-    pub fn neg(&self) -> Result<Self> {
-        Ok(Self {
-            num: self.prime - self.num,
-            prime: self.prime,
-        })
+    pub fn num(&self) -> u64 {
+        self.num
     }
 
-    /// (a(modp))-(b(modp)) (modp)  ==>  amodp + (-b)modp = amodp + (p-b)modp
-    ///
-    /// (a + p - b) is subject to overflows, but our addition function is already
-    /// precautious against such situations
-    pub fn sub(&self, other: &Self) -> Result<Self> {
-        Self::is_same_field(&self.prime, &other.prime)?;
+    pub fn to_bytes_be(&self) -> [u8; 8] {
+        self.num.to_be_bytes()
+    }
 
-        let res = self.add(&other.neg()?)?;
+    pub fn to_bytes_le(&self) -> [u8; 8] {
+        self.num.to_le_bytes()
+    }
 
-        Ok(res)
+    /// Inverse of `to_bytes_be`, rejecting anything but exactly 8 bytes
+    /// holding a value below p
+    pub fn from_bytes_be(bytes: &[u8]) -> Result<Self> {
+        let bytes = bytes.try_into().map_err(|_| Error::InvalidLength)?;
+        Self::from_canonical(u64::from_be_bytes(bytes))
     }
 
-    /// Double & add algorithm
-    ///
-    /// Example: 5 * 45 = 5 * (101101)_2
-    /// Iterate all bits of 45 starting from the LSB, and hold 2 aggregators:
-    ///     base: this ticker will get doubled at each bit, unconditionally.
-    ///             `base` will start from "5"
-    ///     res:  we'll add `base` to this variable whenever the current bit is 1
-    ///             `res` will start from "0"
-    ///
-    /// res = 5 + 20 + 40 + 160 = 225
-    pub fn mul(&self, other: &Self) -> Result<Self> {
-        Self::is_same_field(&self.prime, &other.prime)?;
-
-        let mut other = other.num;
-        if other == Self::ZERO {
-            return Ok(Self {
-                num: 0,
-                prime: self.prime,
-            });
+    /// Inverse of `to_bytes_le`, with the same checks as `from_bytes_be`
+    pub fn from_bytes_le(bytes: &[u8]) -> Result<Self> {
+        let bytes = bytes.try_into().map_err(|_| Error::InvalidLength)?;
+        Self::from_canonical(u64::from_le_bytes(bytes))
+    }
+
+    /// Parses up to 16 hex digits, with or without `0x`. Like `new`, the
+    /// value is reduced mod p.
+    pub fn from_hex_str(num: &str) -> Result<Self> {
+        let digits = num.strip_prefix("0x").unwrap_or(num);
+        Self::from_str_radix(digits, 16)
+    }
+
+    /// Parses a decimal number below 2^64. Like `new`, the value is reduced
+    /// mod p.
+    pub fn from_dec_str(num: &str) -> Result<Self> {
+        Self::from_str_radix(num, 10)
+    }
+
+    /// self * rhs with a single 128-bit product and `%`, variable time
+    pub fn mul_vartime(&self, rhs: &Self) -> Self {
+        let prod = self.num as u128 * rhs.num as u128;
+        Self::from_reduced((prod % Self::PRIME as u128) as u64)
+    }
+
+    /// self^exp in constant time for a fixed `exp.len()`, exponent as
+    /// little-endian u64 limbs
+    pub fn pow_ct(&self, exp: &[u64]) -> Self {
+        crate::field::pow_ct(self, exp)
+    }
+
+    /// self^exp by a sliding window over `mul_vartime`, variable time.
+    /// exp(0) is one, also for zero.
+    pub fn exp(&self, exp: &u64) -> Self {
+        exponentiation::sliding_window(self, &[*exp], Self::ONE, Self::mul_vartime)
+    }
+
+    fn from_str_radix(digits: &str, radix: u32) -> Result<Self> {
+        // from_str_radix would take a sign
+        if digits.starts_with('+') {
+            return Err(Error::InvalidString);
         }
+        let num = u64::from_str_radix(digits, radix).map_err(|_| Error::InvalidString)?;
+        Ok(Self::new(num))
+    }
 
-        let mut base = self.clone();
-        let mut res = Self {
-            num: 0,
-            prime: self.prime,
-        };
+    fn from_canonical(num: u64) -> Result<Self> {
+        match num < Self::PRIME {
+            true => Ok(Self::from_reduced(num)),
+            false => Err(Error::NonCanonical),
+        }
+    }
 
-        while other != Self::ZERO {
-            if other & 1 == 1 {
-                res = res.add(&base)?;
-            }
-            base = base.double()?;
-            other >>= 1;
+    fn redc(t: u128) -> u64 {
+        redc_u64(t, Self::PRIME, Self::INV)
+    }
+
+    const fn from_reduced(num: u64) -> Self {
+        Self {
+            num,
+            _params: PhantomData,
         }
+    }
+}
 
-        Ok(res)
+impl<P: PrimeFieldParams> Field for U64FieldElement<P> {
+    fn zero() -> Self {
+        Self::ZERO
     }
 
-    pub fn sq(&self) -> Result<Self> {
-        self.mul(&self)
+    fn one() -> Self {
+        Self::ONE
     }
 
-    // Uses Fermat's little
-    pub fn mul_inv(&self) -> Result<Self> {
-        self.exp(&(&self.prime - 2))
+    fn is_zero(&self) -> bool {
+        self.num == 0
     }
 
-    pub fn div(&self, other: &Self) -> Result<Self> {
-        let other_inv = other.mul_inv()?;
-        let res = self.mul(&other_inv)?;
-        Ok(res)
+    fn double(&self) -> Self {
+        self + self
     }
 
-    // Square & add algorithm
-    pub fn exp(&self, exp: &u64) -> Result<Self> {
-        // Use fermat's little theorem
-        let mut exp = *exp % (self.prime - 1);
+    fn square(&self) -> Self {
+        self * self
+    }
 
-        if exp == Self::ZERO {
-            return Ok(Self {
-                num: Self::ZERO,
-                prime: self.prime,
-            });
+    // Uses Fermat's little
+    fn inv(&self) -> Option<Self> {
+        if self.is_zero() {
+            return None;
         }
+        Some(self.exp(&(Self::PRIME - 2)))
+    }
 
-        let mut base = self.clone();
-        let mut res = Self {
-            num: Self::ONE,
-            prime: self.prime,
-        };
+    /// The 128-bit products are added up in 192 bits and reduced once
+    fn sum_of_products(a: &[Self], b: &[Self]) -> Self {
+        assert_eq!(a.len(), b.len(), "slices of different lengths");
+        let (mut lo, mut hi) = (0u128, 0u64);
+        for (x, y) in a.iter().zip(b) {
+            let (sum, overflow) = lo.overflowing_add(x.num as u128 * y.num as u128);
+            lo = sum;
+            hi += overflow as u64;
+        }
+
+        // lo + hi 2^128 mod p, all below p^2 + p < 2^128
+        let p = Self::PRIME as u128;
+        let r = (u128::MAX % p + 1) % p;
+        Self::from_reduced(((lo % p + (hi as u128 % p) * r) % p) as u64)
+    }
+
+    fn characteristic() -> Vec<u64> {
+        vec![Self::PRIME]
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes_be().to_vec()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        Self::from_bytes_be(bytes).ok()
+    }
 
-        while exp != Self::ZERO {
-            if exp & 1 == 1 {
-                res = res.mul(&base)?;
+    /// Rejection sampling on NUM_BITS random bits, see `U256FieldElement`
+    fn random<R: RngCore + ?Sized>(rng: &mut R) -> Self {
+        loop {
+            let num = rng.next_u64() >> (64 - Self::NUM_BITS);
+            if num < Self::PRIME {
+                return Self::from_reduced(num);
             }
-            base = base.sq()?;
-            exp >>= 1;
         }
+    }
+}
+
+impl<P: PrimeFieldParams> PrimeField for U64FieldElement<P> {
+    const NUM_BITS: u32 = 64 - Self::PRIME.leading_zeros();
 
-        return Ok(res);
+    fn from_u64(num: u64) -> Self {
+        Self::new(num)
     }
 
-    fn is_same_field(p_1: &u64, p_2: &u64) -> Result<()> {
-        if *p_1 != *p_2 {
-            return Err(Error::DifferentFields);
+    fn multiplicative_generator() -> Option<Self> {
+        P::MULTIPLICATIVE_GENERATOR.map(|g| Self::new(g.low_u64()))
+    }
+}
+
+/////////////////////////////////////////////
+/////////////// Multiplicative Group
+/////////////////////////////////////////////
+/// Structure of GF(p)*, from a factorization of p - 1 (see `factor`).
+/// p - 1 < 2^64 always factors, so these never fail; they return `Result`
+/// only to match `U256FieldElement`. Variable time.
+impl<P: PrimeFieldParams> U64FieldElement<P> {
+    /// The least k > 0 with self^k = 1, `None` for zero
+    pub fn order(&self) -> Result<Option<u64>> {
+        if self.is_zero() {
+            return Ok(None);
+        }
+
+        // strip each prime from p - 1 for as long as the power stays 1
+        let mut order = Self::PRIME - 1;
+        for (q, e) in factor_u64(order) {
+            for _ in 0..e {
+                if self.exp(&(order / q)) != Self::ONE {
+                    break;
+                }
+                order /= q;
+            }
         }
-        Ok(())
+        Ok(Some(order))
+    }
+
+    /// Whether self generates GF(p)*, i.e. has order p - 1
+    pub fn is_primitive_root(&self) -> Result<bool> {
+        Ok(self.generates(&factor_u64(Self::PRIME - 1)))
+    }
+
+    /// The smallest primitive root mod p
+    pub fn find_generator() -> Result<Self> {
+        let factors = factor_u64(Self::PRIME - 1);
+        Ok((1..Self::PRIME)
+            .map(Self::from_reduced)
+            .find(|x| x.generates(&factors))
+            .unwrap())
+    }
+
+    /// x generates GF(p)* iff x^((p-1)/q) != 1 for every prime q | p - 1
+    fn generates(&self, factors: &[(u64, u32)]) -> bool {
+        !self.is_zero()
+            && factors
+                .iter()
+                .all(|(q, _)| self.exp(&((Self::PRIME - 1) / q)) != Self::ONE)
     }
 }
 
-impl Eq for U64FieldElement {}
-impl PartialEq for U64FieldElement {
-    fn eq(&self, other: &Self) -> bool {
-        self.num == other.num && self.prime == other.prime
+/////////////////////////////////////////////
+/////////////// Constant Time
+/////////////////////////////////////////////
+impl<P: PrimeFieldParams> ConstantTimeEq for U64FieldElement<P> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.num.ct_eq(&other.num)
+    }
+}
+
+impl<P: PrimeFieldParams> ConditionallySelectable for U64FieldElement<P> {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        Self::from_reduced(u64::conditional_select(&a.num, &b.num, choice))
+    }
+}
+
+impl<P: PrimeFieldParams> Zeroize for U64FieldElement<P> {
+    fn zeroize(&mut self) {
+        self.num.zeroize();
     }
 }
 
+/////////////////////////////////////////////
+/////////////// Montgomery Reduction
+/////////////////////////////////////////////
+// Shared with `SmallFieldElement`'s default reduction for primes above 2^32.
+// Both need an odd p.
+
+/// 2^128 mod p
+pub(crate) const fn mont_r2_u64(p: u64) -> u64 {
+    let p = p as u128;
+    let r = (1u128 << 64) % p;
+    (r * r % p) as u64
+}
+
+/// t / 2^64 mod p for t < p 2^64, given inv = -p^-1 mod 2^64, in constant
+/// time
+pub(crate) const fn redc_u64(t: u128, p: u64, inv: u64) -> u64 {
+    let p = p as u128;
+    let m = (t as u64).wrapping_mul(inv) as u128;
+    // t + m*p can reach 2^129 when p is close to 2^64
+    let (sum, carry) = t.overflowing_add(m * p);
+    let t = (sum >> 64) | ((carry as u128) << 64);
+    let (r, borrow) = t.overflowing_sub(p);
+    let keep = (borrow as u64).wrapping_neg();
+    (t as u64 & keep) | (r as u64 & !keep)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::params::u256_from_hex;
+    use primitive_types::U256;
+    use subtle::ConditionallyNegatable;
+
+    test_prime_field!(P19, U256([19, 0, 0, 0]));
+    test_prime_field!(P23, U256([23, 0, 0, 0]));
+    test_prime_field!(P97, U256([97, 0, 0, 0]));
+    test_prime_field!(P859, U256([859, 0, 0, 0]));
+    test_prime_field!(P64, u256_from_hex("0xFFFFFFFFFFFFFFC5"));
 
     #[test]
-    fn new_err_not_a_prime() {
-        let err = U64FieldElement::new(17, 21).unwrap_err();
-        assert_eq!(err, Error::NotPrime);
+    fn params_are_prime() {
+        P19::check().unwrap();
+        P23::check().unwrap();
+        P97::check().unwrap();
+        P859::check().unwrap();
+        P64::check().unwrap();
     }
 
     #[test]
     fn new_1() {
-        let prime = 23;
         let num = 7871238;
-        let a = U64FieldElement::new(num, prime).unwrap();
-        assert_eq!(a, U64FieldElement { num: 17, prime });
+        let a = U64FieldElement::<P23>::new(num);
+        assert_eq!(a.num(), 17);
     }
 
     #[test]
     fn cmp_neq_1() {
-        let a = U64FieldElement::new(17, 23).unwrap();
-        let b = U64FieldElement::new(16, 23).unwrap();
+        let a = U64FieldElement::<P23>::new(17);
+        let b = U64FieldElement::<P23>::new(16);
         assert_ne!(a, b);
     }
 
     #[test]
-    fn cmp_neq_2() {
-        let c = U64FieldElement::new(17, 23).unwrap();
-        let d = U64FieldElement::new(17, 29).unwrap();
-        assert_ne!(c, d);
+    fn eq_1() {
+        let a = U64FieldElement::<P23>::new(17);
+        let b = U64FieldElement::<P23>::new(17);
+        assert_eq!(a, b);
     }
 
     #[test]
-    fn eq_1() {
-        let a = U64FieldElement::new(17, 23).unwrap();
-        let b = U64FieldElement::new(17, 23).unwrap();
-        assert_eq!(a, b);
+    fn add_1() {
+        let num1: u64 = 17;
+        let num2: u64 = 2222223; // 849 mod 859
+
+        let a = U64FieldElement::<P859>::new(num1);
+        let b = U64FieldElement::<P859>::new(num2);
+
+        let res = a + b;
+        assert_eq!(res, U64FieldElement::new(7));
     }
 
     #[test]
-    fn add_err_different_primes() {
-        let a = U64FieldElement::new(17, 797).unwrap();
-        let b = U64FieldElement::new(17, 859).unwrap();
+    fn add_overflow() {
+        let a = U64FieldElement::<P64>::new(u64::MAX - 100);
+        let b = U64FieldElement::<P64>::new(u64::MAX - 200);
 
-        let err = a.add(&b).unwrap_err();
-        assert_eq!(err, Error::DifferentFields);
+        // p = 2^64 - 59, so a = p - 42 and b = p - 142
+        let res = a + b;
+        assert_eq!(res.num(), u64::MAX - 58 - 184);
     }
 
     #[test]
-    fn add_1() {
-        let prime: u64 = 859;
+    fn sub_1() {
         let num1: u64 = 17;
         let num2: u64 = 2222223; // 849 mod 859
 
-        let a = U64FieldElement::new(num1, prime).unwrap();
-        let b = U64FieldElement::new(num2, prime).unwrap();
+        let a = U64FieldElement::<P859>::new(num1);
+        let b = U64FieldElement::<P859>::new(num2);
 
-        let res = a.add(&b).unwrap();
-        assert_eq!(res, U64FieldElement { prime, num: 7 });
+        let res = a - b;
+        assert_eq!(res, U64FieldElement::new(27));
     }
 
     #[test]
-    fn sub_err_different_primes() {
-        let a = U64FieldElement::new(17, 797).unwrap();
-        let b = U64FieldElement::new(17, 859).unwrap();
+    fn neg_zero() {
+        let zero = U64FieldElement::<P19>::new(0);
+        assert_eq!((-zero).num(), 0);
+    }
 
-        let err = a.add(&b).unwrap_err();
-        assert_eq!(err, Error::DifferentFields);
+    #[test]
+    fn mul_vartime_matches_mul() {
+        for (a, b) in [
+            (0, 5),
+            (1, 1),
+            (u64::MAX - 59, u64::MAX - 60),
+            (12345, 67890),
+        ] {
+            let (a, b) = (U64FieldElement::<P64>::new(a), U64FieldElement::new(b));
+            assert_eq!(a.mul_vartime(&b), a * b);
+        }
+        let mut x = 0x9e3779b97f4a7c15u64;
+        for _ in 0..1000 {
+            x = x
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let (a, b) = (x, x.rotate_left(29));
+            let (a64, b64) = (U64FieldElement::<P64>::new(a), U64FieldElement::new(b));
+            assert_eq!(a64.mul_vartime(&b64), a64 * b64);
+            let (a97, b97) = (U64FieldElement::<P97>::new(a), U64FieldElement::new(b));
+            assert_eq!(a97.mul_vartime(&b97), a97 * b97);
+        }
     }
 
     #[test]
-    fn sub_1() {
-        let prime: u64 = 859;
-        let num1: u64 = 17;
-        let num2: u64 = 2222223; // 849 mod 859
+    fn constant_time_ops() {
+        let a = U64FieldElement::<P64>::new(u64::MAX - 100);
+        let b = U64FieldElement::<P64>::new(42);
+        assert!(bool::from(a.ct_eq(&a)));
+        assert!(!bool::from(a.ct_eq(&b)));
+        assert_eq!(
+            U64FieldElement::conditional_select(&a, &b, Choice::from(0)),
+            a
+        );
+        assert_eq!(
+            U64FieldElement::conditional_select(&a, &b, Choice::from(1)),
+            b
+        );
 
-        let a = U64FieldElement::new(num1, prime).unwrap();
-        let b = U64FieldElement::new(num2, prime).unwrap();
+        let mut c = a;
+        c.conditional_negate(Choice::from(1));
+        assert_eq!(c, -a);
 
-        let res = a.sub(&b).unwrap();
-        assert_eq!(res, U64FieldElement { prime, num: 27 });
+        assert_eq!(a.pow_ct(&[0]), U64FieldElement::one());
+        assert_eq!(a.pow_ct(&[1234567]), a.pow(&[1234567]));
+        assert_eq!(a.pow_ct(&[1234567, 0]), a.pow(&[1234567]));
     }
 
     #[test]
     fn mul_1() {
-        let prime: u64 = 859;
         let num1: u64 = 17;
         let num2: u64 = 2222223; // 849 mod 859
 
         let expected_result: u64 = 689;
 
-        let a = U64FieldElement::new(num1, prime).unwrap();
-        let b = U64FieldElement::new(num2, prime).unwrap();
+        let a = U64FieldElement::<P859>::new(num1);
+        let b = U64FieldElement::<P859>::new(num2);
 
-        let res = a.mul(&b).unwrap();
-        assert_eq!(
-            res,
-            U64FieldElement {
-                prime,
-                num: expected_result
-            }
-        );
+        let res = a * b;
+        assert_eq!(res, U64FieldElement::new(expected_result));
     }
 
     #[test]
     fn exp_1() {
-        let prime = 97;
-        let a = U64FieldElement::new(3, prime).unwrap();
-        let res = a.exp(&4).unwrap();
-        assert_eq!(res, U64FieldElement { prime, num: 81 });
+        let a = U64FieldElement::<P97>::new(3);
+        let res = a.exp(&4);
+        assert_eq!(res, U64FieldElement::new(81));
     }
 
     #[test]
     fn exp_2() {
-        let prime = 97;
-        let a = U64FieldElement::new(1, prime).unwrap();
-        let res = a.exp(&326423784).unwrap();
-        assert_eq!(res, U64FieldElement { prime, num: 1 });
+        let a = U64FieldElement::<P97>::new(1);
+        let res = a.exp(&326423784);
+        assert_eq!(res, U64FieldElement::new(1));
     }
 
     #[test]
     fn exp_3() {
-        let prime = 18446744073709551557;
-        let a = U64FieldElement::new(2, prime).unwrap();
-        let res = a.exp(&35).unwrap();
+        let a = U64FieldElement::<P64>::new(2);
+        let res = a.exp(&35);
+        assert_eq!(res, U64FieldElement::new(34359738368));
+    }
+
+    #[test]
+    fn exp_zero() {
+        type F = U64FieldElement<P97>;
+        assert_eq!(F::new(3).exp(&0), F::ONE);
+        assert_eq!(F::ZERO.exp(&0), F::ONE);
+        assert_eq!(F::ZERO.exp(&96), F::ZERO);
+        assert_eq!(F::new(3).exp(&96), F::ONE);
+        assert_eq!(F::new(3).exp(&u64::MAX), F::new(3).pow(&[u64::MAX]));
+    }
+
+    #[test]
+    fn inv_zero() {
+        assert_eq!(U64FieldElement::<P19>::zero().inv(), None);
+    }
+
+    #[test]
+    fn bytes_roundtrip() {
+        let a = U64FieldElement::<P64>::new(0xdeadbeef);
+        assert_eq!(U64FieldElement::from_bytes(&a.to_bytes()), Some(a));
         assert_eq!(
-            res,
-            U64FieldElement {
-                prime,
-                num: 34359738368
-            }
+            U64FieldElement::<P64>::from_bytes(&u64::MAX.to_be_bytes()),
+            None
         );
     }
 
+    #[test]
+    fn pow_matches_exp() {
+        let a = U64FieldElement::<P64>::new(3);
+        assert_eq!(a.pow(&[1234567]), a.exp(&1234567));
+    }
+
     #[test]
     fn test_div_ez() {
-        let prime = 19;
-        let a = U64FieldElement::new(2, prime).unwrap();
-        let b = U64FieldElement::new(7, prime).unwrap();
-        let c = U64FieldElement::new(3, prime).unwrap();
+        let a = U64FieldElement::<P19>::new(2);
+        let b = U64FieldElement::<P19>::new(7);
+        let c = U64FieldElement::<P19>::new(3);
 
-        assert_eq!(a.div(&b).unwrap(), c);
+        assert_eq!(a / b, c);
     }
 
     #[test]
     fn test_div_hard() {
-        let prime = 19;
-        let a = U64FieldElement::new(2, prime).unwrap();
-        let b = U64FieldElement::new(7, prime).unwrap();
-        let c = U64FieldElement::new(3, prime).unwrap();
+        let a = U64FieldElement::<P19>::new(2);
+        let b = U64FieldElement::<P19>::new(7);
+        let c = U64FieldElement::<P19>::new(3);
+
+        assert_eq!(a / b, c);
+    }
+
+    #[test]
+    fn bytes_le_be() {
+        let a = U64FieldElement::<P859>::new(0x0102);
+        assert_eq!(a.to_bytes_be(), [0, 0, 0, 0, 0, 0, 1, 2]);
+        assert_eq!(a.to_bytes_le(), [2, 1, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(U64FieldElement::from_bytes_be(&a.to_bytes_be()), Ok(a));
+        assert_eq!(U64FieldElement::from_bytes_le(&a.to_bytes_le()), Ok(a));
+
+        assert_eq!(
+            U64FieldElement::<P859>::from_bytes_le(&859u64.to_le_bytes()),
+            Err(Error::NonCanonical)
+        );
+        assert_eq!(
+            U64FieldElement::<P859>::from_bytes_be(&[1, 2]),
+            Err(Error::InvalidLength)
+        );
+    }
+
+    #[test]
+    fn parse_strings() {
+        type F = U64FieldElement<P97>;
+        assert_eq!(F::from_hex_str("0x64"), Ok(F::new(3)));
+        assert_eq!(F::from_dec_str("100"), Ok(F::new(3)));
+        assert_eq!("64".parse::<F>(), Ok(F::new(3)));
+
+        for bad in ["", "0x", "+64", "0xzz", "1ffffffffffffffff"] {
+            assert_eq!(F::from_hex_str(bad), Err(Error::InvalidString));
+        }
+        for bad in ["", "-1", "+1", "1a", "18446744073709551616"] {
+            assert_eq!(F::from_dec_str(bad), Err(Error::InvalidString));
+        }
+    }
+
+    #[test]
+    fn multiplicative_group() {
+        type F = U64FieldElement<P97>;
+        // 5 is the smallest primitive root mod 97, 96 = 2^5 * 3
+        assert_eq!(F::find_generator(), Ok(F::new(5)));
+        assert_eq!(F::new(5).is_primitive_root(), Ok(true));
+        assert_eq!(F::new(2).is_primitive_root(), Ok(false));
+        assert_eq!(F::ZERO.is_primitive_root(), Ok(false));
+        assert_eq!(F::ZERO.order(), Ok(None));
+        assert_eq!(F::ONE.order(), Ok(Some(1)));
+        assert_eq!(F::new(96).order(), Ok(Some(2)));
+        assert_eq!(F::new(2).order(), Ok(Some(48)));
+        assert_eq!(F::new(5).square().order(), Ok(Some(48)));
+        assert_eq!(F::new(5).exp(&32).order(), Ok(Some(3)));
+
+        type G = U64FieldElement<crate::primes::Goldilocks>;
+        let g = G::find_generator().unwrap();
+        assert_eq!(g, G::new(7));
+        assert_eq!(g.order(), Ok(Some(G::prime() - 1)));
+        assert_eq!(
+            G::root_of_unity(1 << 32).unwrap().order(),
+            Ok(Some(1 << 32))
+        );
 
-        assert_eq!(a.div(&b).unwrap(), c);
+        // p - 1 = 2^2 * 11 * 137 * 547 * 5594472617641, the last one found by rho
+        let g = U64FieldElement::<P64>::find_generator().unwrap();
+        assert_eq!(g, U64FieldElement::new(2));
+        assert_eq!(g.order(), Ok(Some(U64FieldElement::<P64>::prime() - 1)));
     }
 }
@@ -0,0 +1,511 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+use core::ops::{Add, Div, Mul, Neg, Sub};
+use core::str::FromStr;
+use rand_core::RngCore;
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
+use zeroize::Zeroize;
+
+use crate::errors::{Error, Result};
+use crate::field::{Field, PrimeField};
+use crate::limbs::{
+    add_mod, is_probable_prime, mont_inv, mont_mul, num_bits, one, pow2_mod, sbb, sub_mod,
+};
+use crate::params::WidePrimeFieldParams;
+
+/// Element of a prime field with an `N`-limb modulus, for primes that do not
+/// fit in `U256FieldElement`
+///
+/// Same design as `U256FieldElement`: Montgomery form with R = 2^(64 N),
+/// constant-time operators and comparisons, and the per-field constants
+/// computed at compile time. Values go in and out as little-endian limbs.
+///
+/// Inversion is by Fermat's little theorem with the constant-time
+/// multiplication, so unlike for the other prime field types it does not
+/// depend on the value either.
+pub struct WideFieldElement<P: WidePrimeFieldParams<N>, const N: usize> {
+    mont: [u64; N],
+    _params: PhantomData<P>,
+}
+
+/// Primes up to 384 bits, e.g. the BLS12-381 base field or P-384
+pub type Fp384<P> = WideFieldElement<P, 6>;
+/// Primes up to 512 bits
+pub type Fp512<P> = WideFieldElement<P, 8>;
+
+impl<P: WidePrimeFieldParams<N>, const N: usize> Clone for WideFieldElement<P, N> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<P: WidePrimeFieldParams<N>, const N: usize> Copy for WideFieldElement<P, N> {}
+
+impl<P: WidePrimeFieldParams<N>, const N: usize> core::fmt::Debug for WideFieldElement<P, N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("WideFieldElement")
+            .field("num", &to_hex(&self.num()))
+            .field("prime", &to_hex(&P::MODULUS))
+            .finish()
+    }
+}
+
+impl<P: WidePrimeFieldParams<N>, const N: usize> FromStr for WideFieldElement<P, N> {
+    type Err = Error;
+    /// Same as `from_hex_str`
+    fn from_str(num: &str) -> Result<Self> {
+        Self::from_hex_str(num)
+    }
+}
+
+/////////////////////////////////////////////
+/////////////// Operator Overloads
+/////////////////////////////////////////////
+///// Equality
+impl<P: WidePrimeFieldParams<N>, const N: usize> Eq for WideFieldElement<P, N> {}
+impl<P: WidePrimeFieldParams<N>, const N: usize> PartialEq for WideFieldElement<P, N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+///// Addition
+impl<P: WidePrimeFieldParams<N>, const N: usize> Add<&WideFieldElement<P, N>>
+    for &WideFieldElement<P, N>
+{
+    type Output = WideFieldElement<P, N>;
+    /// See `U256FieldElement`, a single conditional subtraction
+    fn add(self, rhs: &WideFieldElement<P, N>) -> WideFieldElement<P, N> {
+        WideFieldElement::from_mont(add_mod(&self.mont, &rhs.mont, &P::MODULUS))
+    }
+}
+///// Subtraction
+impl<P: WidePrimeFieldParams<N>, const N: usize> Sub<&WideFieldElement<P, N>>
+    for &WideFieldElement<P, N>
+{
+    type Output = WideFieldElement<P, N>;
+    fn sub(self, rhs: &WideFieldElement<P, N>) -> WideFieldElement<P, N> {
+        WideFieldElement::from_mont(sub_mod(&self.mont, &rhs.mont, &P::MODULUS))
+    }
+}
+///// Multiplication
+impl<P: WidePrimeFieldParams<N>, const N: usize> Mul<&WideFieldElement<P, N>>
+    for &WideFieldElement<P, N>
+{
+    type Output = WideFieldElement<P, N>;
+    fn mul(self, rhs: &WideFieldElement<P, N>) -> WideFieldElement<P, N> {
+        WideFieldElement::from_mont(mont_mul(
+            &self.mont,
+            &rhs.mont,
+            &P::MODULUS,
+            WideFieldElement::<P, N>::INV,
+        ))
+    }
+}
+///// Division
+impl<P: WidePrimeFieldParams<N>, const N: usize> Div<&WideFieldElement<P, N>>
+    for &WideFieldElement<P, N>
+{
+    type Output = WideFieldElement<P, N>;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: &WideFieldElement<P, N>) -> WideFieldElement<P, N> {
+        self * rhs.inv().expect("division by zero")
+    }
+}
+///// Neg
+impl<P: WidePrimeFieldParams<N>, const N: usize> Neg for &WideFieldElement<P, N> {
+    type Output = WideFieldElement<P, N>;
+    fn neg(self) -> WideFieldElement<P, N> {
+        WideFieldElement::ZERO - self
+    }
+}
+forward_ops!(impl<P: WidePrimeFieldParams<N>, const N: usize> for WideFieldElement<P, N>);
+forward_assign_ops!(impl<P: WidePrimeFieldParams<N>, const N: usize> for WideFieldElement<P, N>);
+
+/////////////////////////////////////////////
+/////////////// Field Requirements
+/////////////////////////////////////////////
+impl<P: WidePrimeFieldParams<N>, const N: usize> WideFieldElement<P, N> {
+    /// -p^{-1} mod 2^64, the Montgomery reduction constant
+    const INV: u64 = {
+        assert!(P::MODULUS[0] & 1 == 1, "Montgomery form needs an odd prime");
+        assert!(is_probable_prime(&P::MODULUS), "modulus is not prime");
+        mont_inv(P::MODULUS[0])
+    };
+    /// R mod p, which is also 1 in Montgomery form
+    const R: [u64; N] = pow2_mod(&one(), 64 * N, &P::MODULUS);
+    /// R^2 mod p, used to move values into Montgomery form
+    const R2: [u64; N] = pow2_mod(&Self::R, 64 * N, &P::MODULUS);
+
+    pub const ZERO: Self = Self::from_mont([0; N]);
+    pub const ONE: Self = Self::from_mont(Self::R);
+
+    /// Any `num` is accepted: multiplying by R^2 also reduces it mod p
+    pub const fn new(num: [u64; N]) -> Self {
+        Self::from_mont(mont_mul(&num, &Self::R2, &P::MODULUS, Self::INV))
+    }
+
+    pub const fn from_u64(num: u64) -> Self {
+        let mut limbs = [0; N];
+        limbs[0] = num;
+        Self::new(limbs)
+    }
+
+    pub const fn prime() -> [u64; N] {
+        P::MODULUS
+    }
+
+    /// Canonical representative in [0, prime)
+    pub fn num(&self) -> [u64; N] {
+        mont_mul(&self.mont, &one(), &P::MODULUS, Self::INV)
+    }
+
+    /// 8 * N bytes
+    pub fn to_bytes_be(&self) -> Vec<u8> {
+        self.num()
+            .iter()
+            .rev()
+            .flat_map(|limb| limb.to_be_bytes())
+            .collect()
+    }
+
+    /// 8 * N bytes
+    pub fn to_bytes_le(&self) -> Vec<u8> {
+        self.num()
+            .iter()
+            .flat_map(|limb| limb.to_le_bytes())
+            .collect()
+    }
+
+    /// Inverse of `to_bytes_be`, rejecting anything but exactly 8 * N bytes
+    /// holding a value below p
+    pub fn from_bytes_be(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != 8 * N {
+            return Err(Error::InvalidLength);
+        }
+        let mut num = [0u64; N];
+        for (limb, chunk) in num.iter_mut().zip(bytes.rchunks(8)) {
+            *limb = u64::from_be_bytes(chunk.try_into().unwrap());
+        }
+        Self::from_canonical(num)
+    }
+
+    /// Inverse of `to_bytes_le`, with the same checks as `from_bytes_be`
+    pub fn from_bytes_le(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != 8 * N {
+            return Err(Error::InvalidLength);
+        }
+        let mut num = [0u64; N];
+        for (limb, chunk) in num.iter_mut().zip(bytes.chunks(8)) {
+            *limb = u64::from_le_bytes(chunk.try_into().unwrap());
+        }
+        Self::from_canonical(num)
+    }
+
+    /// Parses up to 16 * N hex digits, with or without `0x`. Like `new`, the
+    /// value is reduced mod p.
+    pub fn from_hex_str(num: &str) -> Result<Self> {
+        let digits = num.strip_prefix("0x").unwrap_or(num);
+        if digits.is_empty() || digits.len() > 16 * N {
+            return Err(Error::InvalidString);
+        }
+        let mut limbs = [0u64; N];
+        for (limb, chunk) in limbs.iter_mut().zip(digits.as_bytes().rchunks(16)) {
+            let chunk = core::str::from_utf8(chunk).map_err(|_| Error::InvalidString)?;
+            if chunk.starts_with('+') {
+                return Err(Error::InvalidString);
+            }
+            *limb = u64::from_str_radix(chunk, 16).map_err(|_| Error::InvalidString)?;
+        }
+        Ok(Self::new(limbs))
+    }
+
+    /// Parses a decimal number below 2^(64 N). Like `new`, the value is
+    /// reduced mod p.
+    pub fn from_dec_str(num: &str) -> Result<Self> {
+        if num.is_empty() {
+            return Err(Error::InvalidString);
+        }
+        let mut limbs = [0u64; N];
+        for c in num.chars() {
+            let digit = c.to_digit(10).ok_or(Error::InvalidString)?;
+            // limbs = limbs * 10 + digit
+            let mut carry = digit as u128;
+            for limb in limbs.iter_mut() {
+                let t = *limb as u128 * 10 + carry;
+                *limb = t as u64;
+                carry = t >> 64;
+            }
+            if carry != 0 {
+                return Err(Error::InvalidString);
+            }
+        }
+        Ok(Self::new(limbs))
+    }
+
+    /// self^exp in constant time for a fixed `exp.len()`, exponent as
+    /// little-endian u64 limbs
+    pub fn pow_ct(&self, exp: &[u64]) -> Self {
+        crate::field::pow_ct(self, exp)
+    }
+
+    fn from_canonical(num: [u64; N]) -> Result<Self> {
+        match sbb(&num, &P::MODULUS).1 {
+            1 => Ok(Self::new(num)),
+            _ => Err(Error::NonCanonical),
+        }
+    }
+
+    const fn from_mont(mont: [u64; N]) -> Self {
+        Self {
+            mont,
+            _params: PhantomData,
+        }
+    }
+}
+
+impl<P: WidePrimeFieldParams<N>, const N: usize> Field for WideFieldElement<P, N> {
+    fn zero() -> Self {
+        Self::ZERO
+    }
+
+    fn one() -> Self {
+        Self::ONE
+    }
+
+    fn is_zero(&self) -> bool {
+        self.mont.iter().all(|&limb| limb == 0)
+    }
+
+    fn double(&self) -> Self {
+        self + self
+    }
+
+    fn square(&self) -> Self {
+        self * self
+    }
+
+    /// self^(p-2), branching on the bits of p only
+    fn inv(&self) -> Option<Self> {
+        if self.is_zero() {
+            return None;
+        }
+        let mut two = [0; N];
+        two[0] = 2;
+        let (exp, _) = sbb(&P::MODULUS, &two);
+        Some(self.pow(&exp))
+    }
+
+    fn characteristic() -> Vec<u64> {
+        P::MODULUS.to_vec()
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes_be()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        Self::from_bytes_be(bytes).ok()
+    }
+
+    /// Rejection sampling on NUM_BITS random bits, see `U256FieldElement`
+    fn random<R: RngCore + ?Sized>(rng: &mut R) -> Self {
+        let top_bits = Self::NUM_BITS as usize - 64 * (N - 1);
+        loop {
+            let mut num = [0u64; N];
+            for limb in num.iter_mut() {
+                *limb = rng.next_u64();
+            }
+            num[N - 1] >>= 64 - top_bits;
+            if sbb(&num, &P::MODULUS).1 == 1 {
+                return Self::new(num);
+            }
+        }
+    }
+}
+
+impl<P: WidePrimeFieldParams<N>, const N: usize> PrimeField for WideFieldElement<P, N> {
+    const NUM_BITS: u32 = num_bits(&P::MODULUS);
+
+    fn from_u64(num: u64) -> Self {
+        Self::from_u64(num)
+    }
+
+    fn multiplicative_generator() -> Option<Self> {
+        P::MULTIPLICATIVE_GENERATOR.map(Self::new)
+    }
+}
+
+/////////////////////////////////////////////
+/////////////// Constant Time
+/////////////////////////////////////////////
+impl<P: WidePrimeFieldParams<N>, const N: usize> ConstantTimeEq for WideFieldElement<P, N> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.mont.ct_eq(&other.mont)
+    }
+}
+
+impl<P: WidePrimeFieldParams<N>, const N: usize> ConditionallySelectable
+    for WideFieldElement<P, N>
+{
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        let mut mont = [0u64; N];
+        for (i, limb) in mont.iter_mut().enumerate() {
+            *limb = u64::conditional_select(&a.mont[i], &b.mont[i], choice);
+        }
+        Self::from_mont(mont)
+    }
+}
+
+impl<P: WidePrimeFieldParams<N>, const N: usize> Zeroize for WideFieldElement<P, N> {
+    fn zeroize(&mut self) {
+        self.mont.zeroize();
+    }
+}
+
+fn to_hex<const N: usize>(limbs: &[u64; N]) -> String {
+    let digits: String = limbs.iter().rev().map(|l| format!("{:016x}", l)).collect();
+    match digits.trim_start_matches('0') {
+        "" => "0x0".to_string(),
+        digits => format!("0x{}", digits),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::params::limbs_from_hex;
+    use crate::primes::{Bls12381Fq, P384, P521};
+    use num_bigint::BigUint;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    type Bls = Fp384<Bls12381Fq>;
+
+    /// 2^383 + 35 * 2^64 + 1, a prime whose low limb is 1, so that p - 2
+    /// borrows from the next limb
+    #[derive(Debug)]
+    struct LowLimbOne;
+    impl WidePrimeFieldParams<6> for LowLimbOne {
+        const MODULUS: [u64; 6] = [1, 0x23, 0, 0, 0, 1 << 63];
+    }
+
+    fn biguint<const N: usize>(limbs: &[u64; N]) -> BigUint {
+        BigUint::from_bytes_le(
+            &limbs
+                .iter()
+                .flat_map(|l| l.to_le_bytes())
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    #[test]
+    fn params_are_prime() {
+        Bls12381Fq::check().unwrap();
+        P384::check().unwrap();
+        P521::check().unwrap();
+        LowLimbOne::check().unwrap();
+    }
+
+    /// Compares against BigUint arithmetic on random operands
+    fn check_arithmetic<P: WidePrimeFieldParams<N>, const N: usize>() {
+        let p = biguint(&P::MODULUS);
+        let mut rng = StdRng::seed_from_u64(3);
+        for _ in 0..50 {
+            let a = WideFieldElement::<P, N>::random(&mut rng);
+            let b = WideFieldElement::<P, N>::random(&mut rng);
+            let (x, y) = (biguint(&a.num()), biguint(&b.num()));
+            assert!(x < p && y < p);
+
+            assert_eq!(biguint(&(a + b).num()), (&x + &y) % &p);
+            assert_eq!(biguint(&(a - b).num()), (&x + &p - &y) % &p);
+            assert_eq!(biguint(&(a * b).num()), (&x * &y) % &p);
+            assert_eq!(biguint(&(-a).num()), (&p - &x) % &p);
+            assert_eq!(a * a.inv().unwrap(), WideFieldElement::ONE);
+            assert_eq!(a / b * b, a);
+        }
+        assert_eq!(-WideFieldElement::<P, N>::ZERO, WideFieldElement::ZERO);
+        assert_eq!(WideFieldElement::<P, N>::ZERO.inv(), None);
+        assert_eq!(
+            WideFieldElement::<P, N>::from_u64(3).pow(&[4]),
+            WideFieldElement::from_u64(81)
+        );
+    }
+
+    #[test]
+    fn arithmetic_matches_biguint() {
+        check_arithmetic::<Bls12381Fq, 6>();
+        check_arithmetic::<P384, 6>();
+        check_arithmetic::<P521, 9>();
+        check_arithmetic::<LowLimbOne, 6>();
+    }
+
+    /// The generators of BLS12-381 G1, P-384 and P-521 are on their curves
+    #[test]
+    fn curve_generators() {
+        let x = Bls::from_hex_str("17f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb").unwrap();
+        let y = Bls::from_hex_str("08b3f481e3aaa0f1a09e30ed741d8ae4fcf5e095d5d00af600db18cb2c04b3edd03cc744a2888ae40caa232946c5e7e1").unwrap();
+        assert_eq!(y.square(), x.square() * x + Bls::from_u64(4));
+
+        type F384 = Fp384<P384>;
+        let x = F384::from_hex_str("aa87ca22be8b05378eb1c71ef320ad746e1d3b628ba79b9859f741e082542a385502f25dbf55296c3a545e3872760ab7").unwrap();
+        let y = F384::from_hex_str("3617de4a96262c6f5d9e98bf9292dc29f8f41dbd289a147ce9da3113b5f0b8c00a60b1ce1d7e819d7a431d7c90ea0e5f").unwrap();
+        let b = F384::from_hex_str("b3312fa7e23ee7e4988e056be3f82d19181d9c6efe8141120314088f5013875ac656398d8a2ed19d2a85c8edd3ec2aef").unwrap();
+        assert_eq!(y.square(), x.square() * x - x.double() - x + b);
+
+        type F521 = WideFieldElement<P521, 9>;
+        let x = F521::from_hex_str("00c6858e06b70404e9cd9e3ecb662395b4429c648139053fb521f828af606b4d3dbaa14b5e77efe75928fe1dc127a2ffa8de3348b3c1856a429bf97e7e31c2e5bd66").unwrap();
+        let y = F521::from_hex_str("011839296a789a3bc0045c8a5fb42c7d1bd998f54449579b446817afbd17273e662c97ee72995ef42640c550b9013fad0761353c7086a272c24088be94769fd16650").unwrap();
+        let b = F521::from_hex_str("0051953eb9618e1c9a1f929a21a0b68540eea2da725b99b315f3b8b489918ef109e156193951ec7e937b1652c0bd3bb1bf073573df883d2c34f1ef451fd46b503f00").unwrap();
+        assert_eq!(y.square(), x.square() * x - x.double() - x + b);
+    }
+
+    #[test]
+    fn bytes_and_strings() {
+        let a = Bls::from_dec_str("123456789012345678901234567890").unwrap();
+        assert_eq!(a, Bls::from_hex_str("0x18ee90ff6c373e0ee4e3f0ad2").unwrap());
+
+        let be = a.to_bytes_be();
+        assert_eq!(be.len(), 48);
+        assert_eq!(
+            be[35..],
+            [0x1, 0x8e, 0xe9, 0x0f, 0xf6, 0xc3, 0x73, 0xe0, 0xee, 0x4e, 0x3f, 0x0a, 0xd2]
+        );
+        assert_eq!(Bls::from_bytes_be(&be), Ok(a));
+        assert_eq!(Bls::from_bytes_le(&a.to_bytes_le()), Ok(a));
+        assert_eq!(Bls::from_bytes(&a.to_bytes()), Some(a));
+
+        let p: Vec<u8> = Bls12381Fq::MODULUS
+            .iter()
+            .rev()
+            .flat_map(|l| l.to_be_bytes())
+            .collect();
+        assert_eq!(Bls::from_bytes_be(&p), Err(Error::NonCanonical));
+        assert_eq!(Bls::from_bytes_be(&p[1..]), Err(Error::InvalidLength));
+
+        // reduced mod p, like `new`
+        let p_plus_1 = limbs_from_hex::<6>("1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffaaac");
+        assert_eq!(Bls::new(p_plus_1), Bls::ONE);
+        for bad in ["", "0x", "+1", "xyz", "1".repeat(97).as_str()] {
+            assert_eq!(Bls::from_hex_str(bad), Err(Error::InvalidString));
+        }
+        // 2^384 overflows six limbs
+        let two_384 = "39402006196394479212279040100143613805079739270465446667948293404245721771497210611414266254884915640806627990306816";
+        assert_eq!(Bls::from_dec_str(two_384), Err(Error::InvalidString));
+        assert_eq!(Bls::from_dec_str("-1"), Err(Error::InvalidString));
+    }
+
+    #[test]
+    fn prime_field_defaults() {
+        // p = 3 mod 4 for all three
+        let mut rng = StdRng::seed_from_u64(4);
+        let a = Fp384::<P384>::random(&mut rng);
+        let root = a.square().sqrt().unwrap();
+        assert!(root == a || root == -a);
+        assert_eq!(Fp384::<P384>::NUM_BITS, 384);
+        assert_eq!(Bls::NUM_BITS, 381);
+        assert_eq!(WideFieldElement::<P521, 9>::NUM_BITS, 521);
+        assert_eq!(Bls::two_adicity(), 1);
+        assert_eq!(Bls::root_of_unity(2), Ok(-Bls::ONE));
+    }
+}
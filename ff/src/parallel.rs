@@ -0,0 +1,132 @@
+//! Slice-level work split over rayon's thread pool with the `parallel`
+//! feature, and run on the calling thread without it.
+//!
+//! Chunk boundaries depend only on the input length, never on the number of
+//! threads, and every chunk goes through the same serial code, so results are
+//! bit-identical to a serial run. Inputs shorter than `PARALLEL_THRESHOLD`
+//! are not split at all.
+use alloc::vec::Vec;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Inputs shorter than this stay on the calling thread
+pub const PARALLEL_THRESHOLD: usize = 1 << 12;
+
+/// Elements per chunk once an input is split
+pub const CHUNK_LEN: usize = 1 << 10;
+
+/// `Send + Sync` with the `parallel` feature and no bound at all without it,
+/// so that callers have the same signatures either way
+#[cfg(feature = "parallel")]
+pub trait MaybeSync: Send + Sync {}
+#[cfg(feature = "parallel")]
+impl<T: Send + Sync> MaybeSync for T {}
+
+#[cfg(not(feature = "parallel"))]
+pub trait MaybeSync {}
+#[cfg(not(feature = "parallel"))]
+impl<T> MaybeSync for T {}
+
+/// Calls `f` on consecutive chunks of `CHUNK_LEN` elements of `v`, or once on
+/// the whole of `v` when it is not split
+pub fn for_each_chunk_mut<T: MaybeSync>(v: &mut [T], f: impl Fn(&mut [T]) + MaybeSync) {
+    #[cfg(feature = "parallel")]
+    if v.len() >= PARALLEL_THRESHOLD {
+        return v.par_chunks_mut(CHUNK_LEN).for_each(f);
+    }
+    f(v)
+}
+
+/// `for_each_chunk_mut` over `a` with the matching chunks of `b`. Panics if
+/// the lengths differ.
+pub fn for_each_chunk_zip_mut<T: MaybeSync, U: MaybeSync>(
+    a: &mut [T],
+    b: &[U],
+    f: impl Fn(&mut [T], &[U]) + MaybeSync,
+) {
+    assert_eq!(a.len(), b.len(), "slices have different lengths");
+    #[cfg(feature = "parallel")]
+    if a.len() >= PARALLEL_THRESHOLD {
+        return a
+            .par_chunks_mut(CHUNK_LEN)
+            .zip(b.par_chunks(CHUNK_LEN))
+            .for_each(|(x, y)| f(x, y));
+    }
+    f(a, b)
+}
+
+/// `v.iter().map(f).collect()`, chunked like `for_each_chunk_mut`
+pub fn map<T: MaybeSync, U: MaybeSync>(v: &[T], f: impl Fn(&T) -> U + MaybeSync) -> Vec<U> {
+    #[cfg(feature = "parallel")]
+    if v.len() >= PARALLEL_THRESHOLD {
+        return v
+            .par_chunks(CHUNK_LEN)
+            .flat_map_iter(|chunk| chunk.iter().map(&f))
+            .collect();
+    }
+    v.iter().map(f).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::p_small::BabyBearField;
+    use crate::{batch_inverse, Field, PrimeField};
+
+    const LENGTHS: [usize; 5] = [
+        0,
+        PARALLEL_THRESHOLD - 1,
+        PARALLEL_THRESHOLD,
+        PARALLEL_THRESHOLD + CHUNK_LEN + 1,
+        3 * PARALLEL_THRESHOLD + 7,
+    ];
+
+    fn elems(len: usize) -> Vec<BabyBearField> {
+        (0..len as u64)
+            .map(|i| BabyBearField::from_u64(i * i + 3 * i))
+            .collect()
+    }
+
+    #[test]
+    fn helpers_match_serial() {
+        for len in LENGTHS {
+            let v: Vec<u64> = (0..len as u64).collect();
+
+            let mut doubled = v.clone();
+            for_each_chunk_mut(&mut doubled, |c| c.iter_mut().for_each(|x| *x *= 2));
+            assert_eq!(doubled, v.iter().map(|x| x * 2).collect::<Vec<_>>());
+
+            let mut sum = v.clone();
+            for_each_chunk_zip_mut(&mut sum, &doubled, |a, b| {
+                a.iter_mut().zip(b).for_each(|(x, y)| *x += y)
+            });
+            assert_eq!(sum, v.iter().map(|x| x * 3).collect::<Vec<_>>());
+
+            assert_eq!(
+                map(&v, |x| x + 1),
+                v.iter().map(|x| x + 1).collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[test]
+    fn batch_operations_match_serial() {
+        for len in LENGTHS {
+            let a = elems(len);
+            let b: Vec<BabyBearField> = a.iter().map(|x| x.double() + BabyBearField::ONE).collect();
+
+            let mut inverted = a.clone();
+            batch_inverse(&mut inverted);
+            let expected: Vec<BabyBearField> = a
+                .iter()
+                .map(|x| x.inv().unwrap_or(BabyBearField::ZERO))
+                .collect();
+            assert_eq!(inverted, expected);
+
+            let mut prod = a.clone();
+            BabyBearField::batch_mul_assign(&mut prod, &b);
+            let expected: Vec<BabyBearField> = a.iter().zip(&b).map(|(x, y)| *x * y).collect();
+            assert_eq!(prod, expected);
+        }
+    }
+}
@@ -0,0 +1,226 @@
+use alloc::vec::Vec;
+use num_bigint::prime::probably_prime;
+use num_bigint::BigUint;
+use primitive_types::U256;
+
+use crate::errors::{Error, Result};
+
+/// Miller-Rabin rounds of `check`, on top of its Lucas test
+pub(crate) const PRIMALITY_ROUNDS: usize = 20;
+
+/// Compile-time description of a prime field GF(MODULUS).
+///
+/// Field elements are generic over an implementor of this trait, so elements
+/// of different fields are different types and mixing them does not compile.
+/// Implementors are meant to be empty marker structs:
+///
+/// ```text
+/// #[derive(Debug)]
+/// pub struct Secp256k1Base;
+/// impl PrimeFieldParams for Secp256k1Base {
+///     const MODULUS: U256 = u256_from_hex("FFFF...FC2F");
+/// }
+/// ```
+///
+/// Everything derived from the modulus (Montgomery constants etc.) is
+/// computed at compile time by the element types.
+///
+/// **`MODULUS` must be prime.** With a composite modulus the ring operations
+/// still run, but `inv`, `sqrt`, `find_generator` and everything built on
+/// them return wrong results. The element types reject a composite modulus
+/// at compile time with a Miller-Rabin test, which is exact below 2^64 and
+/// uses base 2 only above that. Call `check`, which is stronger, in a unit
+/// test next to every implementation, as is done for all the parameters
+/// shipped with this crate.
+pub trait PrimeFieldParams: 'static + Send + Sync + core::fmt::Debug {
+    const MODULUS: U256;
+
+    /// A generator of the multiplicative group GF(p)*, if known. Roots of
+    /// unity are then powers of it; without one they are found by search.
+    const MULTIPLICATIVE_GENERATOR: Option<U256> = None;
+
+    /// The full test behind the compile-time one, to be run once per field
+    /// next to its definition (e.g. in a unit test). Baillie-PSW plus
+    /// Miller-Rabin rounds, `Error::NotPrime` if it fails.
+    fn check() -> Result<()> {
+        let mut bytes = [0u8; 32];
+        Self::MODULUS.to_big_endian(&mut bytes);
+
+        match probably_prime(&BigUint::from_bytes_be(&bytes), PRIMALITY_ROUNDS) {
+            true => Ok(()),
+            false => Err(Error::NotPrime),
+        }
+    }
+}
+
+/// Marks a prime of the pseudo-Mersenne form p = 2^256 - c with c < 2^64,
+/// e.g. secp256k1's 2^256 - 2^32 - 977, so that `PseudoMersenneFieldElement`
+/// can be used for it. The form is checked at compile time.
+pub trait PseudoMersenneParams: PrimeFieldParams {}
+
+/// A prime below 2^64 with a dedicated reduction of 128-bit products, for
+/// `SmallFieldElement`
+///
+/// The default `reduce` is a Barrett reduction for p < 2^32 and two
+/// Montgomery reductions above that, where x no longer fits the Barrett
+/// product. Primes of a special form override it, see
+/// `p_small::reduce_goldilocks` and `p_small::reduce_mersenne31`.
+pub trait SmallPrimeParams: PrimeFieldParams {
+    /// floor(2^64 / p), used by the default `reduce` for p < 2^32
+    const BARRETT_FACTOR: u64 = u64::MAX / Self::MODULUS.0[0];
+
+    /// -p^-1 mod 2^64, used by the default `reduce` for p > 2^32
+    const MONT_INV: u64 = crate::limbs::mont_inv(Self::MODULUS.0[0]);
+
+    /// 2^128 mod p, used by the default `reduce` for p > 2^32
+    const MONT_R2: u64 = crate::p_u64::mont_r2_u64(Self::MODULUS.0[0]);
+
+    /// x mod p for any x < p^2, branch-free. The branch is on the size of
+    /// p, which is fixed per type.
+    fn reduce(x: u128) -> u64 {
+        let p = Self::MODULUS.0[0];
+        match p >> 32 {
+            0 => crate::p_small::reduce_barrett(x as u64, p, Self::BARRETT_FACTOR),
+            _ => crate::p_small::reduce_montgomery(x, p, Self::MONT_INV, Self::MONT_R2),
+        }
+    }
+}
+
+/// Compile-time description of a binary field GF(2^DEGREE) = GF(2)[x] / f(x)
+///
+/// Polynomials over GF(2) are bit vectors, bit i holding the coefficient of
+/// x^i. f(x) has degree `DEGREE` <= 128, so its leading term does not fit in
+/// a u128 and `MODULUS` holds the rest of it, f(x) - x^DEGREE:
+///
+/// ```text
+/// // AES, x^8 + x^4 + x^3 + x + 1
+/// const DEGREE: u32 = 8;
+/// const MODULUS: u128 = 0x1b;
+/// ```
+pub trait BinaryFieldParams: 'static + Send + Sync + core::fmt::Debug {
+    const DEGREE: u32;
+    const MODULUS: u128;
+
+    /// Rabin's irreducibility test on f, the counterpart of
+    /// `PrimeFieldParams::check`
+    ///
+    /// f of degree n is irreducible iff x^(2^n) = x (mod f) and
+    /// gcd(x^(2^(n/q)) - x, f) = 1 for every prime q dividing n.
+    fn check() -> Result<()>
+    where
+        Self: Sized,
+    {
+        match crate::gf2n::is_irreducible::<Self>() {
+            true => Ok(()),
+            false => Err(Error::NotIrreducible),
+        }
+    }
+}
+
+/// Compile-time description of a prime field whose modulus takes `N` u64
+/// limbs, for primes beyond 256 bits (BLS12-381's base field, P-384, P-521)
+///
+/// Same contract as `PrimeFieldParams`, a prime `MODULUS` included, with the
+/// modulus as little-endian limbs. Its top limb may be anything non-zero,
+/// the Montgomery arithmetic of `WideFieldElement` only needs p < 2^(64 N).
+pub trait WidePrimeFieldParams<const N: usize>: 'static + Send + Sync + core::fmt::Debug {
+    const MODULUS: [u64; N];
+
+    /// See `PrimeFieldParams::MULTIPLICATIVE_GENERATOR`
+    const MULTIPLICATIVE_GENERATOR: Option<[u64; N]> = None;
+
+    /// See `PrimeFieldParams::check`
+    fn check() -> Result<()> {
+        let digits: Vec<u32> = Self::MODULUS
+            .iter()
+            .flat_map(|limb| [*limb as u32, (limb >> 32) as u32])
+            .collect();
+
+        match probably_prime(&BigUint::new(digits), PRIMALITY_ROUNDS) {
+            true => Ok(()),
+            false => Err(Error::NotPrime),
+        }
+    }
+}
+
+/// Parses a hex literal, with or without `0x`, in const context.
+/// Meant for `PrimeFieldParams::MODULUS` and similar constants; panics (at
+/// compile time when used in a const) on a malformed or too long literal.
+pub const fn u256_from_hex(s: &str) -> U256 {
+    U256(limbs_from_hex(s))
+}
+
+/// `u256_from_hex` for any number of little-endian limbs, for
+/// `WidePrimeFieldParams::MODULUS`
+pub const fn limbs_from_hex<const N: usize>(s: &str) -> [u64; N] {
+    let bytes = s.as_bytes();
+    let mut i = 0;
+    if bytes.len() >= 2 && bytes[0] == b'0' && (bytes[1] == b'x' || bytes[1] == b'X') {
+        i = 2;
+    }
+    assert!(
+        bytes.len() - i <= 16 * N,
+        "hex literal does not fit in the limbs"
+    );
+
+    let mut limbs = [0u64; N];
+    while i < bytes.len() {
+        let digit = match bytes[i] {
+            b'0'..=b'9' => bytes[i] - b'0',
+            b'a'..=b'f' => bytes[i] - b'a' + 10,
+            b'A'..=b'F' => bytes[i] - b'A' + 10,
+            _ => panic!("invalid hex digit"),
+        };
+        // limbs <<= 4
+        let mut j = N - 1;
+        while j > 0 {
+            limbs[j] = (limbs[j] << 4) | (limbs[j - 1] >> 60);
+            j -= 1;
+        }
+        limbs[0] = (limbs[0] << 4) | digit as u64;
+        i += 1;
+    }
+
+    limbs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::str::FromStr;
+
+    test_prime_field!(P21, U256([21, 0, 0, 0]));
+    test_prime_field!(P23, U256([23, 0, 0, 0]));
+    test_prime_field!(P561, U256([561, 0, 0, 0]));
+
+    #[test]
+    fn check_not_a_prime() {
+        assert_eq!(P21::check().unwrap_err(), Error::NotPrime);
+        // a Carmichael number, which fools the Fermat test for every base
+        assert_eq!(P561::check().unwrap_err(), Error::NotPrime);
+        assert!(P23::check().is_ok());
+    }
+
+    #[test]
+    fn hex_matches_from_str() {
+        for s in [
+            "0x0",
+            "0xB",
+            "f9cd",
+            "0xFFFFFFFFFFFFFFC5",
+            "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F",
+        ] {
+            assert_eq!(u256_from_hex(s), U256::from_str(s).unwrap());
+        }
+    }
+
+    #[test]
+    fn hex_to_wide_limbs() {
+        assert_eq!(limbs_from_hex::<1>("0xff"), [0xff]);
+        assert_eq!(
+            limbs_from_hex::<3>("100000000000000020000000000000003"),
+            [3, 2, 1]
+        );
+        assert_eq!(limbs_from_hex::<9>("1ff"), [0x1ff, 0, 0, 0, 0, 0, 0, 0, 0]);
+    }
+}
@@ -0,0 +1,116 @@
+//! Parameters of popular NTT-friendly primes, i.e. primes where p - 1 has a
+//! large power of two factor, along with a generator of GF(p)*
+//!
+//! ```text
+//! prime           p                              2-adicity  generator
+//! Goldilocks      2^64 - 2^32 + 1                32         7
+//! BabyBear        2^31 - 2^27 + 1                27         31
+//! Mersenne-31     2^31 - 1                       1          7
+//! BLS12-381 Fr    0x73eda753...ffffffff00000001  32         7
+//! ```
+//!
+//! BN254 Fr, with 2-adicity 28 and generator 5, lives in `bn254`. Mersenne-31
+//! is not NTT-friendly but has the cheapest reduction of all. The three
+//! small primes implement `SmallPrimeParams`, see `p_small`.
+//!
+//! Below them the moduli too wide for `U256FieldElement`, for use with
+//! `WideFieldElement`.
+use crate::p_small::{reduce_goldilocks, reduce_mersenne31};
+use crate::params::{
+    limbs_from_hex, u256_from_hex, PrimeFieldParams, SmallPrimeParams, WidePrimeFieldParams,
+};
+use primitive_types::U256;
+
+#[derive(Debug)]
+pub struct Goldilocks;
+impl PrimeFieldParams for Goldilocks {
+    const MODULUS: U256 = U256([0xffff_ffff_0000_0001, 0, 0, 0]);
+    const MULTIPLICATIVE_GENERATOR: Option<U256> = Some(U256([7, 0, 0, 0]));
+}
+impl SmallPrimeParams for Goldilocks {
+    fn reduce(x: u128) -> u64 {
+        reduce_goldilocks(x)
+    }
+}
+
+#[derive(Debug)]
+pub struct BabyBear;
+impl PrimeFieldParams for BabyBear {
+    const MODULUS: U256 = U256([0x7800_0001, 0, 0, 0]);
+    const MULTIPLICATIVE_GENERATOR: Option<U256> = Some(U256([31, 0, 0, 0]));
+}
+impl SmallPrimeParams for BabyBear {}
+
+#[derive(Debug)]
+pub struct Mersenne31;
+impl PrimeFieldParams for Mersenne31 {
+    const MODULUS: U256 = U256([0x7fff_ffff, 0, 0, 0]);
+    const MULTIPLICATIVE_GENERATOR: Option<U256> = Some(U256([7, 0, 0, 0]));
+}
+impl SmallPrimeParams for Mersenne31 {
+    fn reduce(x: u128) -> u64 {
+        reduce_mersenne31(x)
+    }
+}
+
+/// Scalar field of BLS12-381
+#[derive(Debug)]
+pub struct Bls12381Fr;
+impl PrimeFieldParams for Bls12381Fr {
+    const MODULUS: U256 =
+        u256_from_hex("73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001");
+    const MULTIPLICATIVE_GENERATOR: Option<U256> = Some(U256([7, 0, 0, 0]));
+}
+
+/// Base field of BLS12-381, 381 bits
+#[derive(Debug)]
+pub struct Bls12381Fq;
+impl WidePrimeFieldParams<6> for Bls12381Fq {
+    const MODULUS: [u64; 6] = limbs_from_hex(
+        "1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffaaab",
+    );
+}
+
+/// Base field of NIST P-384, 2^384 - 2^128 - 2^96 + 2^32 - 1
+#[derive(Debug)]
+pub struct P384;
+impl WidePrimeFieldParams<6> for P384 {
+    const MODULUS: [u64; 6] = [
+        0x0000_0000_ffff_ffff,
+        0xffff_ffff_0000_0000,
+        0xffff_ffff_ffff_fffe,
+        u64::MAX,
+        u64::MAX,
+        u64::MAX,
+    ];
+}
+
+/// Base field of NIST P-521, the Mersenne prime 2^521 - 1
+#[derive(Debug)]
+pub struct P521;
+impl WidePrimeFieldParams<9> for P521 {
+    const MODULUS: [u64; 9] = [
+        u64::MAX,
+        u64::MAX,
+        u64::MAX,
+        u64::MAX,
+        u64::MAX,
+        u64::MAX,
+        u64::MAX,
+        u64::MAX,
+        0x1ff,
+    ];
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn params_are_prime() {
+        Goldilocks::check().unwrap();
+        BabyBear::check().unwrap();
+        Mersenne31::check().unwrap();
+        Bls12381Fr::check().unwrap();
+    }
+}
@@ -0,0 +1,205 @@
+//! Multiplicative structure of GF(p) for FFTs: the 2-adicity of p - 1 and
+//! roots of unity, shared by every `PrimeField` through the trait's default
+//! methods.
+//!
+//! With a generator g of GF(p)* (from the field parameters) a primitive n-th
+//! root of unity is simply g^((p-1)/n). Without one, any quadratic
+//! non-residue z gives the primitive 2^s-th root z^t, where p - 1 = 2^s * t,
+//! and roots of other orders are found by trying small elements.
+use crate::errors::{Error, Result};
+use crate::factor::prime_factors;
+use crate::field::PrimeField;
+use crate::sqrt::{shr, trailing_zeros};
+use alloc::{vec, vec::Vec};
+
+pub(crate) fn two_adicity<F: PrimeField>() -> u32 {
+    trailing_zeros(&p_minus_1::<F>()) as u32
+}
+
+pub(crate) fn two_adic_root_of_unity<F: PrimeField>() -> F {
+    let s = two_adicity::<F>();
+    // GF(2)
+    if s == 0 {
+        return F::one();
+    }
+    let t = shr(&p_minus_1::<F>(), s as usize);
+    let z = F::multiplicative_generator().unwrap_or_else(|| {
+        let mut z = F::from_u64(2);
+        while z.legendre() != -1 {
+            z = z + F::one();
+        }
+        z
+    });
+    z.pow(&t)
+}
+
+pub(crate) fn root_of_unity<F: PrimeField>(n: u64) -> Result<F> {
+    if n == 0 {
+        return Err(Error::NoRootOfUnity);
+    }
+    let (cofactor, rem) = div_rem(&p_minus_1::<F>(), n);
+    if rem != 0 {
+        return Err(Error::NoRootOfUnity);
+    }
+    if n == 1 {
+        return Ok(F::one());
+    }
+
+    // Squaring down from the largest two-adic root keeps the powers of two
+    // consistent with each other, with or without a generator
+    if n.is_power_of_two() {
+        let mut root = two_adic_root_of_unity::<F>();
+        for _ in n.trailing_zeros()..two_adicity::<F>() {
+            root = root.square();
+        }
+        return Ok(root);
+    }
+
+    if let Some(g) = F::multiplicative_generator() {
+        return Ok(g.pow(&cofactor));
+    }
+
+    // x^((p-1)/n) has order dividing n, and exactly n unless some
+    // x^((p-1)/q) = 1 for a prime q | n. At least phi(n)/n of all x work.
+    let factors = prime_factors(n);
+    let mut x = F::from_u64(2);
+    loop {
+        let root = x.pow(&cofactor);
+        if factors.iter().all(|q| root.pow(&[n / q]) != F::one()) {
+            return Ok(root);
+        }
+        x = x + F::one();
+    }
+}
+
+fn p_minus_1<F: PrimeField>() -> Vec<u64> {
+    let mut p = F::characteristic();
+    // p is odd or 2, so no borrow
+    p[0] -= 1;
+    p
+}
+
+/// Little-endian limbs divided by a single limb, schoolbook
+fn div_rem(limbs: &[u64], d: u64) -> (Vec<u64>, u64) {
+    let mut quot = vec![0u64; limbs.len()];
+    let mut rem = 0u128;
+    for i in (0..limbs.len()).rev() {
+        let cur = (rem << 64) | limbs[i] as u128;
+        quot[i] = (cur / d as u128) as u64;
+        rem = cur % d as u128;
+    }
+    (quot, rem as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bn254::Bn254Fr;
+    use crate::field::Field;
+    use crate::p_u256::U256FieldElement;
+    use crate::p_u64::U64FieldElement;
+    use crate::params::PrimeFieldParams;
+    use crate::primes::{BabyBear, Bls12381Fr, Goldilocks};
+    use core::str::FromStr;
+    use primitive_types::U256;
+
+    test_prime_field!(P2, U256([2, 0, 0, 0]));
+    test_prime_field!(P97, U256([97, 0, 0, 0]));
+    test_prime_field!(P193, U256([193, 0, 0, 0]));
+
+    /// Whether x has multiplicative order exactly n
+    fn has_order<F: PrimeField>(x: &F, n: u64) -> bool {
+        x.pow(&[n]) == F::one() && prime_factors(n).iter().all(|q| x.pow(&[n / q]) != F::one())
+    }
+
+    /// Primitive roots for every divisor of p - 1 among `orders`, errors for
+    /// the rest, and nested powers of two
+    fn check_roots<F: PrimeField>(orders: impl Iterator<Item = u64>) {
+        let p_minus_1 = p_minus_1::<F>();
+        for n in orders {
+            match div_rem(&p_minus_1, n).1 {
+                0 => assert!(has_order(&F::root_of_unity(n).unwrap(), n), "{}", n),
+                _ => assert_eq!(F::root_of_unity(n), Err(Error::NoRootOfUnity), "{}", n),
+            }
+        }
+        assert_eq!(F::root_of_unity(0), Err(Error::NoRootOfUnity));
+        assert_eq!(F::root_of_unity(1), Ok(F::one()));
+
+        let s = F::two_adicity();
+        let mut root = F::two_adic_root_of_unity();
+        if s < 64 {
+            assert_eq!(F::root_of_unity(1 << s), Ok(root.clone()));
+        }
+        for k in (0..s.min(63)).rev() {
+            root = root.square();
+            assert_eq!(F::root_of_unity(1 << k), Ok(root.clone()));
+        }
+        if s < 63 {
+            assert_eq!(F::root_of_unity(1 << (s + 1)), Err(Error::NoRootOfUnity));
+        }
+    }
+
+    #[test]
+    fn small_fields_without_generator() {
+        // 96 = 2^5 * 3, 192 = 2^6 * 3
+        check_roots::<U256FieldElement<P97>>(1..200);
+        check_roots::<U64FieldElement<P97>>(1..200);
+        check_roots::<U256FieldElement<P193>>(1..200);
+        check_roots::<U64FieldElement<P193>>(1..200);
+        assert_eq!(U256FieldElement::<P97>::two_adicity(), 5);
+        assert_eq!(U256FieldElement::<P97>::multiplicative_generator(), None);
+
+        assert_eq!(U64FieldElement::<P2>::two_adicity(), 0);
+        assert_eq!(
+            U64FieldElement::<P2>::root_of_unity(1),
+            Ok(U64FieldElement::one())
+        );
+        assert_eq!(
+            U64FieldElement::<P2>::root_of_unity(2),
+            Err(Error::NoRootOfUnity)
+        );
+    }
+
+    /// The two-adic roots g^t match the constants other libraries ship
+    fn check_two_adic_root<P: PrimeFieldParams>(s: u32, root: &str) {
+        type F<P> = U256FieldElement<P>;
+        assert_eq!(F::<P>::two_adicity(), s);
+        assert_eq!(
+            F::<P>::two_adic_root_of_unity().num(),
+            U256::from_str(root).unwrap()
+        );
+        // a generator is in particular a non-residue
+        assert_eq!(F::<P>::multiplicative_generator().unwrap().legendre(), -1);
+    }
+
+    #[test]
+    fn popular_primes() {
+        check_two_adic_root::<Goldilocks>(32, "185629dcda58878c");
+        check_two_adic_root::<BabyBear>(27, "1a427a41");
+        check_two_adic_root::<Bn254Fr>(
+            28,
+            "2a3c09f0a58a7e8500e0a7eb8ef62abc402d111e41112ed49bd61b6e725b19f0",
+        );
+        check_two_adic_root::<Bls12381Fr>(
+            32,
+            "16a2a19edfe81f20d09b681922c813b4b63683508c2280b93829971f439f0d2b",
+        );
+
+        // Goldilocks: p - 1 = 2^32 * 3 * 5 * 17 * 257 * 65537
+        check_roots::<U64FieldElement<Goldilocks>>([3, 5, 6, 7, 255, 65537, 1 << 20].into_iter());
+        // BabyBear: p - 1 = 2^27 * 3 * 5
+        check_roots::<U64FieldElement<BabyBear>>((1..50).chain([15 << 27, 7 << 27]));
+        check_roots::<U256FieldElement<Bn254Fr>>([3, 9, 11, 13, 1 << 28].into_iter());
+
+        // the U64 and U256 types agree
+        assert_eq!(
+            U64FieldElement::<Goldilocks>::root_of_unity(3 << 10)
+                .unwrap()
+                .num(),
+            U256FieldElement::<Goldilocks>::root_of_unity(3 << 10)
+                .unwrap()
+                .num()
+                .low_u64()
+        );
+    }
+}
@@ -0,0 +1,93 @@
+//! A wrapper for secret values such as private scalars, wiped from memory on
+//! drop and kept out of `Debug` output.
+//!
+//! `Secret` is deliberately not `Clone`: copies have to be asked for with
+//! `clone_secret`. Note that field elements themselves are `Copy`, so the
+//! arithmetic on an exposed value can still leave copies on the stack; only
+//! the wrapped value is guaranteed to be zeroed.
+use core::fmt;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+pub struct Secret<T: Zeroize>(T);
+
+impl<T: Zeroize> Secret<T> {
+    pub fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    pub fn expose_secret(&self) -> &T {
+        &self.0
+    }
+
+    /// An explicit copy, wiped on drop like the original
+    pub fn clone_secret(&self) -> Self
+    where
+        T: Clone,
+    {
+        Self(self.0.clone())
+    }
+}
+
+impl<T: Zeroize> From<T> for Secret<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T: Zeroize> fmt::Debug for Secret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Secret(<redacted>)")
+    }
+}
+
+impl<T: Zeroize> Zeroize for Secret<T> {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl<T: Zeroize> Drop for Secret<T> {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl<T: Zeroize> ZeroizeOnDrop for Secret<T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bn254::{Bn254Fq12, Bn254Fr};
+    use crate::p_small::BabyBearField;
+    use crate::p_u256::U256FieldElement;
+    use crate::{Field, Fp12, PrimeField};
+    use alloc::format;
+
+    fn check_zeroize<F: Field + Zeroize>(mut x: F) {
+        assert!(!x.is_zero());
+        x.zeroize();
+        assert!(x.is_zero());
+    }
+
+    #[test]
+    fn field_elements_zeroize() {
+        check_zeroize(U256FieldElement::<Bn254Fr>::from_u64(42));
+        check_zeroize(BabyBearField::from_u64(42));
+        check_zeroize(Fp12::<Bn254Fq12>::one());
+    }
+
+    #[test]
+    fn secret() {
+        type F = U256FieldElement<Bn254Fr>;
+        let x = F::from_u64(0xdeadbeef);
+
+        let mut secret = Secret::new(x);
+        assert_eq!(format!("{:?}", secret), "Secret(<redacted>)");
+        assert_eq!(*secret.expose_secret(), x);
+
+        let copy = secret.clone_secret();
+        secret.zeroize();
+        assert!(secret.expose_secret().is_zero());
+        assert_eq!(*copy.expose_secret(), x);
+    }
+}
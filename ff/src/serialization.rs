@@ -0,0 +1,236 @@
+//! `serde` support for the prime field elements, behind the `serde` feature.
+//!
+//! Human-readable formats (JSON, TOML, ...) get a fixed-width `0x`-prefixed
+//! big-endian hex string, binary formats the raw `to_bytes_be` bytes.
+//! Deserialization is strict: the width must match and the value must be
+//! below p.
+use alloc::format;
+use alloc::string::String;
+use alloc::{vec, vec::Vec};
+use core::fmt;
+use core::marker::PhantomData;
+
+use serde::de::{self, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::field::Field;
+use crate::gf2n::GF2n;
+use crate::p_pseudo_mersenne::PseudoMersenneFieldElement;
+use crate::p_small::SmallFieldElement;
+use crate::p_u256::U256FieldElement;
+use crate::p_u64::U64FieldElement;
+use crate::p_wide::WideFieldElement;
+use crate::params::{
+    BinaryFieldParams, PrimeFieldParams, PseudoMersenneParams, SmallPrimeParams,
+    WidePrimeFieldParams,
+};
+
+fn serialize<F: Field, S: Serializer>(elem: &F, serializer: S) -> Result<S::Ok, S::Error> {
+    let bytes = elem.to_bytes();
+    match serializer.is_human_readable() {
+        true => serializer.serialize_str(&to_hex(&bytes)),
+        false => serializer.serialize_bytes(&bytes),
+    }
+}
+
+fn deserialize<'de, F: Field, D: Deserializer<'de>>(deserializer: D) -> Result<F, D::Error> {
+    match deserializer.is_human_readable() {
+        true => deserializer.deserialize_str(ElementVisitor(PhantomData)),
+        false => deserializer.deserialize_bytes(ElementVisitor(PhantomData)),
+    }
+}
+
+struct ElementVisitor<F>(PhantomData<F>);
+
+impl<F: Field> ElementVisitor<F> {
+    fn decode<E: de::Error>(bytes: &[u8]) -> Result<F, E> {
+        F::from_bytes(bytes).ok_or_else(|| E::custom("wrong length or value not below p"))
+    }
+}
+
+impl<'de, F: Field> Visitor<'de> for ElementVisitor<F> {
+    type Value = F;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a canonical field element as hex string or bytes")
+    }
+
+    fn visit_str<E: de::Error>(self, s: &str) -> Result<F, E> {
+        let digits = s.strip_prefix("0x").unwrap_or(s);
+        let bytes = from_hex(digits).ok_or_else(|| E::custom("invalid hex string"))?;
+        Self::decode(&bytes)
+    }
+
+    fn visit_bytes<E: de::Error>(self, bytes: &[u8]) -> Result<F, E> {
+        Self::decode(bytes)
+    }
+
+    /// Some binary formats hand byte strings over as sequences
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<F, A::Error> {
+        let mut bytes = vec![];
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        Self::decode(&bytes)
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    let mut s = String::with_capacity(2 + 2 * bytes.len());
+    s.push_str("0x");
+    for byte in bytes {
+        s.push_str(&format!("{:02x}", byte));
+    }
+    s
+}
+
+fn from_hex(digits: &str) -> Option<Vec<u8>> {
+    if !digits.len().is_multiple_of(2) || !digits.is_ascii() {
+        return None;
+    }
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).ok())
+        .collect()
+}
+
+impl<P: PrimeFieldParams> Serialize for U256FieldElement<P> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize(self, serializer)
+    }
+}
+
+impl<'de, P: PrimeFieldParams> Deserialize<'de> for U256FieldElement<P> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize(deserializer)
+    }
+}
+
+impl<P: PrimeFieldParams> Serialize for U64FieldElement<P> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize(self, serializer)
+    }
+}
+
+impl<'de, P: PrimeFieldParams> Deserialize<'de> for U64FieldElement<P> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize(deserializer)
+    }
+}
+
+impl<P: PseudoMersenneParams> Serialize for PseudoMersenneFieldElement<P> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize(self, serializer)
+    }
+}
+
+impl<'de, P: PseudoMersenneParams> Deserialize<'de> for PseudoMersenneFieldElement<P> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize(deserializer)
+    }
+}
+
+impl<P: SmallPrimeParams> Serialize for SmallFieldElement<P> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize(self, serializer)
+    }
+}
+
+impl<'de, P: SmallPrimeParams> Deserialize<'de> for SmallFieldElement<P> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize(deserializer)
+    }
+}
+
+impl<P: BinaryFieldParams> Serialize for GF2n<P> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize(self, serializer)
+    }
+}
+
+impl<'de, P: BinaryFieldParams> Deserialize<'de> for GF2n<P> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize(deserializer)
+    }
+}
+
+impl<P: WidePrimeFieldParams<N>, const N: usize> Serialize for WideFieldElement<P, N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize(self, serializer)
+    }
+}
+
+impl<'de, P: WidePrimeFieldParams<N>, const N: usize> Deserialize<'de> for WideFieldElement<P, N> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize(deserializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::params::u256_from_hex;
+    use primitive_types::U256;
+    use serde_test::{assert_de_tokens_error, assert_tokens, Configure, Token};
+
+    test_prime_field!(P97, U256([97, 0, 0, 0]));
+    test_prime_field!(
+        Secp256k1P,
+        u256_from_hex("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F")
+    );
+
+    #[test]
+    fn u256_tokens() {
+        let a = U256FieldElement::<Secp256k1P>::from_u64(0xdeadbeef);
+        assert_tokens(
+            &a.readable(),
+            &[Token::Str(
+                "0x00000000000000000000000000000000000000000000000000000000deadbeef",
+            )],
+        );
+        let mut bytes = [0u8; 32];
+        bytes[28..].copy_from_slice(&[0xde, 0xad, 0xbe, 0xef]);
+        let bytes: &'static [u8] = Box::leak(Box::new(bytes));
+        assert_tokens(&a.compact(), &[Token::Bytes(bytes)]);
+    }
+
+    #[test]
+    fn u64_tokens() {
+        let a = U64FieldElement::<P97>::new(42);
+        assert_tokens(&a.readable(), &[Token::Str("0x000000000000002a")]);
+        assert_tokens(&a.compact(), &[Token::Bytes(&[0, 0, 0, 0, 0, 0, 0, 42])]);
+    }
+
+    #[test]
+    fn wide_tokens() {
+        let a = crate::Fp384::<crate::primes::P384>::from_u64(0xdeadbeef);
+        let hex = format!("0x{}deadbeef", "0".repeat(88));
+        assert_tokens(
+            &a.readable(),
+            &[Token::Str(Box::leak(hex.into_boxed_str()))],
+        );
+    }
+
+    #[test]
+    fn rejects_non_canonical() {
+        let err = "wrong length or value not below p";
+        // p itself
+        assert_de_tokens_error::<serde_test::Readable<U64FieldElement<P97>>>(
+            &[Token::Str("0x0000000000000061")],
+            err,
+        );
+        // not fixed width
+        assert_de_tokens_error::<serde_test::Readable<U64FieldElement<P97>>>(
+            &[Token::Str("0x2a")],
+            err,
+        );
+        assert_de_tokens_error::<serde_test::Compact<U64FieldElement<P97>>>(
+            &[Token::Bytes(&[42])],
+            err,
+        );
+        assert_de_tokens_error::<serde_test::Readable<U64FieldElement<P97>>>(
+            &[Token::Str("0x00000000000000zz")],
+            "invalid hex string",
+        );
+    }
+}
@@ -0,0 +1,261 @@
+//! Quadratic residuosity and square roots in prime fields, shared by every
+//! `PrimeField` through the trait's default methods.
+//!
+//! All exponents are derived from `characteristic()` with shifts only, so
+//! this works for any limb count.
+use crate::field::PrimeField;
+use alloc::{vec, vec::Vec};
+
+/// (self | p) by Euler's criterion: a^((p-1)/2) is 1 for squares, -1 otherwise
+pub(crate) fn legendre<F: PrimeField>(a: &F) -> i8 {
+    if a.is_zero() {
+        return 0;
+    }
+    let p = F::characteristic();
+    if p[0] == 2 && p[1..].iter().all(|&l| l == 0) {
+        return 1;
+    }
+    match a.pow(&shr(&p, 1)) == F::one() {
+        true => 1,
+        false => -1,
+    }
+}
+
+pub(crate) fn sqrt<F: PrimeField>(a: &F) -> Option<F> {
+    if a.is_zero() {
+        return Some(F::zero());
+    }
+    let p = F::characteristic();
+    let root = match p[0] & 7 {
+        // GF(2), every element is its own root
+        2 => return Some(a.clone()),
+        3 | 7 => sqrt_3_mod_4(a, &p),
+        5 => sqrt_5_mod_8(a, &p),
+        _ => tonelli_shanks(a, &p)?,
+    };
+    // The closed forms above return garbage for non-residues
+    match root.square() == *a {
+        true => Some(root),
+        false => None,
+    }
+}
+
+/// a^((p+1)/4), since (a^((p+1)/4))^2 = a * a^((p-1)/2) = a for a square a
+fn sqrt_3_mod_4<F: PrimeField>(a: &F, p: &[u64]) -> F {
+    a.pow(&add_one(&shr(p, 2)))
+}
+
+/// Atkin's algorithm, one exponentiation
+///
+/// With b = (2a)^((p-5)/8) and i = 2ab^2, i is a square root of -1
+/// and ab(i - 1) a square root of a.
+fn sqrt_5_mod_8<F: PrimeField>(a: &F, p: &[u64]) -> F {
+    let a2 = a.double();
+    let b = a2.pow(&shr(p, 3));
+    let i = a2 * b.square();
+    a.clone() * b * (i - F::one())
+}
+
+/// Tonelli-Shanks for p = 1 mod 8
+///
+/// p - 1 = 2^s * t with t odd. Starting from x = a^((t+1)/2) and b = a^t we
+/// have x^2 = ab, and each round multiplies by a power of c = z^t (z a
+/// non-residue) to halve the order of b until it is 1.
+fn tonelli_shanks<F: PrimeField>(a: &F, p: &[u64]) -> Option<F> {
+    if legendre(a) != 1 {
+        return None;
+    }
+
+    let mut p_minus_1 = p.to_vec();
+    p_minus_1[0] -= 1;
+    let s = trailing_zeros(&p_minus_1);
+    let t = shr(&p_minus_1, s);
+
+    let mut z = F::from_u64(2);
+    while legendre(&z) != -1 {
+        z = z + F::one();
+    }
+
+    let mut m = s;
+    let mut c = z.pow(&t);
+    let mut x = a.pow(&add_one(&shr(&t, 1)));
+    let mut b = a.pow(&t);
+
+    while b != F::one() {
+        // least i such that b^(2^i) = 1
+        let mut i = 0;
+        let mut b2i = b.clone();
+        while b2i != F::one() {
+            b2i = b2i.square();
+            i += 1;
+        }
+
+        let mut gs = c;
+        for _ in 0..(m - i - 1) {
+            gs = gs.square();
+        }
+        x = x * &gs;
+        c = gs.square();
+        b = b * &c;
+        m = i;
+    }
+
+    Some(x)
+}
+
+pub(crate) fn trailing_zeros(limbs: &[u64]) -> usize {
+    let mut res = 0;
+    for limb in limbs {
+        if *limb != 0 {
+            return res + limb.trailing_zeros() as usize;
+        }
+        res += 64;
+    }
+    res
+}
+
+/// Little-endian limbs shifted right by n < 64 * len bits
+pub(crate) fn shr(limbs: &[u64], n: usize) -> Vec<u64> {
+    let (words, bits) = (n / 64, n % 64);
+    let mut res = vec![0u64; limbs.len()];
+    for i in 0..limbs.len() - words {
+        res[i] = limbs[i + words] >> bits;
+        if bits > 0 && i + words + 1 < limbs.len() {
+            res[i] |= limbs[i + words + 1] << (64 - bits);
+        }
+    }
+    res
+}
+
+fn add_one(limbs: &[u64]) -> Vec<u64> {
+    let mut res = limbs.to_vec();
+    for limb in res.iter_mut() {
+        let (sum, carry) = limb.overflowing_add(1);
+        *limb = sum;
+        if !carry {
+            break;
+        }
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::field::{Field, PrimeField};
+    use crate::p_u256::U256FieldElement;
+    use crate::p_u64::U64FieldElement;
+    use crate::params::{u256_from_hex, PrimeFieldParams};
+    use primitive_types::U256;
+
+    test_prime_field!(P2, U256([2, 0, 0, 0]));
+    test_prime_field!(P3, U256([3, 0, 0, 0]));
+    test_prime_field!(P13, U256([13, 0, 0, 0]));
+    test_prime_field!(P17, U256([17, 0, 0, 0]));
+    test_prime_field!(P29, U256([29, 0, 0, 0]));
+    test_prime_field!(P31, U256([31, 0, 0, 0]));
+    test_prime_field!(P41, U256([41, 0, 0, 0]));
+    test_prime_field!(P97, U256([97, 0, 0, 0]));
+    test_prime_field!(P113, U256([113, 0, 0, 0]));
+    test_prime_field!(P257, U256([257, 0, 0, 0]));
+    test_prime_field!(P193, U256([193, 0, 0, 0]));
+    test_prime_field!(
+        Secp256k1P,
+        u256_from_hex("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F")
+    );
+    test_prime_field!(
+        Ed25519P,
+        u256_from_hex("7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffed")
+    );
+    test_prime_field!(
+        Bn254R,
+        u256_from_hex("30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001")
+    );
+
+    #[test]
+    fn params_are_prime() {
+        P2::check().unwrap();
+        P3::check().unwrap();
+        P13::check().unwrap();
+        P17::check().unwrap();
+        P29::check().unwrap();
+        P31::check().unwrap();
+        P41::check().unwrap();
+        P97::check().unwrap();
+        P113::check().unwrap();
+        P257::check().unwrap();
+        P193::check().unwrap();
+        Ed25519P::check().unwrap();
+        Secp256k1P::check().unwrap();
+        Bn254R::check().unwrap();
+    }
+
+    /// Compares against the set of squares of all elements of GF(p)
+    fn check_all<F: PrimeField>(p: u64) {
+        let squares: Vec<F> = (0..p).map(|x| F::from_u64(x).square()).collect();
+        for a in (0..p).map(F::from_u64) {
+            let is_square = squares.contains(&a);
+            assert_eq!(a.is_square(), is_square, "{:?}", a);
+            assert_eq!(
+                a.legendre(),
+                match (a.is_zero(), is_square) {
+                    (true, _) => 0,
+                    (false, true) => 1,
+                    (false, false) => -1,
+                }
+            );
+            match a.sqrt() {
+                Some(root) => assert_eq!(root.square(), a),
+                None => assert!(!is_square, "{:?}", a),
+            }
+        }
+    }
+
+    fn check_both<P: PrimeFieldParams>() {
+        let p = P::MODULUS.low_u64();
+        check_all::<U64FieldElement<P>>(p);
+        check_all::<U256FieldElement<P>>(p);
+    }
+
+    #[test]
+    fn sqrt_all_elements() {
+        // Montgomery form needs an odd modulus
+        check_all::<U64FieldElement<P2>>(2);
+        // 3 mod 4
+        check_both::<P3>();
+        check_both::<P31>();
+        // 5 mod 8
+        check_both::<P13>();
+        check_both::<P29>();
+        // 1 mod 8, 2-adicity 4, 3, 5, 4, 8, 6
+        check_both::<P17>();
+        check_both::<P41>();
+        check_both::<P97>();
+        check_both::<P113>();
+        check_both::<P257>();
+        check_both::<P193>();
+    }
+
+    fn check_large<P: PrimeFieldParams>() {
+        for i in 1..50 {
+            let x = U256FieldElement::<P>::from_u64(i).exp_by_u64(1_000_003);
+            let sq = x.square();
+            let root = sq.sqrt().unwrap();
+            assert!(root == x || root == -x);
+            assert_eq!(sq.legendre(), 1);
+        }
+        // a non-square times a non-zero square is a non-square
+        let mut z = U256FieldElement::<P>::from_u64(2);
+        while z.is_square() {
+            z += U256FieldElement::one();
+        }
+        assert_eq!(z.sqrt(), None);
+        assert_eq!((z * U256FieldElement::from_u64(49)).sqrt(), None);
+    }
+
+    #[test]
+    fn sqrt_large_fields() {
+        check_large::<Secp256k1P>();
+        check_large::<Ed25519P>();
+        check_large::<Bn254R>();
+    }
+}
@@ -0,0 +1,402 @@
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::ops::{Add, Div, Mul, Neg, Sub};
+
+use crate::u256_polynomial::Polynomial;
+use ff::factor::prime_factors;
+use ff::{Error, PrimeField, Result};
+
+/// GF(p^k) = GF(p)[x] / m(x) for an irreducible m of degree k, chosen at
+/// runtime
+///
+/// Unlike the compile-time towers in ff (`Fp2`, `Fp6`, ...) the modulus is a
+/// value, so elements carry a handle to their field and cannot implement
+/// `ff::Field`, whose `zero()` and `one()` take no arguments. They are
+/// created from the field instead. The handle is reference counted, cloning
+/// a field or an element does not copy the modulus.
+#[derive(Debug, Clone)]
+pub struct ExtensionField<F: PrimeField> {
+	modulus: Arc<Polynomial<F>>
+}
+
+/// c_0 + c_1 x + ... + c_(k-1) x^(k-1) in an `ExtensionField`
+///
+/// Operators panic on elements of different fields, and division by zero
+/// panics like it does for the ff types.
+#[derive(Debug, Clone)]
+pub struct ExtensionFieldElement<F: PrimeField> {
+	field: ExtensionField<F>,
+	value: Polynomial<F> // trimmed, of degree below k
+}
+
+impl<F: PrimeField> PartialEq for ExtensionField<F> {
+	fn eq(&self, other: &Self) -> bool {
+		Arc::ptr_eq(&self.modulus, &other.modulus) || self.modulus == other.modulus
+	}
+}
+impl<F: PrimeField> Eq for ExtensionField<F> {}
+
+impl<F: PrimeField> ExtensionField<F> {
+	/// The field GF(p)[x] / m(x), after scaling m to be monic. Fails with
+	/// `Error::NotIrreducible` unless m is irreducible, which is checked with
+	/// Rabin's test.
+	pub fn new(modulus: &Polynomial<F>) -> Result<Self> {
+		let mut modulus = modulus.clone();
+		modulus.trim();
+		if modulus.coefficients.len() < 2 {
+			return Err(Error::NotIrreducible);
+		}
+		let lead_inv = modulus.last_coefficient().inv().unwrap();
+		let field = Self {
+			modulus: Arc::new(modulus.scale(&lead_inv))
+		};
+
+		match field.is_irreducible() {
+			true => Ok(field),
+			false => Err(Error::NotIrreducible)
+		}
+	}
+
+	/// k, the degree of the extension
+	pub fn degree(&self) -> usize {
+		self.modulus.degree()
+	}
+
+	/// The monic modulus m(x)
+	pub fn modulus(&self) -> &Polynomial<F> {
+		&self.modulus
+	}
+
+	pub fn zero(&self) -> ExtensionFieldElement<F> {
+		self.element(Polynomial::zero())
+	}
+
+	pub fn one(&self) -> ExtensionFieldElement<F> {
+		self.from_base(F::one())
+	}
+
+	/// The class of x, a root of m
+	pub fn x(&self) -> ExtensionFieldElement<F> {
+		self.element(Polynomial::monomial(F::one(), 1))
+	}
+
+	/// The embedding of GF(p)
+	pub fn from_base(&self, c: F) -> ExtensionFieldElement<F> {
+		self.element(Polynomial::new(&[c]))
+	}
+
+	/// The class of c_0 + c_1 x + ..., of any degree
+	pub fn from_coefficients(&self, coefficients: &[F]) -> ExtensionFieldElement<F> {
+		self.element(Polynomial::new(coefficients))
+	}
+
+	fn element(&self, value: Polynomial<F>) -> ExtensionFieldElement<F> {
+		ExtensionFieldElement {
+			field: self.clone(),
+			value: value.div_rem(&self.modulus).1
+		}
+	}
+
+	/// Rabin: m of degree k is irreducible iff x^(p^k) = x (mod m) and
+	/// gcd(x^(p^(k/q)) - x, m) = 1 for every prime q dividing k
+	fn is_irreducible(&self) -> bool {
+		let k = self.degree();
+		let x = self.x();
+
+		// x^(p^j) for j = 1..=k
+		let mut powers = Vec::with_capacity(k);
+		let mut power = x.clone();
+		for _ in 0..k {
+			power = power.frobenius(1);
+			powers.push(power.clone());
+		}
+		if powers[k - 1] != x {
+			return false;
+		}
+
+		prime_factors(k as u64).into_iter().all(|q| {
+			let q = q as usize;
+			let (gcd, _, _) = (&powers[k / q - 1] - &x).value.extended_gcd(&self.modulus);
+			gcd.degree() == 0 && !gcd.is_zero()
+		})
+	}
+}
+
+impl<F: PrimeField> ExtensionFieldElement<F> {
+	pub fn field(&self) -> &ExtensionField<F> {
+		&self.field
+	}
+
+	/// c_0, c_1, ..., without zero leading coefficients
+	pub fn coefficients(&self) -> &[F] {
+		&self.value.coefficients
+	}
+
+	pub fn is_zero(&self) -> bool {
+		self.value.is_zero()
+	}
+
+	pub fn square(&self) -> Self {
+		self * self
+	}
+
+	/// Inverse through the extended gcd with the modulus: s a + t m = 1
+	/// gives s a = 1 (mod m). `None` for zero.
+	pub fn inv(&self) -> Option<Self> {
+		if self.is_zero() {
+			return None;
+		}
+		let (gcd, s, _) = self.value.extended_gcd(&self.field.modulus);
+		debug_assert!(gcd.degree() == 0);
+		Some(self.field.element(s))
+	}
+
+	/// self^exp, exponent as little-endian u64 limbs, see `ff::Field::pow`
+	pub fn pow(&self, exp: &[u64]) -> Self {
+		let mut res = self.field.one();
+		for limb in exp.iter().rev() {
+			for i in (0..64).rev() {
+				res = res.square();
+				if (limb >> i) & 1 == 1 {
+					res = &res * self;
+				}
+			}
+		}
+		res
+	}
+
+	/// self^(p^i), the i-th power of the Frobenius automorphism
+	pub fn frobenius(&self, i: usize) -> Self {
+		let p = F::characteristic();
+		let mut res = self.clone();
+		for _ in 0..i % self.field.degree() {
+			res = res.pow(&p);
+		}
+		res
+	}
+
+	/// Product of the k conjugates a^(p^i), an element of GF(p)
+	pub fn norm(&self) -> F {
+		let mut conjugate = self.clone();
+		let mut norm = self.clone();
+		for _ in 1..self.field.degree() {
+			conjugate = conjugate.frobenius(1);
+			norm = &norm * &conjugate;
+		}
+		norm.to_base()
+	}
+
+	/// Sum of the k conjugates a^(p^i), an element of GF(p)
+	pub fn trace(&self) -> F {
+		let mut conjugate = self.clone();
+		let mut trace = self.clone();
+		for _ in 1..self.field.degree() {
+			conjugate = conjugate.frobenius(1);
+			trace = &trace + &conjugate;
+		}
+		trace.to_base()
+	}
+
+	fn to_base(&self) -> F {
+		debug_assert!(self.value.coefficients.len() <= 1);
+		self.value.last_coefficient()
+	}
+
+	fn check_same_field(&self, other: &Self) {
+		assert!(
+			self.field == other.field,
+			"elements of different extension fields"
+		);
+	}
+}
+
+impl<F: PrimeField> PartialEq for ExtensionFieldElement<F> {
+	fn eq(&self, other: &Self) -> bool {
+		self.field == other.field && self.value == other.value
+	}
+}
+impl<F: PrimeField> Eq for ExtensionFieldElement<F> {}
+
+/////////////////////////////////////////////
+/////////////// Operator Overloads
+/////////////////////////////////////////////
+impl<F: PrimeField> Add<&ExtensionFieldElement<F>> for &ExtensionFieldElement<F> {
+	type Output = ExtensionFieldElement<F>;
+	fn add(self, rhs: &ExtensionFieldElement<F>) -> ExtensionFieldElement<F> {
+		self.check_same_field(rhs);
+		ExtensionFieldElement {
+			field: self.field.clone(),
+			value: &self.value + &rhs.value
+		}
+	}
+}
+impl<F: PrimeField> Sub<&ExtensionFieldElement<F>> for &ExtensionFieldElement<F> {
+	type Output = ExtensionFieldElement<F>;
+	fn sub(self, rhs: &ExtensionFieldElement<F>) -> ExtensionFieldElement<F> {
+		self.check_same_field(rhs);
+		ExtensionFieldElement {
+			field: self.field.clone(),
+			value: &self.value - &rhs.value
+		}
+	}
+}
+impl<F: PrimeField> Mul<&ExtensionFieldElement<F>> for &ExtensionFieldElement<F> {
+	type Output = ExtensionFieldElement<F>;
+	fn mul(self, rhs: &ExtensionFieldElement<F>) -> ExtensionFieldElement<F> {
+		self.check_same_field(rhs);
+		self.field.element(&self.value * &rhs.value)
+	}
+}
+impl<F: PrimeField> Div<&ExtensionFieldElement<F>> for &ExtensionFieldElement<F> {
+	type Output = ExtensionFieldElement<F>;
+	#[allow(clippy::suspicious_arithmetic_impl)]
+	fn div(self, rhs: &ExtensionFieldElement<F>) -> ExtensionFieldElement<F> {
+		self * &rhs.inv().expect("division by zero")
+	}
+}
+impl<F: PrimeField> Neg for &ExtensionFieldElement<F> {
+	type Output = ExtensionFieldElement<F>;
+	fn neg(self) -> ExtensionFieldElement<F> {
+		ExtensionFieldElement {
+			field: self.field.clone(),
+			value: -&self.value
+		}
+	}
+}
+
+impl<F: PrimeField> Add for ExtensionFieldElement<F> {
+	type Output = ExtensionFieldElement<F>;
+	fn add(self, rhs: ExtensionFieldElement<F>) -> ExtensionFieldElement<F> {
+		&self + &rhs
+	}
+}
+impl<F: PrimeField> Sub for ExtensionFieldElement<F> {
+	type Output = ExtensionFieldElement<F>;
+	fn sub(self, rhs: ExtensionFieldElement<F>) -> ExtensionFieldElement<F> {
+		&self - &rhs
+	}
+}
+impl<F: PrimeField> Mul for ExtensionFieldElement<F> {
+	type Output = ExtensionFieldElement<F>;
+	fn mul(self, rhs: ExtensionFieldElement<F>) -> ExtensionFieldElement<F> {
+		&self * &rhs
+	}
+}
+impl<F: PrimeField> Div for ExtensionFieldElement<F> {
+	type Output = ExtensionFieldElement<F>;
+	fn div(self, rhs: ExtensionFieldElement<F>) -> ExtensionFieldElement<F> {
+		&self / &rhs
+	}
+}
+impl<F: PrimeField> Neg for ExtensionFieldElement<F> {
+	type Output = ExtensionFieldElement<F>;
+	fn neg(self) -> ExtensionFieldElement<F> {
+		-&self
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ff::bn254::{Bn254Fq, Bn254Fq2};
+	use ff::p_u64::U64FieldElement;
+	use ff::{Field, Fp, Fp2, Fp2Params, PrimeFieldParams};
+	use primitive_types::U256;
+	use rand::rngs::StdRng;
+	use rand::SeedableRng;
+
+	#[derive(Debug)]
+	struct P7;
+	impl PrimeFieldParams for P7 {
+		const MODULUS: U256 = U256([7, 0, 0, 0]);
+	}
+	type F7 = U64FieldElement<P7>;
+
+	fn poly(coefficients: &[u64]) -> Polynomial<F7> {
+		Polynomial::new(&coefficients.iter().map(|c| F7::new(*c)).collect::<Vec<_>>())
+	}
+
+	#[test]
+	fn rejects_reducible_moduli() {
+		// x^2 - 4 = (x - 2)(x + 2)
+		assert_eq!(
+			ExtensionField::new(&poly(&[3, 0, 1])).unwrap_err(),
+			Error::NotIrreducible
+		);
+		// (x^2 - 3)^2 has no roots, 3 being a non-residue mod 7
+		assert_eq!(
+			ExtensionField::new(&poly(&[2, 0, 1, 0, 1])).unwrap_err(),
+			Error::NotIrreducible
+		);
+		assert_eq!(ExtensionField::new(&poly(&[5])).unwrap_err(), Error::NotIrreducible);
+		assert!(ExtensionField::new(&poly(&[4, 0, 1])).is_ok());
+	}
+
+	/// GF(7^3) = GF(7)[x] / (x^3 - 2), 2 not being a cube mod 7
+	#[test]
+	fn cubic_extension() {
+		let field = ExtensionField::new(&poly(&[5, 0, 0, 1])).unwrap();
+		assert_eq!(field.degree(), 3);
+		// the modulus is made monic
+		assert_eq!(ExtensionField::new(&poly(&[3, 0, 0, 2])).unwrap(), field);
+
+		let mut rng = StdRng::seed_from_u64(17);
+		let mut random = || {
+			let coefficients: Vec<F7> = (0..3).map(|_| F7::random(&mut rng)).collect();
+			field.from_coefficients(&coefficients)
+		};
+		for _ in 0..20 {
+			let (a, b) = (random(), random());
+			assert_eq!(a.frobenius(3), a);
+			assert_eq!(a.frobenius(1), a.pow(&[7]));
+			assert_eq!((&a * &b).norm(), a.norm() * b.norm());
+			assert_eq!((&a + &b).trace(), a.trace() + b.trace());
+			if !a.is_zero() {
+				// |GF(7^3)*| = 342
+				assert_eq!(a.pow(&[342]), field.one());
+				assert_eq!(&a * &a.inv().unwrap(), field.one());
+				assert_eq!(&(&a * &b) / &a, b);
+			}
+		}
+		assert_eq!(field.zero().inv(), None);
+
+		// x^3 = 2, Tr(x) = 0, N(x) = 2
+		let x = field.x();
+		assert_eq!(x.pow(&[3]), field.from_base(F7::new(2)));
+		assert_eq!(x.trace(), F7::zero());
+		assert_eq!(x.norm(), F7::new(2));
+	}
+
+	#[test]
+	#[should_panic(expected = "different extension fields")]
+	fn mixing_fields_panics() {
+		let a = ExtensionField::new(&poly(&[5, 0, 0, 1])).unwrap();
+		let b = ExtensionField::new(&poly(&[4, 0, 1])).unwrap();
+		let _ = a.one() + b.one();
+	}
+
+	/// Fp[x] / (x^2 - β) computes exactly what `Fp2` does
+	#[test]
+	fn matches_fp2() {
+		type Fq = Fp<Bn254Fq>;
+		let beta = Bn254Fq2::NON_RESIDUE;
+		let modulus = Polynomial::new(&[-beta, Fq::zero(), Fq::one()]);
+		let field = ExtensionField::new(&modulus).unwrap();
+		let embed = |a: &Fp2<Bn254Fq2>| field.from_coefficients(&[a.c0, a.c1]);
+
+		let mut rng = StdRng::seed_from_u64(4);
+		for _ in 0..10 {
+			let a = Fp2::<Bn254Fq2>::random(&mut rng);
+			let b = Fp2::<Bn254Fq2>::random(&mut rng);
+			let (x, y) = (embed(&a), embed(&b));
+			assert_eq!(&x + &y, embed(&(a.clone() + &b)));
+			assert_eq!(&x - &y, embed(&(a.clone() - &b)));
+			assert_eq!(&x * &y, embed(&(a.clone() * &b)));
+			assert_eq!(&x / &y, embed(&(a.clone() / &b)));
+			assert_eq!(x.inv(), a.inv().map(|i| embed(&i)));
+			assert_eq!(x.frobenius(1), embed(&a.frobenius_map(1)));
+			assert_eq!(x.norm(), a.norm());
+			assert_eq!(x.trace(), a.c0.double());
+		}
+	}
+}
@@ -1 +1,6 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+pub mod extension_field;
 pub mod u256_polynomial;
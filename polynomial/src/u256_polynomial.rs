@@ -1,14 +1,20 @@
-use ff::p_u256::U256FieldElement;
+use alloc::{vec, vec::Vec};
+use core::ops::{Add, Mul, Neg, Sub};
+use ff::parallel::{self, MaybeSync};
+use ff::Field;
+
+/// Coefficients per `sum_of_products` in `evaluate`
+const HORNER_BLOCK: usize = 16;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Polynomial<FieldElement> {
 	pub coefficients: Vec<FieldElement> // growing in degree
 }
 
-impl Polynomial<U256FieldElement> {
-	pub fn new(coefficients: &[U256FieldElement]) -> Self {
+impl<F: Field> Polynomial<F> {
+	pub fn new(coefficients: &[F]) -> Self {
 		Self {
-			coefficients: coefficients.iter().cloned().collect::<Vec<U256FieldElement>>()
+			coefficients: coefficients.to_vec()
 		}
 	}
 
@@ -17,32 +23,335 @@ impl Polynomial<U256FieldElement> {
 	}
 
 	pub fn degree(&self) -> usize {
-		let mut degree = self.coefficients.len();
-		if degree > 0 {degree -= 1;}
-		degree
+		self.coefficients.len().saturating_sub(1)
 	}
 
-	pub fn last_coefficient(&self) -> U256FieldElement {
+	pub fn last_coefficient(&self) -> F {
 		let coeff_len = self.coefficients.len();
 		if coeff_len == 0 {
-			U256FieldElement::from_str("0", "7").unwrap() // TODO: rearchitect the field
+			F::zero()
 		} else {
 			self.coefficients[coeff_len - 1].clone()
 		}
 	}
 
-	pub fn batch_evaluate(&self, domain: &[U256FieldElement]) -> Vec<U256FieldElement> {
-		domain.iter().map(|x| self.evaluate(x)).collect()
-	}
-	pub fn evaluate(&self, x: &U256FieldElement) -> U256FieldElement {
+	pub fn evaluate(&self, x: &F) -> F {
 		self.horners_method(x)
 	}
-	fn horners_method(&self, x: &U256FieldElement) -> U256FieldElement {
-		let mut val = U256FieldElement::zero(x.prime);
-		for coeff in self.coefficients.iter().rev() {
-			val = val * x + coeff;
+	/// Horner's rule on blocks of up to `HORNER_BLOCK` coefficients, each
+	/// block evaluated by one `sum_of_products` with the powers of x, so the
+	/// field types with unreduced accumulation reduce once per block
+	fn horners_method(&self, x: &F) -> F {
+		let block = HORNER_BLOCK.min(self.coefficients.len()).max(1);
+		// x^0 .. x^block
+		let mut powers = Vec::with_capacity(block + 1);
+		powers.push(F::one());
+		for i in 0..block {
+			powers.push(powers[i].clone() * x);
+		}
+
+		// only the lowest block can be short
+		let mut val = F::zero();
+		for chunk in self.coefficients.rchunks(block) {
+			let terms = F::sum_of_products(chunk, &powers[..chunk.len()]);
+			val = val * &powers[chunk.len()] + terms;
 		}
 		val
 	}
 
+	/// coeff * x^degree
+	pub fn monomial(coeff: F, degree: usize) -> Self {
+		let mut coefficients = vec![F::zero(); degree + 1];
+		coefficients[degree] = coeff;
+		Self { coefficients }
+	}
+
+	pub fn is_zero(&self) -> bool {
+		self.coefficients.iter().all(|c| c.is_zero())
+	}
+
+	/// Drops zero leading coefficients, so that `degree` is the actual degree
+	pub fn trim(&mut self) {
+		while self.coefficients.last().is_some_and(|c| c.is_zero()) {
+			self.coefficients.pop();
+		}
+	}
+
+	pub fn scale(&self, by: &F) -> Self {
+		let mut res = Self {
+			coefficients: self.coefficients.iter().map(|c| c.clone() * by).collect()
+		};
+		res.trim();
+		res
+	}
+
+	/// Long division: (q, r) with self = q * divisor + r and deg r < deg divisor.
+	/// Panics if `divisor` is zero.
+	pub fn div_rem(&self, divisor: &Self) -> (Self, Self) {
+		let mut divisor = divisor.clone();
+		divisor.trim();
+		let lead_inv = divisor
+			.last_coefficient()
+			.inv()
+			.expect("division by the zero polynomial");
+
+		let mut rem = self.clone();
+		rem.trim();
+		if rem.coefficients.len() < divisor.coefficients.len() {
+			return (Self::zero(), rem);
+		}
+
+		let d = divisor.degree();
+		let mut quotient = vec![F::zero(); rem.coefficients.len() - d];
+		for i in (0..quotient.len()).rev() {
+			let c = rem.coefficients[i + d].clone() * &lead_inv;
+			for (j, coeff) in divisor.coefficients.iter().enumerate() {
+				rem.coefficients[i + j] = rem.coefficients[i + j].clone() - c.clone() * coeff;
+			}
+			quotient[i] = c;
+		}
+		rem.coefficients.truncate(d);
+		rem.trim();
+
+		(Self { coefficients: quotient }, rem)
+	}
+
+	/// Extended Euclid: (g, s, t) with s * self + t * other = g, where g is
+	/// the monic gcd, or zero if both inputs are
+	pub fn extended_gcd(&self, other: &Self) -> (Self, Self, Self) {
+		let one = Self::new(&[F::one()]);
+		let (mut r0, mut r1) = (self.clone(), other.clone());
+		r0.trim();
+		r1.trim();
+		let (mut s0, mut s1) = (one.clone(), Self::zero());
+		let (mut t0, mut t1) = (Self::zero(), one);
+
+		while !r1.is_zero() {
+			let (q, r) = r0.div_rem(&r1);
+			(r0, r1) = (r1, r);
+			(s0, s1) = (s1.clone(), &s0 - &(&q * &s1));
+			(t0, t1) = (t1.clone(), &t0 - &(&q * &t1));
+		}
+
+		if r0.is_zero() {
+			return (r0, s0, t0);
+		}
+		let lead_inv = r0.last_coefficient().inv().unwrap();
+		(r0.scale(&lead_inv), s0.scale(&lead_inv), t0.scale(&lead_inv))
+	}
+
+	/// Coefficient-wise `op`, padding the shorter side with zeros
+	fn zip_with(&self, rhs: &Self, op: impl Fn(F, &F) -> F) -> Self {
+		let len = self.coefficients.len().max(rhs.coefficients.len());
+		let zero = F::zero();
+		let mut res = Self {
+			coefficients: (0..len)
+				.map(|i| {
+					let a = self.coefficients.get(i).unwrap_or(&zero).clone();
+					op(a, rhs.coefficients.get(i).unwrap_or(&zero))
+				})
+				.collect()
+		};
+		res.trim();
+		res
+	}
+}
+
+impl<F: Field + MaybeSync> Polynomial<F> {
+	/// `evaluate` at every point of `domain`, split over threads for large
+	/// domains with the `parallel` feature
+	pub fn batch_evaluate(&self, domain: &[F]) -> Vec<F> {
+		parallel::map(domain, |x| self.evaluate(x))
+	}
+}
+
+/////////////////////////////////////////////
+/////////////// Operator Overloads
+/////////////////////////////////////////////
+// Results are trimmed
+impl<F: Field> Add<&Polynomial<F>> for &Polynomial<F> {
+	type Output = Polynomial<F>;
+	fn add(self, rhs: &Polynomial<F>) -> Polynomial<F> {
+		self.zip_with(rhs, |a, b| a + b)
+	}
+}
+impl<F: Field> Add for Polynomial<F> {
+	type Output = Polynomial<F>;
+	fn add(self, rhs: Polynomial<F>) -> Polynomial<F> {
+		&self + &rhs
+	}
+}
+impl<F: Field> Sub<&Polynomial<F>> for &Polynomial<F> {
+	type Output = Polynomial<F>;
+	fn sub(self, rhs: &Polynomial<F>) -> Polynomial<F> {
+		self.zip_with(rhs, |a, b| a - b)
+	}
+}
+impl<F: Field> Sub for Polynomial<F> {
+	type Output = Polynomial<F>;
+	fn sub(self, rhs: Polynomial<F>) -> Polynomial<F> {
+		&self - &rhs
+	}
+}
+impl<F: Field> Mul<&Polynomial<F>> for &Polynomial<F> {
+	type Output = Polynomial<F>;
+
+	/// Schoolbook
+	fn mul(self, rhs: &Polynomial<F>) -> Polynomial<F> {
+		if self.coefficients.is_empty() || rhs.coefficients.is_empty() {
+			return Polynomial::zero();
+		}
+		let len = self.coefficients.len() + rhs.coefficients.len() - 1;
+		let mut coefficients = vec![F::zero(); len];
+		for (i, a) in self.coefficients.iter().enumerate() {
+			for (j, b) in rhs.coefficients.iter().enumerate() {
+				coefficients[i + j] = coefficients[i + j].clone() + a.clone() * b;
+			}
+		}
+		let mut res = Polynomial { coefficients };
+		res.trim();
+		res
+	}
+}
+impl<F: Field> Mul for Polynomial<F> {
+	type Output = Polynomial<F>;
+	fn mul(self, rhs: Polynomial<F>) -> Polynomial<F> {
+		&self * &rhs
+	}
+}
+impl<F: Field> Neg for &Polynomial<F> {
+	type Output = Polynomial<F>;
+	fn neg(self) -> Polynomial<F> {
+		Polynomial::zero() - self.clone()
+	}
+}
+impl<F: Field> Neg for Polynomial<F> {
+	type Output = Polynomial<F>;
+	fn neg(self) -> Polynomial<F> {
+		-&self
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ff::gf2n::{Aes, GF2n};
+	use ff::p_u256::U256FieldElement;
+	use ff::p_u64::U64FieldElement;
+	use ff::PrimeFieldParams;
+	use primitive_types::U256;
+
+	#[derive(Debug)]
+	struct P97;
+	impl PrimeFieldParams for P97 {
+		const MODULUS: U256 = U256([97, 0, 0, 0]);
+	}
+
+	// 3 + 2x + x^2, so f(5) = 38 and f(10) = 123 = 26 (mod 97)
+	fn evaluate_over<F: ff::PrimeField + MaybeSync>() {
+		let poly = Polynomial::new(&[F::from_u64(3), F::from_u64(2), F::from_u64(1)]);
+		assert_eq!(poly.degree(), 2);
+		assert_eq!(
+			poly.batch_evaluate(&[F::from_u64(5), F::from_u64(10)]),
+			vec![F::from_u64(38), F::from_u64(26)]
+		);
+	}
+
+	/// Blocked evaluation against the textbook Horner's rule, around the
+	/// block size
+	#[test]
+	fn evaluate_matches_horner() {
+		use rand::rngs::StdRng;
+		use rand::SeedableRng;
+
+		type F = U256FieldElement<ff::bn254::Bn254Fr>;
+		let mut rng = StdRng::seed_from_u64(22);
+		let x = F::random(&mut rng);
+		for len in [0, 1, 2, 15, 16, 17, 32, 33, 100] {
+			let coefficients: Vec<F> = (0..len).map(|_| F::random(&mut rng)).collect();
+			let horner = coefficients
+				.iter()
+				.rev()
+				.fold(F::zero(), |val, c| val * x + c);
+			assert_eq!(Polynomial::new(&coefficients).evaluate(&x), horner, "{}", len);
+		}
+	}
+
+	/// Domains long enough to be split with the `parallel` feature
+	#[test]
+	fn batch_evaluate_matches_evaluate() {
+		use ff::PrimeField;
+
+		type F = U64FieldElement<P97>;
+		let poly = Polynomial::new(&[F::from_u64(3), F::from_u64(2), F::from_u64(1)]);
+		let len = parallel::PARALLEL_THRESHOLD + parallel::CHUNK_LEN + 1;
+		let domain: Vec<F> = (0..len as u64).map(F::from_u64).collect();
+		let expected: Vec<F> = domain.iter().map(|x| poly.evaluate(x)).collect();
+		assert_eq!(poly.batch_evaluate(&domain), expected);
+	}
+
+	#[test]
+	fn evaluate_u64() {
+		evaluate_over::<U64FieldElement<P97>>();
+	}
+
+	#[test]
+	fn evaluate_u256() {
+		evaluate_over::<U256FieldElement<P97>>();
+	}
+
+	// Same polynomial over GF(2^8), where 2 * 5 = 10 and 5^2 = 17 carry-less,
+	// so f(5) = 3 ^ 10 ^ 17 = 24 and f(10) = 3 ^ 20 ^ 68 = 83
+	#[test]
+	fn evaluate_binary_field() {
+		type F = GF2n<Aes>;
+		let poly = Polynomial::new(&[F::new(3), F::new(2), F::new(1)]);
+		assert_eq!(
+			poly.batch_evaluate(&[F::new(5), F::new(10)]),
+			vec![F::new(24), F::new(83)]
+		);
+	}
+
+	fn p97(coefficients: &[u64]) -> Polynomial<U64FieldElement<P97>> {
+		let coefficients: Vec<_> = coefficients.iter().map(|c| U64FieldElement::new(*c)).collect();
+		Polynomial::new(&coefficients)
+	}
+
+	#[test]
+	fn arithmetic() {
+		// (x + 1)(x - 1) = x^2 - 1
+		let a = p97(&[1, 1]);
+		let b = p97(&[96, 1]);
+		assert_eq!(&a * &b, p97(&[96, 0, 1]));
+		assert_eq!(&a + &b, p97(&[0, 2]));
+		assert_eq!(&a - &a, Polynomial::zero());
+		assert_eq!(-&a, p97(&[96, 96]));
+
+		// x^3 + 2x + 5 = (x^2 + x + 3)(x - 1) + 8
+		let (q, r) = p97(&[5, 2, 0, 1]).div_rem(&b);
+		assert_eq!(q, p97(&[3, 1, 1]));
+		assert_eq!(r, p97(&[8]));
+	}
+
+	#[test]
+	fn extended_gcd() {
+		// gcd((x + 1)(x + 2), (x + 1)(x + 3)) = x + 1
+		let a = p97(&[2, 3, 1]);
+		let b = p97(&[3, 4, 1]);
+		let (g, s, t) = a.extended_gcd(&b);
+		assert_eq!(g, p97(&[1, 1]));
+		assert_eq!(&(&s * &a) + &(&t * &b), g);
+
+		// coprime inputs give 1
+		let (g, s, t) = a.extended_gcd(&p97(&[5, 0, 1]));
+		assert_eq!(g, p97(&[1]));
+		assert_eq!(&(&s * &a) + &(&t * &p97(&[5, 0, 1])), g);
+	}
+
+	#[test]
+	fn zero_polynomial() {
+		let poly = Polynomial::<U64FieldElement<P97>>::zero();
+		assert_eq!(poly.degree(), 0);
+		assert!(poly.last_coefficient().is_zero());
+	}
 }
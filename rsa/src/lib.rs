@@ -4,6 +4,7 @@ use crate::errors::{Error, Result};
 use num_bigint::{BigInt, BigUint, IntoBigInt, ModInverse, RandPrime, Sign::Plus};
 use num_integer::Integer;
 use num_traits::{FromPrimitive, One, Signed, Zero};
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
 //////////////////////////////////////////////////////
 //////////////////   Public Key  /////////////////////
@@ -42,7 +43,7 @@ impl PublicKey {
     }
 
     fn size_bytes(&self) -> usize {
-        (self.n.bits() + 7) / 8
+        self.n.bits().div_ceil(8)
     }
 }
 
@@ -50,7 +51,8 @@ impl PublicKey {
 //////////////////   Private Key  ////////////////////
 //////////////////////////////////////////////////////
 
-#[derive(Debug, Clone)]
+/// The secret parts are wiped on drop and left out of `Debug`. Not `Clone`,
+/// use `clone_secret` to make a copy.
 pub struct PrivateKey {
     public_key: PublicKey,
     p: BigUint,
@@ -64,6 +66,32 @@ pub struct PrivateKey {
     q_inv_mod_p_minus_one: BigInt,
 }
 
+impl std::fmt::Debug for PrivateKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PrivateKey")
+            .field("public_key", &self.public_key)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Zeroize for PrivateKey {
+    fn zeroize(&mut self) {
+        self.p.zeroize();
+        self.q.zeroize();
+        self.d_mod_p_minus_one.zeroize();
+        self.d_mod_q_minus_one.zeroize();
+        self.q_inv_mod_p_minus_one.zeroize();
+    }
+}
+
+impl Drop for PrivateKey {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for PrivateKey {}
+
 struct PrivateKeyComponents {
     n: BigUint,
     d: BigUint,
@@ -73,26 +101,36 @@ struct PrivateKeyComponents {
 
 impl PrivateKey {
     pub fn new(modulus_bit_size: usize) -> Result<PrivateKey> {
-        let comps = PrivateKey::generate_private_key_components(modulus_bit_size)?;
+        let PrivateKeyComponents { n, mut d, p, q } =
+            PrivateKey::generate_private_key_components(modulus_bit_size)?;
 
-        let d_mod_p_minus_one = &comps.d % (&comps.p - BigUint::one());
-        let d_mod_q_minus_one = &comps.d % (&comps.q - BigUint::one());
-        let q_inv_mod_p_minus_one = comps
-            .p
-            .clone()
-            .mod_inverse(&comps.q)
-            .ok_or(Error::InvalidPrime)?;
+        let d_mod_p_minus_one = &d % (&p - BigUint::one());
+        let d_mod_q_minus_one = &d % (&q - BigUint::one());
+        d.zeroize();
+        let q_inv_mod_p_minus_one = p.clone().mod_inverse(&q).ok_or(Error::InvalidPrime)?;
 
         Ok(Self {
-            public_key: PublicKey::new(comps.n)?,
-            p: comps.p,
-            q: comps.q,
+            public_key: PublicKey::new(n)?,
+            p,
+            q,
             d_mod_p_minus_one,
             d_mod_q_minus_one,
             q_inv_mod_p_minus_one,
         })
     }
 
+    /// An explicit copy of the key, wiped on drop like the original
+    pub fn clone_secret(&self) -> Self {
+        Self {
+            public_key: self.public_key.clone(),
+            p: self.p.clone(),
+            q: self.q.clone(),
+            d_mod_p_minus_one: self.d_mod_p_minus_one.clone(),
+            d_mod_q_minus_one: self.d_mod_q_minus_one.clone(),
+            q_inv_mod_p_minus_one: self.q_inv_mod_p_minus_one.clone(),
+        }
+    }
+
     pub fn decrypt(&self, c: &[u8]) -> Result<Vec<u8>> {
         let c = BigUint::from_bytes_be(c);
 
@@ -104,26 +142,33 @@ impl PrivateKey {
             .modpow(&self.d_mod_p_minus_one, &self.p)
             .into_bigint()
             .unwrap();
-        let m2 = c
+        let mut m2 = c
             .modpow(&self.d_mod_q_minus_one, &self.q)
             .into_bigint()
             .unwrap();
 
         m1 -= &m2;
 
-        let p_bigint = BigInt::from_biguint(Plus, self.p.clone());
+        let mut p_bigint = BigInt::from_biguint(Plus, self.p.clone());
         while m1.is_negative() {
             m1 += &p_bigint;
         }
 
         m1 *= &self.q_inv_mod_p_minus_one;
-        m1 %= p_bigint;
+        m1 %= &p_bigint;
+        p_bigint.zeroize();
 
         let mut m = m1.to_biguint().unwrap();
+        m1.zeroize();
         m *= &self.q;
-        m += m2.to_biguint().unwrap();
-
-        Ok(m.to_bytes_be())
+        let mut m2_biguint = m2.to_biguint().unwrap();
+        m2.zeroize();
+        m += &m2_biguint;
+        m2_biguint.zeroize();
+
+        let plaintext = m.to_bytes_be();
+        m.zeroize();
+        Ok(plaintext)
     }
 
     pub fn get_public_key(&self) -> Result<PublicKey> {
@@ -146,19 +191,27 @@ impl PrivateKey {
             p = rng.gen_prime(prime_bits);
             q = rng.gen_prime(prime_bits);
 
+            // rejected candidates are wiped before they are overwritten
             if p == q {
+                p.zeroize();
+                q.zeroize();
                 continue;
             }
 
             n = &p * &q;
-            let totient = (&p - BigUint::one()) * (&q - BigUint::one());
+            let mut totient = (&p - BigUint::one()) * (&q - BigUint::one());
 
             // `mod_inverse` returns `None` if gcd(e, totient) != 1
             // `e` must not be a factor of (p - 1) or (q - 1)
-            if let Some(einv) = (&e).mod_inverse(totient) {
+            let einv = (&e).mod_inverse(&totient);
+            totient.zeroize();
+            if let Some(mut einv) = einv {
                 d = einv.to_biguint().unwrap();
+                einv.zeroize();
                 break;
             }
+            p.zeroize();
+            q.zeroize();
         }
 
         Ok(PrivateKeyComponents { n, p, q, d })
@@ -192,4 +245,35 @@ mod test {
 
         assert_eq!(decryped_msg, plaintext);
     }
+
+    #[test]
+    fn private_key_is_redacted_and_wiped() {
+        let mut private_key = PrivateKey::new(1 << 9).expect("Error generating skey");
+        let c = private_key
+            .get_public_key()
+            .unwrap()
+            .encrypt(b"secret")
+            .unwrap();
+
+        let debug = format!("{:?}", private_key);
+        assert!(debug.starts_with("PrivateKey { public_key: "));
+        assert!(debug.ends_with(", .. }"));
+        for secret in [
+            &private_key.p,
+            &private_key.q,
+            &private_key.d_mod_p_minus_one,
+        ] {
+            assert!(!debug.contains(&format!("{:?}", secret)));
+        }
+
+        let copy = private_key.clone_secret();
+        private_key.zeroize();
+        // zeroize clears the digits but keeps their number
+        let wiped = |bytes: Vec<u8>| bytes.iter().all(|b| *b == 0);
+        assert!(wiped(private_key.p.to_bytes_be()));
+        assert!(wiped(private_key.q.to_bytes_be()));
+        assert!(wiped(private_key.d_mod_p_minus_one.to_bytes_be()));
+        assert!(wiped(private_key.q_inv_mod_p_minus_one.to_bytes_be().1));
+        assert_eq!(copy.decrypt(&c).unwrap(), b"secret");
+    }
 }
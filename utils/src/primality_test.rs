@@ -1,3 +1,4 @@
+use alloc::string::ToString;
 use num_bigint::BigUint;
 
 // TODO: carry out to a separate crate `utils`